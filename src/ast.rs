@@ -0,0 +1,618 @@
+use crate::lexer::Span;
+use std::fmt;
+
+/// How a run of points is to be read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectKind {
+    /// Bare points: a point, segment or polygon depending on how many.
+    Figure,
+    /// `wAB`
+    Ray,
+    /// `lAB`
+    Line,
+    /// `cO`
+    Circle,
+    /// `aAB`
+    Arc,
+    /// `qOAB`
+    Sector,
+    /// `△ABC`
+    Triangle,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Object {
+    pub kind: ObjectKind,
+    pub points: Vec<String>,
+}
+
+impl Object {
+    /// The point names run together, e.g. `ABC`.
+    pub fn name(&self) -> String {
+        self.points.concat()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithOp {
+    Add,
+    Sub,
+    Div,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(String),
+    /// Lowercase unknown such as `x` or `r`.
+    Variable(String),
+    /// Named constant, stored in its ASCII spelling (`\P`, `\T`, `\G`).
+    Constant(String),
+    /// A length, arc, sector or other object used as a value.
+    Object(Object),
+    /// `[ABC]`
+    Area(Object),
+    /// `(ABC)`
+    Perimeter(Object),
+    /// `<ABC` or `∠ABC`
+    Angle(Object),
+    Binary(Box<Expr>, ArithOp, Box<Expr>),
+    /// A coefficient written against what it multiplies, as in `2x`, `3\P`
+    /// or `2CD`.
+    Product(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// What an expression measures: `angle`, `area`, `perimeter`, `arc`,
+    /// `sector`, `length` or, for numbers and arithmetic, `value`.
+    pub fn measure(&self) -> &'static str {
+        match self {
+            Expr::Angle(_) => "angle",
+            Expr::Area(_) => "area",
+            Expr::Perimeter(_) => "perimeter",
+            Expr::Object(obj) => match obj.kind {
+                ObjectKind::Arc => "arc",
+                ObjectKind::Sector => "sector",
+                _ => "length",
+            },
+            Expr::Product(_, factor) => factor.measure(),
+            _ => "value",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CmpOp {
+    Eq,
+    NotEq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogicOp {
+    And,
+    Or,
+    Implies,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantifier {
+    ForAll,
+    Exists,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofMarker {
+    /// `\q` / `□`
+    Qed,
+    /// `\qC` / `↯`
+    Contradiction,
+    /// `\bc` / `∵`
+    Because,
+    /// `\th` / `∴`
+    Therefore,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinearKind {
+    Segment,
+    Line,
+    Ray,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PointDecl {
+    /// `P:A` or `P:A,B,C`
+    Free(Vec<String>),
+    /// `P:A{1,2}`
+    Coordinates { name: String, coords: String },
+    /// `P:C.AB` or `P:D.AC|R:3;AD=ADE,[ADE]=20`
+    OnObject {
+        name: String,
+        object: Object,
+        conditions: Vec<Statement>,
+    },
+    /// `P:C..ABC`
+    InRegion { name: String, region: Object },
+    /// `P:X=ABxCD`
+    Intersection { name: String, objects: Vec<Object> },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CircleDecl {
+    /// `C:A;B;C`
+    ThroughPoints(Vec<String>),
+    /// `C:O;5` or `C:O;r`
+    CenterRadius { center: String, radius: Expr },
+    /// `C:O;A`
+    CenterThrough { center: String, point: String },
+    /// `C:A`
+    Through(String),
+    /// `C:=ω`
+    Named(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegularPolygon {
+    pub sides: String,
+    pub side: Object,
+    pub polygon: Object,
+}
+
+/// A `\def` line, which gives a file its own shorthand.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Definition {
+    /// `\def FOOT:AHX{P:X.BC|AX;BC*PD}`: a name for a sequence of
+    /// statements, whose parameters are replaced by the points it is called
+    /// with, as in `FOOT:DEF`.
+    Macro {
+        name: String,
+        params: Vec<String>,
+        body: Vec<Statement>,
+    },
+    /// `\def NAG:{Nagel point}`: a derived construction code.
+    Construction { code: String, name: String },
+    /// `\def *EQL{equilateral}`: a property code.
+    Property { code: String, name: String },
+    /// `\def _MEN{Menelaus' Theorem}`: a theorem code.
+    Theorem { code: String, name: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Case {
+    pub condition: Statement,
+    pub result: Statement,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StmtKind {
+    /// `\p:...`
+    Prove(Box<Statement>),
+    /// `\pC:...`
+    ProveByContradiction(Box<Statement>),
+    Marker(ProofMarker),
+    Logic(LogicOp, Box<Statement>, Box<Statement>),
+    Quantified(Quantifier, Box<Statement>),
+    /// `MAIN<<(a;b),(c;d)>>`, or `MAIN<<` opening casework that a later `>>` ends.
+    /// `MAIN` may be a statement or, as in `AB<<...>>`, a value.
+    Casework {
+        main: Option<Box<Statement>>,
+        cases: Option<Vec<Case>>,
+    },
+    EndCasework,
+    /// A value on its own, which only stands as the subject of casework.
+    Value(Expr),
+    /// `CCO:ABC`, optionally naming the result with `=O`.
+    Derived {
+        code: String,
+        args: Vec<Object>,
+        target: Option<String>,
+    },
+    /// `G:{y=x^2}`
+    Graph(String),
+    Point(PointDecl),
+    /// `S:`, `L:` and `W:`
+    Linear(LinearKind, Vec<Object>),
+    /// `J:ABC`, or `J:DFH*R` for a polygon with properties.
+    Polygon {
+        objects: Vec<Object>,
+        properties: Vec<String>,
+    },
+    Circle(CircleDecl),
+    RegularPolygon(RegularPolygon),
+    /// `_PY` on its own.
+    Citation(String),
+    Relation(Expr, CmpOp, Expr),
+    /// `AB?`, `[ABC]?`
+    Query(Expr),
+    /// `AB=BC\?`
+    ProveQuery(Box<Statement>),
+    /// `ABC*EQ`, `ABC*EQ?`
+    Property {
+        object: Object,
+        property: String,
+        question: bool,
+    },
+    /// `AB;CD*P`, `AB∥CD`, `AB;CD*P?`
+    Relationship {
+        objects: Vec<Object>,
+        relation: String,
+        infix: bool,
+        question: bool,
+    },
+    /// `ABC*+` / `ABC*-`
+    Orientation {
+        object: Object,
+        clockwise: bool,
+    },
+    /// `\def ...`
+    Define(Definition),
+    /// `\include "file.gsl"`
+    Include(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statement {
+    pub kind: StmtKind,
+    /// Where the statement appears in the source.
+    pub span: Span,
+    /// The theorem cited to justify the statement, from a trailing code such
+    /// as the `_PY` of `AC=5_PY`.
+    pub theorem: Option<String>,
+    /// Where the theorem code appears, when there is one.
+    pub theorem_span: Span,
+}
+
+impl Statement {
+    pub fn new(kind: StmtKind, span: Span) -> Self {
+        Statement {
+            kind,
+            span,
+            theorem: None,
+            theorem_span: Span::default(),
+        }
+    }
+
+    /// A short snake_case name for what kind of statement this is, such as
+    /// `point_construction`, `area_query` or `angle_assertion`.
+    pub fn kind_name(&self) -> &'static str {
+        match &self.kind {
+            StmtKind::Prove(_) => "prove",
+            StmtKind::ProveByContradiction(_) => "prove_by_contradiction",
+            StmtKind::Marker(_) => "proof_marker",
+            StmtKind::Logic(..) => "logic",
+            StmtKind::Quantified(..) => "quantified",
+            StmtKind::Casework { .. } => "casework",
+            StmtKind::EndCasework => "casework_end",
+            StmtKind::Value(_) => "value",
+            StmtKind::Derived { .. } => "derived_construction",
+            StmtKind::Graph(_) => "graph",
+            StmtKind::Point(_) => "point_construction",
+            StmtKind::Linear(LinearKind::Segment, _) => "segment_construction",
+            StmtKind::Linear(LinearKind::Line, _) => "line_construction",
+            StmtKind::Linear(LinearKind::Ray, _) => "ray_construction",
+            StmtKind::Polygon { .. } => "polygon_construction",
+            StmtKind::Circle(_) => "circle_construction",
+            StmtKind::RegularPolygon(_) => "regular_polygon_construction",
+            StmtKind::Citation(_) => "theorem_citation",
+            StmtKind::Relation(lhs, ..) => match lhs.measure() {
+                "angle" => "angle_assertion",
+                "area" => "area_assertion",
+                "perimeter" => "perimeter_assertion",
+                "arc" => "arc_assertion",
+                "sector" => "sector_assertion",
+                "length" => "length_assertion",
+                _ => "value_assertion",
+            },
+            StmtKind::Query(expr) => match expr.measure() {
+                "angle" => "angle_query",
+                "area" => "area_query",
+                "perimeter" => "perimeter_query",
+                "arc" => "arc_query",
+                "sector" => "sector_query",
+                "length" => "length_query",
+                _ => "value_query",
+            },
+            StmtKind::ProveQuery(_) => "prove_query",
+            StmtKind::Property {
+                question: false, ..
+            } => "property",
+            StmtKind::Property { question: true, .. } => "property_query",
+            StmtKind::Relationship {
+                question: false, ..
+            } => "relationship",
+            StmtKind::Relationship { question: true, .. } => "relationship_query",
+            StmtKind::Orientation { .. } => "orientation",
+            StmtKind::Define(Definition::Macro { .. }) => "macro_definition",
+            StmtKind::Define(Definition::Construction { .. }) => "construction_definition",
+            StmtKind::Define(Definition::Property { .. }) => "property_definition",
+            StmtKind::Define(Definition::Theorem { .. }) => "theorem_definition",
+            StmtKind::Include(_) => "include",
+        }
+    }
+
+    /// Call `f` on this statement and every statement nested inside it.
+    pub fn walk<'a>(&'a self, f: &mut impl FnMut(&'a Statement)) {
+        f(self);
+        match &self.kind {
+            StmtKind::Prove(inner)
+            | StmtKind::ProveByContradiction(inner)
+            | StmtKind::Quantified(_, inner)
+            | StmtKind::ProveQuery(inner) => inner.walk(f),
+            StmtKind::Logic(_, lhs, rhs) => {
+                lhs.walk(f);
+                rhs.walk(f);
+            }
+            StmtKind::Casework { main, cases } => {
+                if let Some(main) = main {
+                    main.walk(f);
+                }
+                for case in cases.iter().flatten() {
+                    case.condition.walk(f);
+                    case.result.walk(f);
+                }
+            }
+            StmtKind::Point(PointDecl::OnObject { conditions, .. }) => {
+                for condition in conditions {
+                    condition.walk(f);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// Display renders canonical GSL shorthand.
+
+impl fmt::Display for Object {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let prefix = match self.kind {
+            ObjectKind::Figure => "",
+            ObjectKind::Ray => "w",
+            ObjectKind::Line => "l",
+            ObjectKind::Circle => "c",
+            ObjectKind::Arc => "a",
+            ObjectKind::Sector => "q",
+            ObjectKind::Triangle => "△",
+        };
+        write!(f, "{}{}", prefix, self.name())
+    }
+}
+
+impl fmt::Display for ArithOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ArithOp::Add => "+",
+            ArithOp::Sub => "-",
+            ArithOp::Div => "/",
+        })
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Number(n) => f.write_str(n),
+            Expr::Variable(v) => f.write_str(v),
+            Expr::Constant(c) => f.write_str(c),
+            Expr::Object(o) => write!(f, "{}", o),
+            Expr::Area(o) => write!(f, "[{}]", o),
+            Expr::Perimeter(o) => write!(f, "({})", o),
+            Expr::Angle(o) => write!(f, "<{}", o),
+            Expr::Binary(l, op, r) => write!(f, "{}{}{}", l, op, r),
+            Expr::Product(l, r) => write!(f, "{}{}", l, r),
+        }
+    }
+}
+
+impl fmt::Display for CmpOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            CmpOp::Eq => "=",
+            CmpOp::NotEq => "!=",
+            CmpOp::Lt => "<",
+            CmpOp::Le => "<=",
+            CmpOp::Gt => ">",
+            CmpOp::Ge => ">=",
+        })
+    }
+}
+
+impl fmt::Display for LogicOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            LogicOp::And => "&&",
+            LogicOp::Or => "||",
+            LogicOp::Implies => "=>",
+        })
+    }
+}
+
+fn write_list<T: fmt::Display>(f: &mut fmt::Formatter, items: &[T], sep: &str) -> fmt::Result {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            f.write_str(sep)?;
+        }
+        write!(f, "{}", item)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for PointDecl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PointDecl::Free(names) => write_list(f, names, ","),
+            PointDecl::Coordinates { name, coords } => write!(f, "{}{{{}}}", name, coords),
+            PointDecl::OnObject {
+                name,
+                object,
+                conditions,
+            } => {
+                write!(f, "{}.{}", name, object)?;
+                if !conditions.is_empty() {
+                    f.write_str("|")?;
+                    write_list(f, conditions, ",")?;
+                }
+                Ok(())
+            }
+            PointDecl::InRegion { name, region } => write!(f, "{}..{}", name, region),
+            PointDecl::Intersection { name, objects } => {
+                write!(f, "{}=", name)?;
+                write_list(f, objects, "x")
+            }
+        }
+    }
+}
+
+impl fmt::Display for CircleDecl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CircleDecl::ThroughPoints(points) => write_list(f, points, ";"),
+            CircleDecl::CenterRadius { center, radius } => write!(f, "{};{}", center, radius),
+            CircleDecl::CenterThrough { center, point } => write!(f, "{};{}", center, point),
+            CircleDecl::Through(point) => f.write_str(point),
+            CircleDecl::Named(name) => write!(f, "={}", name),
+        }
+    }
+}
+
+impl fmt::Display for RegularPolygon {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{};{}={}", self.sides, self.side, self.polygon)
+    }
+}
+
+impl fmt::Display for Case {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({};{})", self.condition, self.result)
+    }
+}
+
+impl fmt::Display for Definition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Definition::Macro { name, params, body } => {
+                write!(f, "\\def {}:{}{{", name, params.concat())?;
+                write_list(f, body, "/")?;
+                f.write_str("}")
+            }
+            Definition::Construction { code, name } => write!(f, "\\def {}:{{{}}}", code, name),
+            Definition::Property { code, name } => write!(f, "\\def *{}{{{}}}", code, name),
+            Definition::Theorem { code, name } => write!(f, "\\def {}{{{}}}", code, name),
+        }
+    }
+}
+
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.kind.fmt(f)?;
+        match &self.theorem {
+            Some(code) => f.write_str(code),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for StmtKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StmtKind::Prove(inner) => write!(f, "\\p:{}", inner),
+            StmtKind::ProveByContradiction(inner) => write!(f, "\\pC:{}", inner),
+            StmtKind::Marker(marker) => f.write_str(match marker {
+                ProofMarker::Qed => "\\q",
+                ProofMarker::Contradiction => "\\qC",
+                ProofMarker::Because => "\\bc",
+                ProofMarker::Therefore => "\\th",
+            }),
+            StmtKind::Logic(op, l, r) => write!(f, "{}{}{}", l, op, r),
+            StmtKind::Quantified(q, body) => match q {
+                Quantifier::ForAll => write!(f, "|A{}", body),
+                Quantifier::Exists => write!(f, "|E{}", body),
+            },
+            StmtKind::Casework { main, cases } => {
+                if let Some(main) = main {
+                    write!(f, "{}", main)?;
+                }
+                f.write_str("<<")?;
+                if let Some(cases) = cases {
+                    write_list(f, cases, ",")?;
+                    f.write_str(">>")?;
+                }
+                Ok(())
+            }
+            StmtKind::EndCasework => f.write_str(">>"),
+            StmtKind::Value(expr) => write!(f, "{}", expr),
+            StmtKind::Derived { code, args, target } => {
+                write!(f, "{}:", code)?;
+                write_list(f, args, ";")?;
+                if let Some(target) = target {
+                    write!(f, "={}", target)?;
+                }
+                Ok(())
+            }
+            StmtKind::Graph(eq) => write!(f, "G:{{{}}}", eq),
+            StmtKind::Point(decl) => write!(f, "P:{}", decl),
+            StmtKind::Linear(kind, objects) => {
+                let prefix = match kind {
+                    LinearKind::Segment => "S",
+                    LinearKind::Line => "L",
+                    LinearKind::Ray => "W",
+                };
+                write!(f, "{}:", prefix)?;
+                write_list(f, objects, ",")
+            }
+            StmtKind::Polygon {
+                objects,
+                properties,
+            } => {
+                f.write_str("J:")?;
+                write_list(f, objects, ",")?;
+                for property in properties {
+                    write!(f, "*{}", property)?;
+                }
+                Ok(())
+            }
+            StmtKind::Circle(decl) => write!(f, "C:{}", decl),
+            StmtKind::RegularPolygon(poly) => write!(f, "R:{}", poly),
+            StmtKind::Citation(code) => f.write_str(code),
+            StmtKind::Relation(l, op, r) => write!(f, "{}{}{}", l, op, r),
+            StmtKind::Query(expr) => write!(f, "{}?", expr),
+            StmtKind::ProveQuery(inner) => write!(f, "{}\\?", inner),
+            StmtKind::Property {
+                object,
+                property,
+                question,
+            } => write!(
+                f,
+                "{}*{}{}",
+                object,
+                property,
+                if *question { "?" } else { "" }
+            ),
+            StmtKind::Relationship {
+                objects,
+                relation,
+                infix,
+                question,
+            } => {
+                if *infix {
+                    write_list(f, objects, relation)?;
+                } else {
+                    write_list(f, objects, ";")?;
+                    write!(f, "*{}", relation)?;
+                }
+                if *question {
+                    f.write_str("?")?;
+                }
+                Ok(())
+            }
+            StmtKind::Orientation { object, clockwise } => {
+                write!(f, "{}*{}", object, if *clockwise { "+" } else { "-" })
+            }
+            StmtKind::Define(definition) => write!(f, "{}", definition),
+            StmtKind::Include(path) => write!(f, "\\include \"{}\"", path),
+        }
+    }
+}
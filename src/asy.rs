@@ -0,0 +1,441 @@
+//! Asymptote figures in the style of olympiad.asy. Constructions from the
+//! derived-construction table are written as the olympiad.asy calls they
+//! correspond to (`circumcenter`, `incircle`, `foot`, `extension`, …); points
+//! the problem only pins down numerically are declared at the coordinates the
+//! evaluator solved.
+
+use crate::ast::*;
+use crate::eval::{format_number, Figure, Scene};
+use crate::geometry::{self, Point};
+use crate::latex;
+use crate::svg::{collect_marks, Mark};
+use crate::translator::Translation;
+use std::collections::HashSet;
+use std::fmt::Write;
+
+/// olympiad.asy is not part of a stock Asymptote install, so the helpers the
+/// output uses are defined here with the same names and signatures.
+const PRELUDE: &str = "import math;
+
+// olympiad.asy is not part of a stock Asymptote install, so the helpers this
+// figure uses are defined here with the same names and signatures. Where
+// olympiad.asy is installed, `import olympiad;` can replace them.
+real markscalefactor = 0.03;
+pair foot(pair P, pair A, pair B) {
+  return A + dot(P - A, B - A) / dot(B - A, B - A) * (B - A);
+}
+pair circumcenter(pair A, pair B, pair C) {
+  pair P = (A + B) / 2, Q = (B + C) / 2;
+  return extension(P, P + rotate(90) * (B - A), Q, Q + rotate(90) * (C - B));
+}
+real circumradius(pair A, pair B, pair C) {
+  return abs(A - circumcenter(A, B, C));
+}
+path circumcircle(pair A, pair B, pair C) {
+  return circle(circumcenter(A, B, C), circumradius(A, B, C));
+}
+pair incenter(pair A, pair B, pair C) {
+  real a = abs(B - C), b = abs(C - A), c = abs(A - B);
+  return (a * A + b * B + c * C) / (a + b + c);
+}
+real inradius(pair A, pair B, pair C) {
+  pair I = incenter(A, B, C);
+  return abs(I - foot(I, A, B));
+}
+path incircle(pair A, pair B, pair C) {
+  return circle(incenter(A, B, C), inradius(A, B, C));
+}
+pair excenter(pair A, pair B, pair C) {
+  real a = abs(B - C), b = abs(C - A), c = abs(A - B);
+  return (-a * A + b * B + c * C) / (-a + b + c);
+}
+real exradius(pair A, pair B, pair C) {
+  pair E = excenter(A, B, C);
+  return abs(E - foot(E, B, C));
+}
+path excircle(pair A, pair B, pair C) {
+  return circle(excenter(A, B, C), exradius(A, B, C));
+}
+pair orthocenter(pair A, pair B, pair C) {
+  return extension(A, foot(A, B, C), B, foot(B, C, A));
+}
+pair centroid(pair A, pair B, pair C) {
+  return (A + B + C) / 3;
+}
+path rightanglemark(pair A, pair B, pair C, real s = 8) {
+  pair P = s * markscalefactor * unit(A - B) + B;
+  pair R = s * markscalefactor * unit(C - B) + B;
+  return P--(P + R - B)--R;
+}
+path anglemark(pair A, pair B, pair C, real t = 8) {
+  real a = degrees(A - B), c = degrees(C - B);
+  if (c < a) c += 360;
+  if (c - a > 180) {
+    real s = c - 360;
+    c = a;
+    a = s;
+  }
+  return arc(B, t * markscalefactor, a, c);
+}
+
+";
+
+/// An Asymptote file drawing every object the translation constructs.
+pub fn translation(translation: &Translation) -> String {
+    let statements: Vec<&Statement> = translation.steps.iter().map(|s| &s.statement).collect();
+    let scene = Scene::evaluate(statements.iter().copied());
+    render(&statements, &scene)
+}
+
+/// Draw `scene` by replaying the statements' constructions, then mark the
+/// angles they mention and label every point.
+pub fn render(statements: &[&Statement], scene: &Scene) -> String {
+    let mut asy = Asy {
+        scene,
+        out: String::from(PRELUDE),
+        declared: HashSet::new(),
+    };
+    asy.line("size(8cm);".to_string());
+    let all: Vec<Point> = scene.points().map(|(_, p)| p).collect();
+    if !all.is_empty() {
+        // Keep marks the same size relative to the figure.
+        let span = all
+            .iter()
+            .flat_map(|p| all.iter().map(move |q| p.dist(*q)))
+            .fold(1.0, f64::max);
+        asy.line(format!(
+            "markscalefactor = {};",
+            format_number(span * 0.005)
+        ));
+    }
+    for stmt in statements {
+        asy.statement(stmt);
+    }
+
+    let mut marks = Vec::new();
+    for stmt in statements {
+        stmt.walk(&mut |s| collect_marks(s, &mut marks));
+    }
+    for mark in &marks {
+        if let Mark::Angle(obj) = mark {
+            asy.angle(obj);
+        }
+    }
+
+    let center = if all.is_empty() {
+        Point::new(0.0, 0.0)
+    } else {
+        geometry::centroid(&all)
+    };
+    let names: Vec<(String, Point)> = scene.points().map(|(n, p)| (n.to_string(), p)).collect();
+    for (name, p) in names {
+        asy.declare(&name);
+        let away = p - center;
+        let angle = if away.norm() < geometry::EPSILON {
+            45.0
+        } else {
+            away.y.atan2(away.x).to_degrees()
+        };
+        asy.line(format!("dot({});", ident(&name)));
+        asy.line(format!(
+            "label(\"${}$\", {}, dir({}));",
+            latex::point(&name),
+            ident(&name),
+            format_number(angle.round())
+        ));
+    }
+    asy.out
+}
+
+/// Asymptote identifiers cannot contain primes.
+fn ident(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '\'' => "p".to_string(),
+            c if c.is_ascii_alphanumeric() => c.to_string(),
+            _ => "_".to_string(),
+        })
+        .collect()
+}
+
+fn pair(p: Point) -> String {
+    format!("({}, {})", format_number(p.x), format_number(p.y))
+}
+
+struct Asy<'a> {
+    scene: &'a Scene,
+    out: String,
+    declared: HashSet<String>,
+}
+
+impl Asy<'_> {
+    fn line(&mut self, line: String) {
+        writeln!(self.out, "{}", line).unwrap();
+    }
+
+    fn comment(&mut self, stmt: &Statement) {
+        self.line(format!("// {}", stmt));
+    }
+
+    /// Declare `name` at its solved coordinates unless it already is.
+    fn declare(&mut self, name: &str) {
+        if let Some(p) = self.scene.point(name) {
+            self.define(name, pair(p));
+        }
+    }
+
+    fn define(&mut self, name: &str, value: String) {
+        if self.declared.insert(name.to_string()) {
+            self.line(format!("pair {} = {};", ident(name), value));
+        }
+    }
+
+    /// Identifiers for `points`, declaring any the file has not seen yet.
+    fn idents(&mut self, points: &[String]) -> Vec<String> {
+        for p in points {
+            self.declare(p);
+        }
+        points.iter().map(|p| ident(p)).collect()
+    }
+
+    fn statement(&mut self, stmt: &Statement) {
+        match &stmt.kind {
+            StmtKind::Point(decl) => self.point(decl),
+            StmtKind::Linear(kind, objects) => {
+                for obj in objects {
+                    let ids = self.idents(&obj.points);
+                    for pair in ids.windows(2) {
+                        let (a, b) = (&pair[0], &pair[1]);
+                        self.line(match kind {
+                            LinearKind::Segment => format!("draw({}--{});", a, b),
+                            LinearKind::Line => format!("drawline({}, {});", a, b),
+                            LinearKind::Ray => format!("draw({a}--interp({a}, {b}, 3));"),
+                        });
+                    }
+                }
+            }
+            StmtKind::Polygon { objects, .. } => {
+                for obj in objects {
+                    let ids = self.idents(&obj.points);
+                    self.line(format!("draw({}--cycle);", ids.join("--")));
+                }
+            }
+            StmtKind::RegularPolygon(poly) => {
+                let ids = self.idents(&poly.polygon.points);
+                self.line(format!("draw({}--cycle);", ids.join("--")));
+            }
+            StmtKind::Circle(decl) => self.circle(stmt, decl),
+            StmtKind::Derived { code, args, target } => {
+                if !self.derived(stmt, code, args, target.as_deref()) {
+                    self.comment(stmt);
+                }
+            }
+            _ => self.comment(stmt),
+        }
+    }
+
+    fn point(&mut self, decl: &PointDecl) {
+        match decl {
+            PointDecl::Free(names) => {
+                for name in names {
+                    self.declare(name);
+                }
+            }
+            PointDecl::Intersection { name, objects } => {
+                let straight = |o: &Object| {
+                    o.kind != ObjectKind::Circle && o.kind != ObjectKind::Arc && o.points.len() == 2
+                };
+                match &objects[..] {
+                    [l, m] if straight(l) && straight(m) => {
+                        let ids = self.idents(&[l.points.clone(), m.points.clone()].concat());
+                        self.define(name, format!("extension({})", ids.join(", ")));
+                    }
+                    _ => self.declare(name),
+                }
+            }
+            PointDecl::Coordinates { name, .. }
+            | PointDecl::OnObject { name, .. }
+            | PointDecl::InRegion { name, .. } => self.declare(name),
+        }
+        if let PointDecl::OnObject { conditions, .. } = decl {
+            for condition in conditions {
+                self.statement(condition);
+            }
+        }
+    }
+
+    fn circle(&mut self, stmt: &Statement, decl: &CircleDecl) {
+        match decl {
+            CircleDecl::ThroughPoints(points) if points.len() == 3 => {
+                let ids = self.idents(points);
+                self.line(format!("draw(circumcircle({}));", ids.join(", ")));
+            }
+            CircleDecl::CenterRadius {
+                center,
+                radius: Expr::Number(r),
+            } => {
+                let o = self.idents(std::slice::from_ref(center)).remove(0);
+                self.line(format!("draw(circle({}, {}));", o, r));
+            }
+            CircleDecl::CenterThrough { center, point } => {
+                let ids = self.idents(&[center.clone(), point.clone()]);
+                self.line(format!(
+                    "draw(circle({o}, abs({p} - {o})));",
+                    o = ids[0],
+                    p = ids[1]
+                ));
+            }
+            CircleDecl::ThroughPoints(_) => self.comment(stmt),
+            CircleDecl::CenterRadius { center: key, .. }
+            | CircleDecl::Through(key)
+            | CircleDecl::Named(key) => {
+                // Fall back to the circle the evaluator settled on.
+                match self.scene.circle_by_key(key) {
+                    Some(c) => self.line(format!(
+                        "draw(circle({}, {})); // {}",
+                        pair(c.center),
+                        format_number(c.radius),
+                        stmt
+                    )),
+                    None => self.comment(stmt),
+                }
+            }
+        }
+    }
+
+    /// Returns false for constructions with no Asymptote counterpart.
+    fn derived(
+        &mut self,
+        stmt: &Statement,
+        code: &str,
+        objects: &[Object],
+        target: Option<&str>,
+    ) -> bool {
+        let Some(first) = objects.first() else {
+            return false;
+        };
+        let ids = self.idents(&first.points);
+        let point = |this: &mut Self, value: String| match target {
+            Some(target) => this.define(target, value),
+            None => this.line(format!("dot({});", value)),
+        };
+        match (code, &ids[..]) {
+            ("M", [a, b]) => point(self, format!("({} + {}) / 2", a, b)),
+            ("CCO" | "ICO" | "ECO" | "OC" | "CT", [a, b, c]) => {
+                let function = match code {
+                    "CCO" => "circumcenter",
+                    "ICO" => "incenter",
+                    "ECO" => "excenter",
+                    "OC" => "orthocenter",
+                    _ => "centroid",
+                };
+                point(self, format!("{}({}, {}, {})", function, a, b, c));
+            }
+            ("9O", [a, b, c]) => point(
+                self,
+                format!("(circumcenter({a}, {b}, {c}) + orthocenter({a}, {b}, {c})) / 2"),
+            ),
+            ("CC" | "IC" | "EC" | "9C", [a, b, c]) => {
+                let path = match code {
+                    "CC" => format!("circumcircle({a}, {b}, {c})"),
+                    "IC" => format!("incircle({a}, {b}, {c})"),
+                    "EC" => format!("excircle({a}, {b}, {c})"),
+                    _ => format!("circumcircle(({a} + {b}) / 2, ({b} + {c}) / 2, ({c} + {a}) / 2)"),
+                };
+                self.line(format!("draw({});", path));
+            }
+            ("MD", [a, b, c]) => {
+                let mid = format!("({} + {}) / 2", b, c);
+                match target {
+                    Some(target) => {
+                        self.define(target, mid);
+                        self.line(format!("draw({}--{});", a, ident(target)));
+                    }
+                    None => self.line(format!("draw({}--{});", a, mid)),
+                }
+            }
+            ("PB", [a, b]) => {
+                let mid = format!("({} + {}) / 2", a, b);
+                self.line(format!(
+                    "drawline({mid}, {mid} + rotate(90) * ({b} - {a}), dashed);"
+                ));
+            }
+            ("AB", [a, b, c]) => {
+                let direction = format!("{b} + unit({a} - {b}) + unit({c} - {b})");
+                match target {
+                    Some(target) => {
+                        self.define(target, format!("extension({b}, {direction}, {a}, {c})"));
+                        self.line(format!("draw({}--{});", b, ident(target)));
+                    }
+                    None => self.line(format!("drawline({b}, {direction}, dashed);")),
+                }
+            }
+            ("EAB", [a, b, c]) => self.line(format!(
+                "drawline({b}, {b} + rotate(90) * (unit({a} - {b}) + unit({c} - {b})), dashed);"
+            )),
+            ("PD" | "PL", _) => {
+                let (from, a, b) = match (&ids[..], objects.get(1)) {
+                    ([p], Some(line)) => {
+                        let ends = self.idents(&line.points);
+                        let [a, b] = &ends[..] else {
+                            return false;
+                        };
+                        (p.clone(), a.clone(), b.clone())
+                    }
+                    ([p, a, b], None) => (p.clone(), a.clone(), b.clone()),
+                    _ => return false,
+                };
+                if code == "PL" {
+                    self.line(format!("drawline({from}, {from} + {b} - {a}, dashed);"));
+                    return true;
+                }
+                let foot = format!("foot({}, {}, {})", from, a, b);
+                match target {
+                    Some(target) => {
+                        self.define(target, foot);
+                        self.line(format!("draw({}--{}, dashed);", from, ident(target)));
+                    }
+                    None => self.line(format!("draw({}--{}, dashed);", from, foot)),
+                }
+            }
+            _ => {
+                // Anything else (tangents) is drawn where the evaluator put it.
+                let label = stmt.to_string();
+                let line = self.scene.figures().iter().find_map(|f| match f {
+                    Figure::DerivedLine { label: l, line } if *l == label => Some(*line),
+                    _ => None,
+                });
+                let Some(line) = line else {
+                    return false;
+                };
+                self.line(format!(
+                    "drawline({}, {}, dashed); // {}",
+                    pair(line.a),
+                    pair(line.b),
+                    label
+                ));
+                if let Some(target) = target {
+                    self.declare(target);
+                }
+            }
+        }
+        true
+    }
+
+    fn angle(&mut self, obj: &Object) {
+        let points: Option<Vec<Point>> = obj.points.iter().map(|n| self.scene.point(n)).collect();
+        let Some([a, b, c]) = points.as_deref() else {
+            return;
+        };
+        if a.dist(*b) < geometry::EPSILON || c.dist(*b) < geometry::EPSILON {
+            return;
+        }
+        let ids = self.idents(&obj.points);
+        let mark = if (geometry::angle(*a, *b, *c) - 90.0).abs() < 1e-6 {
+            "rightanglemark"
+        } else {
+            "anglemark"
+        };
+        self.line(format!("draw({}({}));", mark, ids.join(", ")));
+    }
+}
@@ -0,0 +1,502 @@
+use crate::ast::*;
+use crate::diagnostic::Diagnostic;
+use crate::lexer::Span;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    Point,
+    Segment,
+    Line,
+    Ray,
+    Circle,
+    Polygon,
+}
+
+impl SymbolKind {
+    pub fn noun(self) -> &'static str {
+        match self {
+            SymbolKind::Point => "point",
+            SymbolKind::Segment => "segment",
+            SymbolKind::Line => "line",
+            SymbolKind::Ray => "ray",
+            SymbolKind::Circle => "circle",
+            SymbolKind::Polygon => "polygon",
+        }
+    }
+}
+
+/// Everything constructed so far, keyed by kind and canonical name.
+#[derive(Debug, Default)]
+pub struct SymbolTable {
+    symbols: HashMap<(SymbolKind, String), Span>,
+    /// Sides of constructed polygons, which count as segments.
+    edges: HashMap<String, Span>,
+}
+
+/// Name under which an object is stored, so that `BA` finds segment `AB`
+/// and `CAB` finds polygon `ABC`.
+fn canonical(kind: SymbolKind, points: &[String]) -> String {
+    match kind {
+        SymbolKind::Segment | SymbolKind::Line => {
+            let mut sorted = points.to_vec();
+            sorted.sort();
+            sorted.concat()
+        }
+        SymbolKind::Polygon => {
+            let n = points.len();
+            let mut best: Option<String> = None;
+            for start in 0..n {
+                for reversed in [false, true] {
+                    let name: String = (0..n)
+                        .map(|i| {
+                            let idx = if reversed {
+                                (start + n - i) % n
+                            } else {
+                                (start + i) % n
+                            };
+                            points[idx].as_str()
+                        })
+                        .collect();
+                    if best.as_ref().is_none_or(|b| name < *b) {
+                        best = Some(name);
+                    }
+                }
+            }
+            best.unwrap_or_default()
+        }
+        _ => points.concat(),
+    }
+}
+
+fn edges(points: &[String]) -> impl Iterator<Item = [String; 2]> + '_ {
+    (0..points.len()).map(move |i| [points[i].clone(), points[(i + 1) % points.len()].clone()])
+}
+
+impl SymbolTable {
+    pub fn lookup(&self, kind: SymbolKind, points: &[String]) -> Option<Span> {
+        let key = (kind, canonical(kind, points));
+        if let Some(span) = self.symbols.get(&key) {
+            return Some(*span);
+        }
+        if kind == SymbolKind::Segment {
+            return self.edges.get(&key.1).copied();
+        }
+        None
+    }
+
+    /// Every constructed object as `(kind, canonical name, span)`, in
+    /// source order.
+    pub fn iter(&self) -> impl Iterator<Item = (SymbolKind, &str, Span)> {
+        let mut all: Vec<_> = self
+            .symbols
+            .iter()
+            .map(|((kind, name), span)| (*kind, name.as_str(), *span))
+            .collect();
+        all.sort_by_key(|&(_, name, span)| (span.start, name));
+        all.into_iter()
+    }
+
+    fn insert(&mut self, kind: SymbolKind, points: &[String], span: Span) -> Option<Span> {
+        if kind == SymbolKind::Polygon && points.len() > 1 {
+            for edge in edges(points) {
+                self.edges
+                    .entry(canonical(SymbolKind::Segment, &edge))
+                    .or_insert(span);
+            }
+        }
+        self.symbols.insert((kind, canonical(kind, points)), span)
+    }
+}
+
+/// Derived constructions whose first argument must have a fixed number of points.
+fn derived_arity(code: &str) -> Option<usize> {
+    match code {
+        "M" | "PB" => Some(2),
+        "CCO" | "CC" | "ICO" | "IC" | "ECO" | "EC" | "CT" | "OC" | "9O" | "9C" | "AB" | "EAB" => {
+            Some(3)
+        }
+        _ => None,
+    }
+}
+
+/// What the target of each derived construction is, as in `CCO:ABC=O`. The
+/// evaluator places a target only for the codes listed here.
+pub(crate) const DERIVED_TARGETS: &[(&str, SymbolKind)] = &[
+    ("CCO", SymbolKind::Point),
+    ("ICO", SymbolKind::Point),
+    ("ECO", SymbolKind::Point),
+    ("OC", SymbolKind::Point),
+    ("9O", SymbolKind::Point),
+    ("M", SymbolKind::Point),
+    ("CT", SymbolKind::Point),
+    ("PB", SymbolKind::Point),
+    ("AB", SymbolKind::Point),
+    ("EAB", SymbolKind::Point),
+    ("MD", SymbolKind::Point),
+    ("PD", SymbolKind::Point),
+    ("TG", SymbolKind::Point),
+    ("CC", SymbolKind::Circle),
+    ("IC", SymbolKind::Circle),
+    ("EC", SymbolKind::Circle),
+    ("9C", SymbolKind::Circle),
+];
+
+/// What a named derived construction introduces.
+pub(crate) fn derived_target(code: &str) -> Option<SymbolKind> {
+    DERIVED_TARGETS
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map(|(_, kind)| *kind)
+}
+
+/// Check that every object is constructed before it is used, is constructed
+/// only once, and has the number of points its construction calls for.
+pub fn check(statements: &[Statement]) -> Vec<Diagnostic> {
+    let mut checker = Checker::default();
+    for stmt in statements {
+        checker.statement(stmt);
+    }
+    checker.diagnostics
+}
+
+/// The objects the statements construct, and where each was constructed.
+pub fn symbols(statements: &[Statement]) -> SymbolTable {
+    let mut checker = Checker::default();
+    for stmt in statements {
+        checker.statement(stmt);
+    }
+    checker.symbols
+}
+
+#[derive(Default)]
+struct Checker {
+    symbols: SymbolTable,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Checker {
+    fn statement(&mut self, stmt: &Statement) {
+        let span = stmt.span;
+        match &stmt.kind {
+            StmtKind::Prove(inner)
+            | StmtKind::ProveByContradiction(inner)
+            | StmtKind::Quantified(_, inner)
+            | StmtKind::ProveQuery(inner) => self.statement(inner),
+            StmtKind::Logic(_, lhs, rhs) => {
+                self.statement(lhs);
+                self.statement(rhs);
+            }
+            StmtKind::Casework { main, cases } => {
+                if let Some(main) = main {
+                    self.statement(main);
+                }
+                for case in cases.iter().flatten() {
+                    self.statement(&case.condition);
+                    self.statement(&case.result);
+                }
+            }
+            StmtKind::Marker(_)
+            | StmtKind::EndCasework
+            | StmtKind::Graph(_)
+            | StmtKind::Citation(_)
+            | StmtKind::Define(_)
+            | StmtKind::Include(_) => {}
+            StmtKind::Derived { code, args, target } => {
+                for arg in args {
+                    self.use_points(&arg.points, span);
+                }
+                if let (Some(n), Some(first)) = (derived_arity(code), args.first()) {
+                    self.arity(&first.points, n, &format!("`{}:`", code), span);
+                }
+                if let Some(kind) = derived_target(code) {
+                    match (kind, target) {
+                        (_, Some(target)) => self.define(kind, std::slice::from_ref(target), span),
+                        (SymbolKind::Circle, None) => {
+                            if let Some(first) = args.first() {
+                                self.define(kind, &first.points, span);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            StmtKind::Point(decl) => self.point(decl, span),
+            StmtKind::Linear(kind, objects) => {
+                let kind = match kind {
+                    LinearKind::Segment => SymbolKind::Segment,
+                    LinearKind::Line => SymbolKind::Line,
+                    LinearKind::Ray => SymbolKind::Ray,
+                };
+                for object in objects {
+                    self.use_points(&object.points, span);
+                    self.arity(&object.points, 2, kind.noun(), span);
+                    self.define(kind, &object.points, span);
+                }
+            }
+            StmtKind::Polygon { objects, .. } => {
+                for object in objects {
+                    // Vertices not yet constructed are constructed along with it.
+                    for point in &object.points {
+                        self.refine(SymbolKind::Point, std::slice::from_ref(point), span);
+                    }
+                    if object.points.len() < 3 {
+                        self.diagnostics.push(Diagnostic::warning(
+                            format!("polygon `{}` needs at least 3 vertices", object.name()),
+                            span,
+                        ));
+                    }
+                    self.define(SymbolKind::Polygon, &object.points, span);
+                }
+            }
+            StmtKind::Circle(decl) => match decl {
+                CircleDecl::ThroughPoints(points) => {
+                    self.use_points(points, span);
+                    self.define(SymbolKind::Circle, points, span);
+                }
+                CircleDecl::CenterRadius { center, radius } => {
+                    self.use_points(std::slice::from_ref(center), span);
+                    self.use_expr(radius, span);
+                    // `C:O;5/C:O;r` gives the radius of circle O a name.
+                    self.refine(SymbolKind::Circle, std::slice::from_ref(center), span);
+                }
+                CircleDecl::CenterThrough { center, point } => {
+                    self.use_points(&[center.clone(), point.clone()], span);
+                    self.refine(SymbolKind::Circle, std::slice::from_ref(center), span);
+                }
+                CircleDecl::Through(point) => {
+                    self.use_points(std::slice::from_ref(point), span);
+                }
+                CircleDecl::Named(name) => {
+                    self.define(SymbolKind::Circle, std::slice::from_ref(name), span)
+                }
+            },
+            StmtKind::RegularPolygon(poly) => {
+                self.use_points(&poly.side.points, span);
+                self.arity(&poly.side.points, 2, "the side of a regular polygon", span);
+                if let Ok(sides) = poly.sides.parse::<usize>() {
+                    if sides != poly.polygon.points.len() {
+                        self.diagnostics.push(
+                            Diagnostic::warning(
+                                format!(
+                                    "`R:{}` describes a {}-sided polygon, but `{}` has {} vertices",
+                                    poly.sides,
+                                    sides,
+                                    poly.polygon.name(),
+                                    poly.polygon.points.len()
+                                ),
+                                span,
+                            )
+                            .with_hint("name one vertex per side, e.g. `R:4;AB=ABCD`"),
+                        );
+                    }
+                }
+                // The polygon's other vertices are constructed along with it.
+                for point in &poly.polygon.points {
+                    self.refine(SymbolKind::Point, std::slice::from_ref(point), span);
+                }
+                // `J:ABC/R:3;AB=ABC` says that polygon ABC is regular.
+                self.refine(SymbolKind::Polygon, &poly.polygon.points, span);
+            }
+            StmtKind::Relation(lhs, _, rhs) => {
+                self.use_expr(lhs, span);
+                self.use_expr(rhs, span);
+            }
+            StmtKind::Query(expr) | StmtKind::Value(expr) => self.use_expr(expr, span),
+            StmtKind::Property { object, .. } | StmtKind::Orientation { object, .. } => {
+                self.use_points(&object.points, span);
+            }
+            StmtKind::Relationship { objects, .. } => {
+                for object in objects {
+                    self.use_points(&object.points, span);
+                }
+            }
+        }
+    }
+
+    fn point(&mut self, decl: &PointDecl, span: Span) {
+        match decl {
+            PointDecl::Free(names) => {
+                for name in names {
+                    self.define(SymbolKind::Point, std::slice::from_ref(name), span);
+                }
+            }
+            PointDecl::Coordinates { name, .. } => {
+                self.define(SymbolKind::Point, std::slice::from_ref(name), span)
+            }
+            PointDecl::OnObject {
+                name,
+                object,
+                conditions,
+            } => {
+                self.use_object(object, span);
+                self.define(SymbolKind::Point, std::slice::from_ref(name), span);
+                for condition in conditions {
+                    self.statement(condition);
+                }
+            }
+            PointDecl::InRegion { name, region } => {
+                self.use_object(region, span);
+                self.define(SymbolKind::Point, std::slice::from_ref(name), span);
+            }
+            PointDecl::Intersection { name, objects } => {
+                for object in objects {
+                    self.use_object(object, span);
+                }
+                self.define(SymbolKind::Point, std::slice::from_ref(name), span);
+            }
+        }
+    }
+
+    fn define(&mut self, kind: SymbolKind, points: &[String], span: Span) {
+        if let Some(first) = self.symbols.insert(kind, points, span) {
+            self.diagnostics.push(
+                Diagnostic::warning(
+                    format!(
+                        "{} `{}` is already constructed",
+                        kind.noun(),
+                        points.concat()
+                    ),
+                    span,
+                )
+                .with_note("first constructed here", first),
+            );
+            // Keep pointing at the original construction.
+            self.symbols.insert(kind, points, first);
+        }
+    }
+
+    /// Construct `points` unless they already are, for a statement that may
+    /// say more about an object than construct it.
+    fn refine(&mut self, kind: SymbolKind, points: &[String], span: Span) {
+        if self.symbols.lookup(kind, points).is_none() {
+            self.symbols.insert(kind, points, span);
+        }
+    }
+
+    /// Report any of `points` that have not been constructed. Returns true if all have.
+    fn use_points(&mut self, points: &[String], span: Span) -> bool {
+        let missing: Vec<&String> = points
+            .iter()
+            .filter(|p| {
+                self.symbols
+                    .lookup(SymbolKind::Point, std::slice::from_ref(*p))
+                    .is_none()
+            })
+            .collect();
+        let message = match missing.as_slice() {
+            [] => return true,
+            [point] => format!("point `{}` is used before it is constructed", point),
+            _ => format!(
+                "points {} are used before they are constructed",
+                missing
+                    .iter()
+                    .map(|p| format!("`{}`", p))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+        let names: Vec<&str> = missing.iter().map(|p| p.as_str()).collect();
+        let pronoun = if names.len() == 1 { "it" } else { "them" };
+        self.diagnostics
+            .push(Diagnostic::warning(message, span).with_hint(format!(
+                "construct {} first, e.g. `P:{}`",
+                pronoun,
+                names.join(",")
+            )));
+        false
+    }
+
+    /// A figure used as an object (something a point lies on, or is
+    /// intersected): its points and the object itself must be constructed.
+    fn use_object(&mut self, object: &Object, span: Span) {
+        // A circle may be named for a construction, as in `CC:ABC=W`, rather
+        // than for its center.
+        if object.kind == ObjectKind::Circle
+            && self
+                .symbols
+                .lookup(SymbolKind::Circle, &object.points)
+                .is_some()
+        {
+            return;
+        }
+        if !self.use_points(&object.points, span) {
+            return;
+        }
+        let (kind, hint) = match (object.kind, object.points.len()) {
+            (ObjectKind::Ray, _) => (SymbolKind::Ray, "W"),
+            (ObjectKind::Line, _) => (SymbolKind::Line, "L"),
+            (ObjectKind::Circle, _) => (SymbolKind::Circle, "C"),
+            (ObjectKind::Figure, 2) => (SymbolKind::Segment, "S"),
+            (ObjectKind::Figure, n) if n >= 3 => (SymbolKind::Polygon, "J"),
+            _ => return,
+        };
+        self.require(kind, &object.points, hint, span);
+    }
+
+    fn require(&mut self, kind: SymbolKind, points: &[String], prefix: &str, span: Span) {
+        let found = self.symbols.lookup(kind, points).is_some()
+            // A segment can also be read off a line or ray through the same points.
+            || (kind == SymbolKind::Segment
+                && (self.symbols.lookup(SymbolKind::Line, points).is_some()
+                    || self.symbols.lookup(SymbolKind::Ray, points).is_some()))
+            // A polygon whose sides have all been drawn counts as constructed.
+            || (kind == SymbolKind::Polygon
+                && edges(points).all(|e| self.symbols.lookup(SymbolKind::Segment, &e).is_some()));
+        if !found {
+            let name = points.concat();
+            self.diagnostics.push(
+                Diagnostic::warning(
+                    format!("{} `{}` has not been constructed", kind.noun(), name),
+                    span,
+                )
+                .with_hint(format!("construct it first with `{}:{}`", prefix, name)),
+            );
+        }
+    }
+
+    fn use_expr(&mut self, expr: &Expr, span: Span) {
+        match expr {
+            Expr::Number(_) | Expr::Variable(_) | Expr::Constant(_) => {}
+            Expr::Object(object) => {
+                if object.kind == ObjectKind::Circle {
+                    self.use_object(object, span);
+                } else {
+                    self.use_points(&object.points, span);
+                }
+            }
+            Expr::Area(object) | Expr::Perimeter(object) => {
+                if object.kind == ObjectKind::Figure {
+                    self.use_object(object, span);
+                } else {
+                    self.use_points(&object.points, span);
+                }
+            }
+            Expr::Angle(object) => {
+                self.use_points(&object.points, span);
+                if object.kind != ObjectKind::Arc {
+                    self.arity(&object.points, 3, "an angle", span);
+                }
+            }
+            Expr::Binary(lhs, _, rhs) | Expr::Product(lhs, rhs) => {
+                self.use_expr(lhs, span);
+                self.use_expr(rhs, span);
+            }
+        }
+    }
+
+    fn arity(&mut self, points: &[String], expected: usize, what: &str, span: Span) {
+        if points.len() != expected {
+            self.diagnostics.push(Diagnostic::warning(
+                format!(
+                    "{} takes {} points, but `{}` names {}",
+                    what,
+                    expected,
+                    points.concat(),
+                    points.len()
+                ),
+                span,
+            ));
+        }
+    }
+}
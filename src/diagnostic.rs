@@ -0,0 +1,156 @@
+use crate::lexer::Span;
+use crate::parser::ParseError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A secondary location that helps explain a diagnostic.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Note {
+    pub message: String,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub span: Span,
+    pub hint: Option<String>,
+    pub notes: Vec<Note>,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>, span: Span) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            message: message.into(),
+            span,
+            hint: None,
+            notes: Vec::new(),
+        }
+    }
+
+    pub fn warning(message: impl Into<String>, span: Span) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            ..Diagnostic::error(message, span)
+        }
+    }
+
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    pub fn with_note(mut self, message: impl Into<String>, span: Span) -> Self {
+        self.notes.push(Note {
+            message: message.into(),
+            span,
+        });
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl From<&ParseError> for Diagnostic {
+    fn from(err: &ParseError) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            message: err.message.clone(),
+            span: err.span,
+            hint: err.hint.clone(),
+            notes: Vec::new(),
+        }
+    }
+}
+
+/// Source text with a name, used to turn byte spans into lines and columns.
+pub struct SourceFile<'a> {
+    name: &'a str,
+    text: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> SourceFile<'a> {
+    pub fn new(name: &'a str, text: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        SourceFile {
+            name,
+            text,
+            line_starts,
+        }
+    }
+
+    /// 1-based line and column (in characters) of a byte offset.
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let col = self.text[self.line_starts[line]..offset].chars().count() + 1;
+        (line + 1, col)
+    }
+
+    fn line_text(&self, line: usize) -> &'a str {
+        let start = self.line_starts[line - 1];
+        let end = self
+            .line_starts
+            .get(line)
+            .map_or(self.text.len(), |next| next - 1);
+        self.text[start..end].trim_end_matches('\r')
+    }
+
+    /// Render a diagnostic rustc-style, with a caret under the offending text.
+    pub fn render(&self, diag: &Diagnostic) -> String {
+        let label = match diag.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        let mut out = format!("{}: {}\n", label, diag.message);
+        let gutter = self.snippet(&mut out, diag.span);
+        if let Some(hint) = &diag.hint {
+            out.push_str(&format!("{} = hint: {}\n", gutter, hint));
+        }
+        for note in &diag.notes {
+            out.push_str(&self.render_note(&note.message, note.span));
+        }
+        out
+    }
+
+    /// Render a note on its own, for one that belongs with a diagnostic
+    /// about another file.
+    pub fn render_note(&self, message: &str, span: Span) -> String {
+        let mut out = format!("note: {}\n", message);
+        self.snippet(&mut out, span);
+        out
+    }
+
+    /// Write the location, source line and caret for `span`, returning the gutter.
+    fn snippet(&self, out: &mut String, span: Span) -> String {
+        let (line, col) = self.line_col(span.start);
+        let text = self.line_text(line);
+        let gutter = " ".repeat(line.to_string().len());
+
+        // Underline to the end of the span, or of the line for multi-line spans.
+        let line_end = self.line_starts[line - 1] + text.len();
+        let end = span.end.min(line_end).max(span.start);
+        let width = self.text[span.start..end].chars().count().max(1);
+
+        out.push_str(&format!("{}--> {}:{}:{}\n", gutter, self.name, line, col));
+        out.push_str(&format!("{} |\n", gutter));
+        out.push_str(&format!("{} | {}\n", line, text));
+        out.push_str(&format!(
+            "{} | {}{}\n",
+            gutter,
+            " ".repeat(col - 1),
+            "^".repeat(width)
+        ));
+        gutter
+    }
+}
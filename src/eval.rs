@@ -0,0 +1,799 @@
+//! Numeric evaluation: runs a problem's constructions on coordinates so that
+//! queries can be answered and figures drawn.
+//!
+//! Points given with `P:A{x,y}` and everything constructed only from them are
+//! *fixed*. Other points are placed deterministically so the figure can still
+//! be drawn, but answers that depend on them are not reported.
+
+use crate::ast::*;
+use crate::checker;
+use crate::diagnostic::Diagnostic;
+use crate::geometry::{self, Circle, Line, Point};
+use crate::lexer::Span;
+use std::collections::HashMap;
+use std::f64::consts::{PI, TAU};
+
+/// Where the first free points go; later ones follow a spiral around them.
+const LAYOUT: [(f64, f64); 6] = [
+    (0.0, 0.0),
+    (6.0, 0.0),
+    (2.0, 4.5),
+    (7.0, 4.0),
+    (-2.0, 3.0),
+    (4.0, -3.0),
+];
+
+/// How many layout spots a free point tries before taking one that is close
+/// to a point already placed.
+const LAYOUT_TRIES: usize = 32;
+
+/// Radius used when a circle's size is not given.
+const DEFAULT_RADIUS: f64 = 3.0;
+
+/// A computed value, and whether the problem pins it down.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placed<T> {
+    pub value: T,
+    pub fixed: bool,
+}
+
+/// Something a construction draws, in construction order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Figure {
+    Segment(String, String),
+    Line(String, String),
+    Ray(String, String),
+    Polygon(Vec<String>),
+    /// A circle, by its key in the scene.
+    Circle(String),
+    /// A line from a derived construction such as `PB:AB`, labelled with its shorthand.
+    DerivedLine {
+        label: String,
+        line: Line,
+    },
+}
+
+/// Coordinates for every point and circle a problem constructs.
+#[derive(Debug, Default)]
+pub struct Scene {
+    points: HashMap<String, Placed<Point>>,
+    order: Vec<String>,
+    circles: HashMap<String, Placed<Circle>>,
+    figures: Vec<Figure>,
+    diagnostics: Vec<Diagnostic>,
+    free: usize,
+}
+
+/// The key a circle is stored under: its center, or its defining points in order.
+pub fn circle_key(points: &[String]) -> String {
+    let mut points = points.to_vec();
+    points.sort();
+    points.concat()
+}
+
+/// A number rounded for display, without trailing zeros.
+pub fn format_number(value: f64) -> String {
+    if (value - value.round()).abs() < 1e-9 {
+        return format!("{}", value.round() as i64);
+    }
+    let text = format!("{:.4}", value);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// The `k`th spot free points are laid out at.
+fn layout(k: usize) -> Point {
+    match LAYOUT.get(k) {
+        Some(&(x, y)) => Point::new(x, y),
+        None => {
+            let angle = (k as f64 * 137.5).to_radians();
+            Point::new(2.5, 1.5) + Point::new(angle.cos(), angle.sin()) * (4.0 + k as f64 * 0.5)
+        }
+    }
+}
+
+/// Something a point can be intersected with.
+enum Curve {
+    Line(Line),
+    Circle(Circle),
+}
+
+impl Scene {
+    /// Run every construction in order.
+    pub fn evaluate<'a>(statements: impl IntoIterator<Item = &'a Statement>) -> Scene {
+        let mut scene = Scene::default();
+        for stmt in statements {
+            scene.statement(stmt);
+        }
+        scene
+    }
+
+    pub fn point(&self, name: &str) -> Option<Point> {
+        self.points.get(name).map(|p| p.value)
+    }
+
+    /// Points in the order they were placed.
+    pub fn points(&self) -> impl Iterator<Item = (&str, Point)> {
+        self.order
+            .iter()
+            .map(|name| (name.as_str(), self.points[name].value))
+    }
+
+    pub fn is_fixed(&self, name: &str) -> bool {
+        self.points.get(name).is_some_and(|p| p.fixed)
+    }
+
+    /// The circle an object such as `cO` refers to.
+    pub fn circle(&self, obj: &Object) -> Option<Circle> {
+        self.circles.get(&circle_key(&obj.points)).map(|c| c.value)
+    }
+
+    pub fn circle_by_key(&self, key: &str) -> Option<Circle> {
+        self.circles.get(key).map(|c| c.value)
+    }
+
+    pub fn figures(&self) -> &[Figure] {
+        &self.figures
+    }
+
+    /// Constructions that turned out to be impossible with the given coordinates.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// The numeric value of an expression in this figure.
+    pub fn value(&self, expr: &Expr) -> Option<f64> {
+        self.measure(expr).map(|m| m.value)
+    }
+
+    /// The value of an expression, but only when the problem determines it.
+    pub fn answer(&self, expr: &Expr) -> Option<f64> {
+        self.measure(expr).filter(|m| m.fixed).map(|m| m.value)
+    }
+
+    fn statement(&mut self, stmt: &Statement) {
+        let span = stmt.span;
+        match &stmt.kind {
+            StmtKind::Point(decl) => self.point_decl(decl, span),
+            StmtKind::Linear(kind, objects) => {
+                for obj in objects {
+                    self.place_all(&obj.points);
+                    for pair in obj.points.windows(2) {
+                        let (a, b) = (pair[0].clone(), pair[1].clone());
+                        self.figures.push(match kind {
+                            LinearKind::Segment => Figure::Segment(a, b),
+                            LinearKind::Line => Figure::Line(a, b),
+                            LinearKind::Ray => Figure::Ray(a, b),
+                        });
+                    }
+                }
+            }
+            StmtKind::Polygon { objects, .. } => {
+                for obj in objects {
+                    self.place_all(&obj.points);
+                    self.figures.push(Figure::Polygon(obj.points.clone()));
+                }
+            }
+            StmtKind::Circle(decl) => self.circle_decl(decl, span),
+            StmtKind::RegularPolygon(poly) => self.regular_polygon(poly),
+            StmtKind::Derived { code, args, target } => {
+                self.derived(stmt, code, args, target.as_deref(), span)
+            }
+            _ => {}
+        }
+    }
+
+    fn set_point(&mut self, name: &str, value: Point, fixed: bool) {
+        if self
+            .points
+            .insert(name.to_string(), Placed { value, fixed })
+            .is_none()
+        {
+            self.order.push(name.to_string());
+        }
+    }
+
+    fn set_circle(&mut self, key: String, value: Circle, fixed: bool) {
+        self.circles.insert(key.clone(), Placed { value, fixed });
+        self.figures.push(Figure::Circle(key));
+    }
+
+    /// The next spot for a point nothing constrains, skipping spots within
+    /// half a unit of a point already placed.
+    fn next_free(&mut self) -> Point {
+        let mut spot = Point::new(0.0, 0.0);
+        for _ in 0..LAYOUT_TRIES {
+            spot = layout(self.free);
+            self.free += 1;
+            let taken = self
+                .points
+                .values()
+                .any(|p| p.value.is_finite() && p.value.dist(spot) <= 0.5);
+            if !taken {
+                break;
+            }
+        }
+        spot
+    }
+
+    /// A fraction along an object for the next point placed on it, varied so
+    /// that two points on the same object do not coincide.
+    fn next_fraction(&mut self) -> f64 {
+        let k = self.free;
+        self.free += 1;
+        [0.4, 0.65, 0.25, 0.8][k % 4]
+    }
+
+    fn place(&mut self, name: &str) -> Placed<Point> {
+        if let Some(&p) = self.points.get(name) {
+            return p;
+        }
+        let value = self.next_free();
+        self.set_point(name, value, false);
+        Placed {
+            value,
+            fixed: false,
+        }
+    }
+
+    fn place_all(&mut self, names: &[String]) -> (Vec<Point>, bool) {
+        let placed: Vec<Placed<Point>> = names.iter().map(|n| self.place(n)).collect();
+        let fixed = placed.iter().all(|p| p.fixed);
+        (placed.into_iter().map(|p| p.value).collect(), fixed)
+    }
+
+    fn warn(&mut self, fixed: bool, message: String, span: Span) {
+        if fixed {
+            self.diagnostics.push(Diagnostic::warning(message, span));
+        }
+    }
+
+    fn point_decl(&mut self, decl: &PointDecl, span: Span) {
+        match decl {
+            PointDecl::Free(names) => {
+                for name in names {
+                    self.place(name);
+                }
+            }
+            PointDecl::Coordinates { name, coords } => {
+                let parsed: Vec<f64> = coords
+                    .split(',')
+                    .filter_map(|c| c.trim().parse().ok())
+                    .collect();
+                if parsed.iter().any(|v: &f64| !v.is_finite()) {
+                    self.diagnostics.push(
+                        Diagnostic::error(
+                            format!("the coordinates of `{}` are not finite numbers", name),
+                            span,
+                        )
+                        .with_hint("write each coordinate as a number, such as `P:A{0,2.5}`"),
+                    );
+                    self.place(name);
+                    return;
+                }
+                match parsed[..] {
+                    [x, y] => self.set_point(name, Point::new(x, y), true),
+                    _ => {
+                        self.place(name);
+                    }
+                }
+            }
+            PointDecl::OnObject {
+                name,
+                object,
+                conditions,
+            } => {
+                let at = self.on_object(object);
+                self.set_point(name, at, false);
+                for condition in conditions {
+                    self.statement(condition);
+                }
+            }
+            PointDecl::InRegion { name, region } => {
+                let (points, _) = self.place_all(&region.points);
+                let center = match self.circle(region) {
+                    Some(circle) if region.kind == ObjectKind::Circle => circle.center,
+                    _ => geometry::centroid(&points),
+                };
+                let offset = Point::new(0.3, 0.2) * self.free as f64;
+                self.free += 1;
+                self.set_point(name, center + offset, false);
+            }
+            PointDecl::Intersection { name, objects } => self.intersection(name, objects, span),
+        }
+    }
+
+    fn on_object(&mut self, obj: &Object) -> Point {
+        if obj.kind == ObjectKind::Circle {
+            if let Some(circle) = self.circle(obj) {
+                let angle = (60.0 + 77.0 * self.free as f64).to_radians();
+                self.free += 1;
+                return circle.at(angle);
+            }
+        }
+        let (points, _) = self.place_all(&obj.points);
+        let t = self.next_fraction();
+        match (obj.kind, points.as_slice()) {
+            (ObjectKind::Ray, [a, b]) => a.lerp(*b, 1.0 + t),
+            (ObjectKind::Line, [a, b]) => a.lerp(*b, t * 1.5 - 0.25),
+            (_, [a, b, ..]) => a.lerp(*b, t),
+            (_, [a]) => *a + Point::new(1.0, 1.0),
+            _ => self.next_free(),
+        }
+    }
+
+    fn curve(&mut self, obj: &Object) -> Option<Placed<Curve>> {
+        if obj.kind == ObjectKind::Circle {
+            let circle = self.circles.get(&circle_key(&obj.points))?;
+            return Some(Placed {
+                value: Curve::Circle(circle.value),
+                fixed: circle.fixed,
+            });
+        }
+        let (points, fixed) = self.place_all(&obj.points);
+        match points[..] {
+            [a, b] => Some(Placed {
+                value: Curve::Line(Line::new(a, b)),
+                fixed,
+            }),
+            _ => None,
+        }
+    }
+
+    fn intersection(&mut self, name: &str, objects: &[Object], span: Span) {
+        let curves: Vec<Placed<Curve>> = objects.iter().filter_map(|o| self.curve(o)).collect();
+        let fixed = curves.len() >= 2 && curves.iter().all(|c| c.fixed);
+        let candidates = match (curves.first(), curves.get(1)) {
+            (Some(first), Some(second)) => match (&first.value, &second.value) {
+                (Curve::Line(l), Curve::Line(m)) => {
+                    geometry::intersect_lines(l, m).into_iter().collect()
+                }
+                (Curve::Line(l), Curve::Circle(c)) | (Curve::Circle(c), Curve::Line(l)) => {
+                    geometry::intersect_line_circle(l, c)
+                }
+                (Curve::Circle(c), Curve::Circle(k)) => geometry::intersect_circles(c, k),
+            },
+            _ => Vec::new(),
+        };
+        // Prefer an intersection that is not already a named point, such as
+        // the second point where a line through A meets a circle through A.
+        let fresh = candidates
+            .iter()
+            .find(|p| self.points.values().all(|q| q.value.dist(**p) > 1e-6))
+            .or(candidates.first())
+            .copied();
+        match fresh {
+            Some(at) => self.set_point(name, at, fixed),
+            None => {
+                let names: Vec<String> = objects.iter().map(|o| format!("`{}`", o)).collect();
+                self.warn(
+                    fixed,
+                    format!("{} do not intersect", names.join(" and ")),
+                    span,
+                );
+                self.place(name);
+            }
+        }
+    }
+
+    fn circle_decl(&mut self, decl: &CircleDecl, span: Span) {
+        match decl {
+            CircleDecl::ThroughPoints(names) => {
+                let (points, fixed) = self.place_all(names);
+                match geometry::circumcircle(points[0], points[1], points[2]) {
+                    Some(circle) => self.set_circle(circle_key(names), circle, fixed),
+                    None => self.warn(
+                        fixed,
+                        format!(
+                            "no circle passes through `{}`, `{}` and `{}` because they are collinear",
+                            names[0], names[1], names[2]
+                        ),
+                        span,
+                    ),
+                }
+            }
+            CircleDecl::CenterRadius { center, radius } => {
+                let c = self.place(center);
+                let (radius, known) = match self.measure(radius) {
+                    Some(r) if r.value > 0.0 => (r.value, r.fixed),
+                    _ => (DEFAULT_RADIUS, false),
+                };
+                self.set_circle(
+                    center.clone(),
+                    Circle::new(c.value, radius),
+                    c.fixed && known,
+                );
+            }
+            CircleDecl::CenterThrough { center, point } => {
+                let c = self.place(center);
+                let p = self.place(point);
+                let circle = Circle::new(c.value, c.value.dist(p.value));
+                self.set_circle(center.clone(), circle, c.fixed && p.fixed);
+            }
+            CircleDecl::Through(point) => {
+                let p = self.place(point);
+                let circle = Circle::new(p.value + Point::new(DEFAULT_RADIUS, 0.0), DEFAULT_RADIUS);
+                self.set_circle(point.clone(), circle, false);
+            }
+            CircleDecl::Named(name) => {
+                let center = if self.order.is_empty() {
+                    Point::new(0.0, 0.0)
+                } else {
+                    let points: Vec<Point> = self.points().map(|(_, p)| p).collect();
+                    geometry::centroid(&points)
+                };
+                self.set_circle(name.clone(), Circle::new(center, DEFAULT_RADIUS), false);
+            }
+        }
+    }
+
+    fn regular_polygon(&mut self, poly: &RegularPolygon) {
+        let names = &poly.polygon.points;
+        let n = names.len();
+        let (side, fixed) = self.place_all(&poly.side.points);
+        if n < 3 || side.len() != 2 {
+            self.place_all(names);
+            return;
+        }
+        // Walk the polygon's names starting from the given side, in whichever
+        // direction the side runs.
+        let start = names.iter().position(|p| *p == poly.side.points[0]);
+        let order: Vec<usize> = match start {
+            Some(i) if names[(i + 1) % n] == poly.side.points[1] => {
+                (0..n).map(|k| (i + k) % n).collect()
+            }
+            Some(i) if names[(i + n - 1) % n] == poly.side.points[1] => {
+                (0..n).map(|k| (i + n - k) % n).collect()
+            }
+            _ => {
+                self.place_all(names);
+                self.figures.push(Figure::Polygon(names.clone()));
+                return;
+            }
+        };
+        let vertices = geometry::regular_polygon(side[0], side[1], n);
+        for (k, &index) in order.iter().enumerate().skip(2) {
+            if !self.points.contains_key(&names[index]) {
+                self.set_point(&names[index], vertices[k], fixed);
+            }
+        }
+        self.figures.push(Figure::Polygon(names.clone()));
+    }
+
+    fn derived(
+        &mut self,
+        stmt: &Statement,
+        code: &str,
+        args: &[Object],
+        target: Option<&str>,
+        span: Span,
+    ) {
+        let Some(first) = args.first() else {
+            return;
+        };
+        let target = target.filter(|_| checker::derived_target(code).is_some());
+        let (points, mut fixed) = self.place_all(&first.points);
+        let label = stmt.to_string();
+        let triangle = match points[..] {
+            [a, b, c] => Some((a, b, c)),
+            _ => None,
+        };
+
+        let center =
+            |f: fn(Point, Point, Point) -> Option<Point>| triangle.and_then(|(a, b, c)| f(a, b, c));
+        let circle = |f: fn(Point, Point, Point) -> Option<Circle>| {
+            triangle.and_then(|(a, b, c)| f(a, b, c))
+        };
+
+        let degenerate = format!("`{}` is degenerate, so `{}:` is undefined", first, code);
+        match code {
+            "M" => {
+                if let ([a, b], Some(target)) = (&points[..], target) {
+                    self.set_point(target, a.midpoint(*b), fixed);
+                }
+            }
+            "CT" => {
+                if let Some(target) = target {
+                    self.set_point(target, geometry::centroid(&points), fixed);
+                }
+            }
+            "CCO" | "ICO" | "ECO" | "OC" | "9O" => {
+                let found = center(match code {
+                    "CCO" => geometry::circumcenter,
+                    "ICO" => geometry::incenter,
+                    "ECO" => geometry::excenter,
+                    "OC" => geometry::orthocenter,
+                    _ => geometry::nine_point_center,
+                });
+                match (found, target) {
+                    (Some(p), Some(target)) => self.set_point(target, p, fixed),
+                    (None, _) if triangle.is_some() => self.warn(fixed, degenerate, span),
+                    _ => {}
+                }
+            }
+            "CC" | "IC" | "EC" | "9C" => {
+                let found = circle(match code {
+                    "CC" => geometry::circumcircle,
+                    "IC" => geometry::incircle,
+                    "EC" => geometry::excircle,
+                    _ => geometry::nine_point_circle,
+                });
+                match found {
+                    Some(c) => {
+                        let key = match target {
+                            Some(target) => target.to_string(),
+                            None => circle_key(&first.points),
+                        };
+                        self.set_circle(key, c, fixed);
+                    }
+                    None if triangle.is_some() => self.warn(fixed, degenerate, span),
+                    None => {}
+                }
+            }
+            "PB" => {
+                if let [a, b] = points[..] {
+                    let line = geometry::perpendicular_bisector(a, b);
+                    self.figures.push(Figure::DerivedLine { label, line });
+                    if let Some(target) = target {
+                        self.set_point(target, a.midpoint(b), fixed);
+                    }
+                }
+            }
+            "AB" | "EAB" => {
+                if let Some((a, b, c)) = triangle {
+                    let line = if code == "AB" {
+                        geometry::angle_bisector(a, b, c)
+                    } else {
+                        geometry::exterior_angle_bisector(a, b, c)
+                    };
+                    self.figures.push(Figure::DerivedLine { label, line });
+                    if let Some(target) = target {
+                        match geometry::intersect_lines(&line, &Line::new(a, c)) {
+                            Some(p) => self.set_point(target, p, fixed),
+                            None => self.warn(
+                                fixed,
+                                format!(
+                                    "the bisector of angle `{}` never meets `{}{}`",
+                                    first, first.points[0], first.points[2]
+                                ),
+                                span,
+                            ),
+                        }
+                    }
+                }
+            }
+            "MD" => {
+                if let Some((a, b, c)) = triangle {
+                    let foot = b.midpoint(c);
+                    self.figures.push(Figure::DerivedLine {
+                        label,
+                        line: Line::new(a, foot),
+                    });
+                    if let Some(target) = target {
+                        self.set_point(target, foot, fixed);
+                    }
+                }
+            }
+            "PD" | "PL" => {
+                // `PD:P;AB` drops a perpendicular from P to AB; `PD:ABC` from A to BC.
+                let (from, line) = match (&points[..], args.get(1)) {
+                    ([p], Some(line)) => {
+                        let (ends, line_fixed) = self.place_all(&line.points);
+                        fixed &= line_fixed;
+                        match ends[..] {
+                            [a, b] => (*p, Line::new(a, b)),
+                            _ => return,
+                        }
+                    }
+                    ([p, a, b], None) => (*p, Line::new(*a, *b)),
+                    _ => return,
+                };
+                if code == "PD" {
+                    let foot = line.foot(from);
+                    let line = if foot.dist(from) < geometry::EPSILON {
+                        line.perpendicular_through(from)
+                    } else {
+                        Line::new(from, foot)
+                    };
+                    self.figures.push(Figure::DerivedLine { label, line });
+                    if let Some(target) = target {
+                        self.set_point(target, foot, fixed);
+                    }
+                } else {
+                    let line = line.parallel_through(from);
+                    self.figures.push(Figure::DerivedLine { label, line });
+                }
+            }
+            "TG" => {
+                let (Some(p), Some(obj)) = (points.first(), args.get(1)) else {
+                    return;
+                };
+                let Some(c) = self.circles.get(&circle_key(&obj.points)).copied() else {
+                    return;
+                };
+                fixed &= c.fixed;
+                let circle = c.value;
+                let touch = if circle.contains(*p) {
+                    Some(*p)
+                } else {
+                    // The tangent points lie on the circle with diameter OP.
+                    let mid = circle.center.midpoint(*p);
+                    let thales = Circle::new(mid, mid.dist(*p));
+                    geometry::intersect_circles(&circle, &thales)
+                        .first()
+                        .copied()
+                };
+                match touch {
+                    Some(t) => {
+                        let line = Line::new(circle.center, t).perpendicular_through(t);
+                        self.figures.push(Figure::DerivedLine { label, line });
+                        if let Some(target) = target {
+                            self.set_point(target, t, fixed);
+                        }
+                    }
+                    None => self.warn(
+                        fixed,
+                        format!(
+                            "no tangent from `{}` touches `{}` because the point is inside it",
+                            first, obj
+                        ),
+                        span,
+                    ),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn measure(&self, expr: &Expr) -> Option<Placed<f64>> {
+        let known = |value: f64| Some(Placed { value, fixed: true });
+        match expr {
+            Expr::Number(n) => known(n.parse().ok()?),
+            Expr::Constant(c) => match c.as_str() {
+                "\\P" | "π" => known(PI),
+                "\\T" | "τ" => known(TAU),
+                "\\G" | "φ" => known((1.0 + 5f64.sqrt()) / 2.0),
+                _ => None,
+            },
+            Expr::Variable(_) => None,
+            Expr::Object(obj) => match obj.kind {
+                ObjectKind::Figure => match self.lookup(&obj.points)?[..] {
+                    [a, b] => Some(self.with(&obj.points, a.dist(b))),
+                    _ => None,
+                },
+                ObjectKind::Arc => {
+                    let (circle, theta) = self.arc(obj)?;
+                    Some(circle.map(|c| c.radius * theta))
+                }
+                ObjectKind::Sector => self.sector(obj),
+                _ => None,
+            },
+            Expr::Area(obj) => match obj.kind {
+                ObjectKind::Circle => {
+                    let c = self.circles.get(&circle_key(&obj.points))?;
+                    Some(c.map(|c| PI * c.radius * c.radius))
+                }
+                ObjectKind::Sector => self.sector(obj),
+                _ => {
+                    let points = self.lookup(&obj.points)?;
+                    (points.len() >= 3)
+                        .then(|| self.with(&obj.points, geometry::polygon_area(&points)))
+                }
+            },
+            Expr::Perimeter(obj) => match obj.kind {
+                ObjectKind::Circle => {
+                    let c = self.circles.get(&circle_key(&obj.points))?;
+                    Some(c.map(|c| TAU * c.radius))
+                }
+                _ => {
+                    let points = self.lookup(&obj.points)?;
+                    (points.len() >= 3)
+                        .then(|| self.with(&obj.points, geometry::polygon_perimeter(&points)))
+                }
+            },
+            Expr::Angle(obj) => match obj.kind {
+                ObjectKind::Arc => {
+                    let (circle, theta) = self.arc(obj)?;
+                    Some(circle.map(|_| theta.to_degrees()))
+                }
+                _ => match self.lookup(&obj.points)?[..] {
+                    // An angle needs a side on each side of its vertex
+                    [a, b, c] if a.dist(b) > geometry::EPSILON && c.dist(b) > geometry::EPSILON => {
+                        Some(self.with(&obj.points, geometry::angle(a, b, c)))
+                    }
+                    _ => None,
+                },
+            },
+            Expr::Binary(lhs, op, rhs) => {
+                let l = self.measure(lhs)?;
+                let r = self.measure(rhs)?;
+                let value = match op {
+                    ArithOp::Add => l.value + r.value,
+                    ArithOp::Sub => l.value - r.value,
+                    ArithOp::Div if r.value.abs() < geometry::EPSILON => return None,
+                    ArithOp::Div => l.value / r.value,
+                };
+                Some(Placed {
+                    value,
+                    fixed: l.fixed && r.fixed,
+                })
+            }
+            Expr::Product(lhs, rhs) => {
+                let l = self.measure(lhs)?;
+                let r = self.measure(rhs)?;
+                Some(Placed {
+                    value: l.value * r.value,
+                    fixed: l.fixed && r.fixed,
+                })
+            }
+        }
+    }
+
+    fn lookup(&self, names: &[String]) -> Option<Vec<Point>> {
+        names.iter().map(|n| self.point(n)).collect()
+    }
+
+    fn with(&self, names: &[String], value: f64) -> Placed<f64> {
+        Placed {
+            value,
+            fixed: names.iter().all(|n| self.is_fixed(n)),
+        }
+    }
+
+    /// The first constructed circle (by key) that passes through every named point.
+    pub fn circle_through(&self, names: &[String]) -> Option<Circle> {
+        self.placed_circle_through(names).map(|c| c.value)
+    }
+
+    fn placed_circle_through(&self, names: &[String]) -> Option<Placed<Circle>> {
+        let points = self.lookup(names)?;
+        let mut keys: Vec<&String> = self.circles.keys().collect();
+        keys.sort();
+        keys.into_iter()
+            .map(|k| self.circles[k])
+            .find(|c| points.iter().all(|&p| c.value.contains(p)))
+    }
+
+    /// The circle an arc lies on and its central angle in radians. `aAB` is
+    /// the minor arc from A to B, and `aAXB` the arc from A to B through X,
+    /// which may be the major one.
+    fn arc(&self, obj: &Object) -> Option<(Placed<Circle>, f64)> {
+        let circle = self.placed_circle_through(&obj.points)?;
+        let center = circle.value.center;
+        let theta = match self.lookup(&obj.points)?[..] {
+            [a, b] => geometry::angle(a, center, b),
+            [a, x, b] => {
+                let sweep = geometry::turn(a, center, b);
+                if geometry::turn(a, center, x) < sweep {
+                    sweep
+                } else {
+                    360.0 - sweep
+                }
+            }
+            _ => return None,
+        };
+        let fixed = circle.fixed && obj.points.iter().all(|n| self.is_fixed(n));
+        Some((Placed { fixed, ..circle }, theta.to_radians()))
+    }
+
+    /// The area of sector `qOAB` with center O.
+    fn sector(&self, obj: &Object) -> Option<Placed<f64>> {
+        let [o, a, b] = self.lookup(&obj.points)?[..] else {
+            return None;
+        };
+        if o.dist(a) < geometry::EPSILON || o.dist(b) < geometry::EPSILON {
+            return None;
+        }
+        let radius = o.dist(a);
+        let theta = geometry::angle(a, o, b).to_radians();
+        Some(self.with(&obj.points, radius * radius * theta / 2.0))
+    }
+}
+
+impl<T> Placed<T> {
+    fn map<U>(self, f: impl FnOnce(T) -> U) -> Placed<U> {
+        Placed {
+            value: f(self.value),
+            fixed: self.fixed,
+        }
+    }
+}
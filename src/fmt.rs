@@ -0,0 +1,96 @@
+//! `gslc fmt`: rewrite GSL files in canonical style.
+
+use gslc::diagnostic::SourceFile;
+use gslc::formatter::{self, Style};
+use std::fs;
+use std::io::{self, Read};
+
+const USAGE: &str = "usage: gslc fmt [--check] [--style ascii|unicode] [file.gsl ...]";
+
+/// Format the named files in place, or standard input to standard output
+/// when there are none. Returns the exit code.
+pub fn run(args: &[String]) -> i32 {
+    let mut check = false;
+    let mut style = Style::Ascii;
+    let mut files = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--style" => {
+                style = match args.next().map(String::as_str) {
+                    Some("ascii") => Style::Ascii,
+                    Some("unicode") => Style::Unicode,
+                    _ => {
+                        eprintln!("Error: --style expects ascii or unicode");
+                        return 2;
+                    }
+                }
+            }
+            "--ascii" => style = Style::Ascii,
+            "--unicode" => style = Style::Unicode,
+            _ if arg.starts_with('-') => {
+                eprintln!("Error: unknown option `{}`\n{}", arg, USAGE);
+                return 2;
+            }
+            _ => files.push(arg.clone()),
+        }
+    }
+
+    if files.is_empty() {
+        let mut input = String::new();
+        if let Err(e) = io::stdin().read_to_string(&mut input) {
+            eprintln!("Error reading input: {}", e);
+            return 1;
+        }
+        return match format("<stdin>", &input, style) {
+            Some(output) if check => i32::from(output != input),
+            Some(output) => {
+                print!("{}", output);
+                0
+            }
+            None => 1,
+        };
+    }
+
+    let mut status = 0;
+    for path in &files {
+        let input = match fs::read_to_string(path) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("Error reading {}: {}", path, e);
+                status = 1;
+                continue;
+            }
+        };
+        let Some(output) = format(path, &input, style) else {
+            status = 1;
+            continue;
+        };
+        if output == input {
+            continue;
+        }
+        if check {
+            println!("{} is not formatted", path);
+            status = 1;
+        } else if let Err(e) = fs::write(path, output) {
+            eprintln!("Error writing {}: {}", path, e);
+            status = 1;
+        }
+    }
+    status
+}
+
+/// The formatted text, or `None` after printing why there is none.
+fn format(name: &str, input: &str, style: Style) -> Option<String> {
+    match formatter::format(input, style) {
+        Ok(output) => Some(output),
+        Err(diagnostics) => {
+            let source = SourceFile::new(name, input);
+            for diagnostic in &diagnostics {
+                eprintln!("{}", source.render(diagnostic));
+            }
+            None
+        }
+    }
+}
@@ -0,0 +1,346 @@
+//! Canonical formatting for GSL source, as `gslc fmt` does it.
+//!
+//! Whitespace is dropped and every statement is spelled one way. In the
+//! ASCII style that is the syntax tree's `Display`, with symbolic
+//! relationships written as codes (`AB∥CD` becomes `AB;CD*P`); the Unicode
+//! style uses the symbols instead (`∠ABC`, `AB⊥CD`, `∴`, `π`).
+
+use crate::ast::*;
+use crate::diagnostic::Diagnostic;
+use crate::lexer::{self, Span};
+use crate::parser;
+use crate::{Problem, Translator};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Style {
+    #[default]
+    Ascii,
+    Unicode,
+}
+
+/// Relationship codes and the infix symbols that mean the same thing. The
+/// code listed first for a symbol is the one the ASCII style writes.
+const SYMBOLS: &[(&str, &str)] = &[
+    ("P", "∥"),
+    ("PD", "⊥"),
+    ("PR", "⊥"),
+    ("CG", "≅"),
+    ("SM", "~"),
+];
+
+/// Format a file of one or more problems. Input with errors is left alone and its diagnostics
+/// returned; so is input whose translation the formatting would change,
+/// which would be a bug here rather than in the input.
+pub fn format(input: &str, style: Style) -> Result<String, Vec<Diagnostic>> {
+    let mut statements = Vec::new();
+    // Which `\\...\\` problem of the file each statement belongs to.
+    let mut problem_of = Vec::new();
+    let mut errors = Vec::new();
+    // Metadata lines are kept where they are, like comments.
+    let mut comments = lexer::comments(input);
+    for (problem, parsed) in parser::parse_problems(input).into_iter().enumerate() {
+        comments.extend(parsed.fields.iter().map(|field| field.span));
+        for parsed in parsed.statements {
+            match parsed {
+                Ok(stmt) => {
+                    statements.push(stmt);
+                    problem_of.push(problem);
+                }
+                Err(err) => errors.push(Diagnostic::from(&err)),
+            }
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let lines: Vec<String> = statements.iter().map(|s| statement(s, style)).collect();
+    comments.sort_by_key(|span| span.start);
+    let setup = statements
+        .iter()
+        .any(|s| matches!(s.kind, StmtKind::Define(_) | StmtKind::Include(_)));
+    let output = if !comments.is_empty() || setup || problem_of.last().is_some_and(|&p| p > 0) {
+        with_comments(input, &statements, &problem_of, &lines, &comments)
+    } else if input.trim().contains('\n') {
+        // Problems written over several lines keep one statement per line.
+        format!("\\\\{}\\\\\n", lines.join("/\n"))
+    } else {
+        format!("\\\\{}\\\\\n", lines.join("/"))
+    };
+
+    // Shorthand quoted in the English (casework, logic) shows its spelling,
+    // so both sides are compared in the same style.
+    let translator = Translator::new();
+    let before = translator.translate_problems(input);
+    let after = translator.translate_problems(&output);
+    // Macros expand to several steps and definitions to none, so steps are
+    // traced back to the statement they came from.
+    let meanings = |problems: &[Problem]| -> Vec<(String, Option<f64>)> {
+        let steps = problems.iter().flat_map(|p| &p.translation.steps);
+        steps
+            .map(|step| {
+                let stmt = normalize(&step.statement, Style::Ascii);
+                (translator.translate_statement(&stmt), step.answer)
+            })
+            .collect()
+    };
+    let spans: Vec<Span> = before
+        .iter()
+        .flat_map(|p| &p.translation.steps)
+        .map(|step| step.statement.span)
+        .collect();
+    let (old, new) = (meanings(&before), meanings(&after));
+    let mut differing: Vec<usize> = (0..old.len().max(new.len()))
+        .filter(|&i| old.get(i) != new.get(i))
+        .map(|i| {
+            spans
+                .get(i)
+                .and_then(|span| {
+                    statements
+                        .iter()
+                        .position(|s| s.span.start <= span.start && span.end <= s.span.end)
+                })
+                .unwrap_or(statements.len().saturating_sub(1))
+        })
+        .collect();
+    differing.dedup();
+    let changed: Vec<Diagnostic> = differing
+        .into_iter()
+        .map(|i| {
+            Diagnostic::error(
+                format!("formatting would change what this says, to `{}`", lines[i]),
+                statements[i].span,
+            )
+        })
+        .collect();
+    if changed.is_empty() {
+        Ok(output)
+    } else {
+        Err(changed)
+    }
+}
+
+/// One statement per line, with each comment kept beside the statement it
+/// was written on, or above the statement that follows it. Each problem of
+/// the file gets its own wrappers, with a blank line between problems.
+/// Definitions and includes that open a problem are kept above its
+/// wrapper.
+fn with_comments(
+    input: &str,
+    statements: &[Statement],
+    problem_of: &[usize],
+    lines: &[String],
+    comments: &[Span],
+) -> String {
+    let line_of = |offset: usize| input[..offset].matches('\n').count();
+    // Index `statements.len()` holds comments after the last statement.
+    let mut above: Vec<Vec<&str>> = vec![Vec::new(); statements.len() + 1];
+    let mut beside: Vec<Option<&str>> = vec![None; statements.len()];
+    for comment in comments {
+        let text = &input[comment.start..comment.end];
+        let previous = statements.iter().rposition(|s| s.span.end <= comment.start);
+        match previous {
+            Some(i) if line_of(statements[i].span.end) == line_of(comment.start) => {
+                beside[i] = Some(text)
+            }
+            _ => {
+                let next = statements
+                    .iter()
+                    .position(|s| s.span.end > comment.start)
+                    .unwrap_or(statements.len());
+                above[next].push(text);
+            }
+        }
+    }
+
+    // Definitions and includes
+    let setup = |i: usize| {
+        matches!(
+            statements[i].kind,
+            StmtKind::Define(_) | StmtKind::Include(_)
+        )
+    };
+    let mut out = String::new();
+    // Whether the current problem's wrapper has been opened.
+    let mut open = false;
+    for (i, line) in lines.iter().enumerate() {
+        let starts = i == 0 || problem_of[i - 1] != problem_of[i];
+        let last = i + 1 == lines.len() || problem_of[i + 1] != problem_of[i];
+        if starts {
+            open = false;
+            if i > 0 {
+                out.push('\n');
+            }
+        }
+        for comment in &above[i] {
+            out.push_str(comment);
+            out.push('\n');
+        }
+        // A problem of nothing but setup keeps its wrapper, so it
+        // stays a problem of its own.
+        let rest = (i..lines.len()).take_while(|&j| problem_of[j] == problem_of[i]);
+        if !open && setup(i) && !rest.clone().all(setup) {
+            out.push_str(line);
+        } else {
+            if !open {
+                out.push_str("\\\\");
+                open = true;
+            }
+            out.push_str(line);
+            out.push_str(if last { "\\\\" } else { "/" });
+        }
+        if let Some(comment) = beside[i] {
+            out.push(' ');
+            out.push_str(comment);
+        }
+        out.push('\n');
+    }
+    if lines.is_empty() {
+        out.push_str("\\\\\\\\\n");
+    }
+    for comment in &above[statements.len()] {
+        out.push_str(comment);
+        out.push('\n');
+    }
+    out
+}
+
+/// One statement in `style`.
+pub fn statement(stmt: &Statement, style: Style) -> String {
+    let mut stmt = normalize(stmt, style);
+    match style {
+        Style::Ascii => stmt.to_string(),
+        Style::Unicode => {
+            let theorem = stmt.theorem.take();
+            unicode(&stmt) + theorem.as_deref().unwrap_or_default()
+        }
+    }
+}
+
+/// Spell every relationship the way `style` wants it.
+fn normalize(stmt: &Statement, style: Style) -> Statement {
+    let mut stmt = stmt.clone();
+    rewrite(&mut stmt, &mut |s| {
+        let StmtKind::Relationship {
+            relation, infix, ..
+        } = &mut s.kind
+        else {
+            return;
+        };
+        let Some(symbol) = SYMBOLS
+            .iter()
+            .find(|(code, symbol)| code == relation || symbol == relation)
+            .map(|(_, symbol)| *symbol)
+        else {
+            return;
+        };
+        match style {
+            Style::Ascii => {
+                let (code, _) = SYMBOLS.iter().find(|(_, s)| *s == symbol).unwrap();
+                *relation = code.to_string();
+                *infix = false;
+            }
+            Style::Unicode => {
+                *relation = symbol.to_string();
+                *infix = true;
+            }
+        }
+    });
+    stmt
+}
+
+/// Like [`Statement::walk`], but with mutable access.
+fn rewrite(stmt: &mut Statement, f: &mut impl FnMut(&mut Statement)) {
+    f(stmt);
+    match &mut stmt.kind {
+        StmtKind::Prove(inner)
+        | StmtKind::ProveByContradiction(inner)
+        | StmtKind::Quantified(_, inner)
+        | StmtKind::ProveQuery(inner) => rewrite(inner, f),
+        StmtKind::Logic(_, lhs, rhs) => {
+            rewrite(lhs, f);
+            rewrite(rhs, f);
+        }
+        StmtKind::Casework { main, cases } => {
+            if let Some(main) = main {
+                rewrite(main, f);
+            }
+            for case in cases.iter_mut().flatten() {
+                rewrite(&mut case.condition, f);
+                rewrite(&mut case.result, f);
+            }
+        }
+        StmtKind::Point(PointDecl::OnObject { conditions, .. }) => {
+            for condition in conditions {
+                rewrite(condition, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `Display` with symbols wherever GSL has one.
+fn unicode(stmt: &Statement) -> String {
+    match &stmt.kind {
+        StmtKind::Prove(inner) => format!("\\p:{}", unicode(inner)),
+        StmtKind::ProveByContradiction(inner) => format!("\\pC:{}", unicode(inner)),
+        StmtKind::Marker(marker) => match marker {
+            ProofMarker::Qed => "□",
+            ProofMarker::Contradiction => "↯",
+            ProofMarker::Because => "∵",
+            ProofMarker::Therefore => "∴",
+        }
+        .to_string(),
+        StmtKind::Logic(op, lhs, rhs) => format!("{}{}{}", unicode(lhs), op, unicode(rhs)),
+        StmtKind::Quantified(Quantifier::ForAll, body) => format!("∀{}", unicode(body)),
+        StmtKind::Quantified(Quantifier::Exists, body) => format!("∃{}", unicode(body)),
+        StmtKind::Casework { main, cases } => {
+            let main = main.as_deref().map(unicode).unwrap_or_default();
+            match cases {
+                Some(cases) => {
+                    let cases: Vec<String> = cases
+                        .iter()
+                        .map(|c| format!("({};{})", unicode(&c.condition), unicode(&c.result)))
+                        .collect();
+                    format!("{}<<{}>>", main, cases.join(","))
+                }
+                None => format!("{}<<", main),
+            }
+        }
+        StmtKind::Point(PointDecl::OnObject {
+            name,
+            object,
+            conditions,
+        }) if !conditions.is_empty() => {
+            let conditions: Vec<String> = conditions.iter().map(unicode).collect();
+            format!("P:{}.{}|{}", name, object, conditions.join(","))
+        }
+        StmtKind::Circle(CircleDecl::CenterRadius { center, radius }) => {
+            format!("C:{};{}", center, unicode_expr(radius))
+        }
+        StmtKind::Relation(lhs, op, rhs) => {
+            format!("{}{}{}", unicode_expr(lhs), op, unicode_expr(rhs))
+        }
+        StmtKind::Query(expr) => format!("{}?", unicode_expr(expr)),
+        StmtKind::Value(expr) => unicode_expr(expr),
+        StmtKind::ProveQuery(inner) => format!("{}\\?", unicode(inner)),
+        _ => stmt.to_string(),
+    }
+}
+
+fn unicode_expr(expr: &Expr) -> String {
+    match expr {
+        Expr::Angle(obj) => format!("∠{}", obj),
+        Expr::Constant(c) => match c.as_str() {
+            "\\P" => "π".to_string(),
+            "\\T" => "τ".to_string(),
+            "\\G" => "φ".to_string(),
+            other => other.to_string(),
+        },
+        Expr::Binary(lhs, op, rhs) => {
+            format!("{}{}{}", unicode_expr(lhs), op, unicode_expr(rhs))
+        }
+        Expr::Product(lhs, rhs) => format!("{}{}", unicode_expr(lhs), unicode_expr(rhs)),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,336 @@
+//! Export to GeoGebra input-bar commands. Statements GeoGebra has no command
+//! for (relations, queries, proof steps) become `#` comments so nothing is lost.
+
+use crate::ast::*;
+use crate::eval::{circle_key, format_number, Scene};
+use crate::translator::Translation;
+
+/// One GeoGebra command (or comment) per line, in construction order.
+pub fn translation(translation: &Translation) -> String {
+    let statements: Vec<&Statement> = translation.steps.iter().map(|s| &s.statement).collect();
+    let scene = Scene::evaluate(statements.iter().copied());
+    let mut export = Export {
+        scene: &scene,
+        lines: Vec::new(),
+    };
+    for step in &translation.steps {
+        export.statement(&step.statement, &step.english);
+    }
+    let mut out = export.lines.join("\n");
+    out.push('\n');
+    out
+}
+
+struct Export<'a> {
+    scene: &'a Scene,
+    lines: Vec<String>,
+}
+
+/// The GeoGebra label for a circle with the given key.
+fn circle_label(key: &str) -> String {
+    format!("c_{{{}}}", key)
+}
+
+fn args(points: &[String]) -> String {
+    points.join(", ")
+}
+
+/// A GeoGebra expression for an object used as an argument.
+fn object(obj: &Object) -> String {
+    match obj.kind {
+        ObjectKind::Circle => circle_label(&circle_key(&obj.points)),
+        ObjectKind::Line => format!("Line({})", args(&obj.points)),
+        ObjectKind::Ray => format!("Ray({})", args(&obj.points)),
+        ObjectKind::Arc if obj.points.len() == 2 => {
+            format!("Segment({})", args(&obj.points))
+        }
+        _ => match obj.points.len() {
+            1 => obj.points[0].clone(),
+            2 => format!("Segment({})", args(&obj.points)),
+            _ => format!("Polygon({})", args(&obj.points)),
+        },
+    }
+}
+
+fn expr(e: &Expr) -> Option<String> {
+    Some(match e {
+        Expr::Number(n) => n.clone(),
+        Expr::Variable(v) => v.clone(),
+        Expr::Constant(c) => match c.as_str() {
+            "\\P" | "π" => "pi".to_string(),
+            "\\T" | "τ" => "2pi".to_string(),
+            "\\G" | "φ" => "(1 + sqrt(5)) / 2".to_string(),
+            _ => return None,
+        },
+        Expr::Object(obj) if obj.kind == ObjectKind::Figure && obj.points.len() == 2 => {
+            format!("Distance({})", args(&obj.points))
+        }
+        Expr::Binary(l, op, r) => format!("({}) {} ({})", expr(l)?, op, expr(r)?),
+        Expr::Product(l, r) => format!("({}) * ({})", expr(l)?, expr(r)?),
+        _ => return None,
+    })
+}
+
+/// Vertices of the triangle a derived construction is built on.
+fn triangle(obj: &Object) -> Option<(&str, &str, &str)> {
+    match &obj.points[..] {
+        [a, b, c] => Some((a, b, c)),
+        _ => None,
+    }
+}
+
+impl Export<'_> {
+    fn push(&mut self, line: String) {
+        self.lines.push(line);
+    }
+
+    fn assign(&mut self, target: Option<&str>, command: String) {
+        match target {
+            Some(target) => self.push(format!("{}={}", target, command)),
+            None => self.push(command),
+        }
+    }
+
+    fn comment(&mut self, stmt: &Statement, english: &str) {
+        self.push(format!("# {}: {}", stmt, english.replace('\n', " ")));
+    }
+
+    fn free_point(&mut self, name: &str) {
+        let at = self
+            .scene
+            .point(name)
+            .unwrap_or(crate::geometry::Point::new(0.0, 0.0));
+        self.push(format!(
+            "{}=({}, {})",
+            name,
+            format_number(at.x),
+            format_number(at.y)
+        ));
+    }
+
+    fn statement(&mut self, stmt: &Statement, english: &str) {
+        match &stmt.kind {
+            StmtKind::Point(decl) => self.point(decl, english),
+            StmtKind::Linear(kind, objects) => {
+                let (prefix, command) = match kind {
+                    LinearKind::Segment => ("s", "Segment"),
+                    LinearKind::Line => ("l", "Line"),
+                    LinearKind::Ray => ("r", "Ray"),
+                };
+                for obj in objects {
+                    for pair in obj.points.windows(2) {
+                        self.push(format!(
+                            "{}_{{{}}}={}({})",
+                            prefix,
+                            pair.concat(),
+                            command,
+                            args(pair)
+                        ));
+                    }
+                }
+            }
+            StmtKind::Polygon { objects, .. } => {
+                for obj in objects {
+                    self.push(format!(
+                        "poly_{{{}}}=Polygon({})",
+                        obj.name(),
+                        args(&obj.points)
+                    ));
+                }
+            }
+            StmtKind::Circle(decl) => match decl {
+                CircleDecl::ThroughPoints(points) => self.push(format!(
+                    "{}=Circle({})",
+                    circle_label(&circle_key(points)),
+                    args(points)
+                )),
+                CircleDecl::CenterRadius { center, radius } => match expr(radius) {
+                    Some(radius) => self.push(format!(
+                        "{}=Circle({}, {})",
+                        circle_label(center),
+                        center,
+                        radius
+                    )),
+                    None => self.comment(stmt, english),
+                },
+                CircleDecl::CenterThrough { center, point } => self.push(format!(
+                    "{}=Circle({}, {})",
+                    circle_label(center),
+                    center,
+                    point
+                )),
+                CircleDecl::Through(_) | CircleDecl::Named(_) => self.comment(stmt, english),
+            },
+            StmtKind::RegularPolygon(poly) => self.regular_polygon(stmt, poly, english),
+            StmtKind::Derived { code, args, target } => {
+                if !self.derived(code, args, target.as_deref()) {
+                    self.comment(stmt, english);
+                }
+            }
+            StmtKind::Graph(equation) => self.push(equation.clone()),
+            _ => self.comment(stmt, english),
+        }
+    }
+
+    fn point(&mut self, decl: &PointDecl, english: &str) {
+        match decl {
+            PointDecl::Free(names) => {
+                for name in names {
+                    self.free_point(name);
+                }
+            }
+            PointDecl::Coordinates { name, coords } => {
+                let parts: Vec<&str> = coords.split(',').map(str::trim).collect();
+                if parts.len() == 2 {
+                    self.push(format!("{}=({}, {})", name, parts[0], parts[1]));
+                } else {
+                    self.free_point(name);
+                }
+            }
+            PointDecl::OnObject {
+                name,
+                object: obj,
+                conditions,
+            } => {
+                self.push(format!("{}=Point({})", name, object(obj)));
+                for condition in conditions {
+                    self.statement(condition, &condition.to_string());
+                }
+            }
+            PointDecl::InRegion { name, region } => {
+                self.push(format!("{}=PointIn({})", name, object(region)));
+            }
+            PointDecl::Intersection { name, objects } => {
+                let parts: Vec<String> = objects.iter().map(object).collect();
+                if parts.len() == 2 {
+                    self.push(format!("{}=Intersect({})", name, parts.join(", ")));
+                } else {
+                    self.push(format!("# P:{}: {}", decl, english));
+                }
+            }
+        }
+    }
+
+    /// `Polygon(A, B, n)` names its new vertices itself, so follow it with
+    /// `Vertex` commands that give them the problem's names.
+    fn regular_polygon(&mut self, stmt: &Statement, poly: &RegularPolygon, english: &str) {
+        let names = &poly.polygon.points;
+        let n = names.len();
+        let ([a, b], Ok(sides)) = (&poly.side.points[..], poly.sides.parse::<usize>()) else {
+            self.comment(stmt, english);
+            return;
+        };
+        let start = names.iter().position(|p| p == a);
+        let order: Vec<usize> = match start {
+            Some(i) if names[(i + 1) % n] == *b => (0..n).map(|k| (i + k) % n).collect(),
+            Some(i) if names[(i + n - 1) % n] == *b => (0..n).map(|k| (i + n - k) % n).collect(),
+            _ => {
+                self.comment(stmt, english);
+                return;
+            }
+        };
+        let label = format!("poly_{{{}}}", poly.polygon.name());
+        self.push(format!("{}=Polygon({}, {}, {})", label, a, b, sides));
+        for (k, &index) in order.iter().enumerate().skip(2) {
+            self.push(format!("{}=Vertex({}, {})", names[index], label, k + 1));
+        }
+    }
+
+    /// Returns false for constructions GeoGebra cannot express.
+    fn derived(&mut self, code: &str, objects: &[Object], target: Option<&str>) -> bool {
+        let Some(first) = objects.first() else {
+            return false;
+        };
+        let points = &first.points;
+        let tri = triangle(first);
+        let command = match (code, tri) {
+            ("M", _) if points.len() == 2 => format!("Midpoint({})", args(points)),
+            ("PB", _) if points.len() == 2 => format!("PerpendicularBisector({})", args(points)),
+            ("AB", Some((a, b, c))) => match target {
+                Some(_) => format!("Intersect(AngleBisector({a}, {b}, {c}), Line({a}, {c}))"),
+                None => format!("AngleBisector({a}, {b}, {c})"),
+            },
+            ("EAB", Some((a, b, c))) => {
+                format!("PerpendicularLine({b}, AngleBisector({a}, {b}, {c}))")
+            }
+            ("ICO", Some((a, b, c))) => format!("TriangleCenter({a}, {b}, {c}, 1)"),
+            ("CT", Some((a, b, c))) => format!("TriangleCenter({a}, {b}, {c}, 2)"),
+            ("CT", None) => format!("Centroid(Polygon({}))", args(points)),
+            ("CCO", Some((a, b, c))) => format!("TriangleCenter({a}, {b}, {c}, 3)"),
+            ("OC", Some((a, b, c))) => format!("TriangleCenter({a}, {b}, {c}, 4)"),
+            ("9O", Some((a, b, c))) => format!("TriangleCenter({a}, {b}, {c}, 5)"),
+            ("ECO", Some((a, b, c))) => excenter(a, b, c),
+            ("CC" | "IC" | "EC" | "9C", Some((a, b, c))) => {
+                let command = match code {
+                    "CC" => format!("Circle({a}, {b}, {c})"),
+                    "IC" => format!("Incircle({a}, {b}, {c})"),
+                    "EC" => {
+                        let center = excenter(a, b, c);
+                        format!("Circle({center}, Distance({center}, Line({b}, {c})))")
+                    }
+                    _ => format!(
+                        "Circle(Midpoint({a}, {b}), Midpoint({b}, {c}), Midpoint({c}, {a}))"
+                    ),
+                };
+                let key = match target {
+                    Some(target) => target.to_string(),
+                    None => circle_key(points),
+                };
+                self.push(format!("{}={}", circle_label(&key), command));
+                return true;
+            }
+            ("MD", Some((a, b, c))) => {
+                self.push(format!("Segment({a}, Midpoint({b}, {c}))"));
+                if let Some(target) = target {
+                    self.push(format!("{}=Midpoint({b}, {c})", target));
+                }
+                return true;
+            }
+            ("PD" | "PL", _) => {
+                let (from, line) = match (&points[..], objects.get(1)) {
+                    ([p], Some(line)) => (
+                        p.clone(),
+                        object(&Object {
+                            kind: match line.kind {
+                                ObjectKind::Figure => ObjectKind::Line,
+                                kind => kind,
+                            },
+                            points: line.points.clone(),
+                        }),
+                    ),
+                    ([p, a, b], None) => (p.clone(), format!("Line({}, {})", a, b)),
+                    _ => return false,
+                };
+                if code == "PL" {
+                    self.push(format!("Line({}, {})", from, line));
+                    return true;
+                }
+                self.push(format!("PerpendicularLine({}, {})", from, line));
+                if let Some(target) = target {
+                    self.push(format!("{}=ClosestPoint({}, {})", target, line, from));
+                }
+                return true;
+            }
+            ("TG", _) => {
+                let ([p], Some(circle)) = (&points[..], objects.get(1)) else {
+                    return false;
+                };
+                self.push(format!("Tangent({}, {})", p, object(circle)));
+                if let Some(target) = target {
+                    self.push(format!("# tangent point {} is not exported", target));
+                }
+                return true;
+            }
+            _ => return false,
+        };
+        self.assign(target, command);
+        true
+    }
+}
+
+/// The excenter opposite `a`, where the external bisectors at `b` and `c` meet.
+fn excenter(a: &str, b: &str, c: &str) -> String {
+    format!(
+        "Intersect(PerpendicularLine({b}, AngleBisector({a}, {b}, {c})), PerpendicularLine({c}, AngleBisector({a}, {c}, {b})))"
+    )
+}
@@ -0,0 +1,311 @@
+//! Plane geometry on `f64` coordinates: the primitives the evaluator builds
+//! constructions from.
+
+use std::ops::{Add, Mul, Sub};
+
+/// Distances below this are treated as zero.
+pub const EPSILON: f64 = 1e-9;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Self {
+        Point { x, y }
+    }
+
+    pub fn dist(self, other: Point) -> f64 {
+        (self - other).norm()
+    }
+
+    pub fn norm(self) -> f64 {
+        self.x.hypot(self.y)
+    }
+
+    pub fn dot(self, other: Point) -> f64 {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// The z component of the cross product.
+    pub fn cross(self, other: Point) -> f64 {
+        self.x * other.y - self.y * other.x
+    }
+
+    pub fn midpoint(self, other: Point) -> Point {
+        (self + other) * 0.5
+    }
+
+    /// The point a fraction `t` of the way from `self` to `other`.
+    pub fn lerp(self, other: Point, t: f64) -> Point {
+        self + (other - self) * t
+    }
+
+    /// Rotate counterclockwise about the origin by `angle` radians.
+    pub fn rotate(self, angle: f64) -> Point {
+        let (sin, cos) = angle.sin_cos();
+        Point::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
+
+    /// Whether both coordinates are finite, which points from a degenerate
+    /// construction may not be.
+    pub fn is_finite(self) -> bool {
+        self.x.is_finite() && self.y.is_finite()
+    }
+
+    pub fn unit(self) -> Point {
+        self * (1.0 / self.norm())
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<f64> for Point {
+    type Output = Point;
+    fn mul(self, k: f64) -> Point {
+        Point::new(self.x * k, self.y * k)
+    }
+}
+
+/// The infinite line through two distinct points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Line {
+    pub a: Point,
+    pub b: Point,
+}
+
+impl Line {
+    pub fn new(a: Point, b: Point) -> Self {
+        Line { a, b }
+    }
+
+    pub fn direction(&self) -> Point {
+        self.b - self.a
+    }
+
+    /// The foot of the perpendicular from `p`.
+    pub fn foot(&self, p: Point) -> Point {
+        let d = self.direction();
+        self.a + d * ((p - self.a).dot(d) / d.dot(d))
+    }
+
+    /// The line through `p` parallel to this one.
+    pub fn parallel_through(&self, p: Point) -> Line {
+        Line::new(p, p + self.direction())
+    }
+
+    /// The line through `p` perpendicular to this one.
+    pub fn perpendicular_through(&self, p: Point) -> Line {
+        let d = self.direction();
+        Line::new(p, p + Point::new(-d.y, d.x))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Circle {
+    pub center: Point,
+    pub radius: f64,
+}
+
+impl Circle {
+    pub fn new(center: Point, radius: f64) -> Self {
+        Circle { center, radius }
+    }
+
+    /// The point on the circle at `angle` radians from the positive x axis.
+    pub fn at(&self, angle: f64) -> Point {
+        self.center + Point::new(angle.cos(), angle.sin()) * self.radius
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        (self.center.dist(p) - self.radius).abs() < 1e-6 * self.radius.max(1.0)
+    }
+}
+
+/// The perpendicular bisector of `ab`.
+pub fn perpendicular_bisector(a: Point, b: Point) -> Line {
+    Line::new(a, b).perpendicular_through(a.midpoint(b))
+}
+
+/// The internal bisector of angle `abc`.
+pub fn angle_bisector(a: Point, b: Point, c: Point) -> Line {
+    Line::new(b, b + (a - b).unit() + (c - b).unit())
+}
+
+/// The external bisector of angle `abc`.
+pub fn exterior_angle_bisector(a: Point, b: Point, c: Point) -> Line {
+    angle_bisector(a, b, c).perpendicular_through(b)
+}
+
+/// Where two lines cross, or `None` if they are parallel.
+pub fn intersect_lines(l: &Line, m: &Line) -> Option<Point> {
+    let d = l.direction();
+    let e = m.direction();
+    let denom = d.cross(e);
+    if denom.abs() < EPSILON * d.norm() * e.norm() {
+        return None;
+    }
+    let t = (m.a - l.a).cross(e) / denom;
+    Some(l.a + d * t)
+}
+
+/// Where a line meets a circle, in order along the line.
+pub fn intersect_line_circle(l: &Line, c: &Circle) -> Vec<Point> {
+    let foot = l.foot(c.center);
+    let h = c.center.dist(foot);
+    if h > c.radius + EPSILON {
+        return Vec::new();
+    }
+    let half = (c.radius * c.radius - h * h).max(0.0).sqrt();
+    if half < EPSILON {
+        return vec![foot];
+    }
+    let u = l.direction().unit();
+    vec![foot - u * half, foot + u * half]
+}
+
+/// Where two circles meet.
+pub fn intersect_circles(c: &Circle, k: &Circle) -> Vec<Point> {
+    let d = c.center.dist(k.center);
+    if d < EPSILON || d > c.radius + k.radius + EPSILON || d < (c.radius - k.radius).abs() - EPSILON
+    {
+        return Vec::new();
+    }
+    let a = (c.radius * c.radius - k.radius * k.radius + d * d) / (2.0 * d);
+    let h = (c.radius * c.radius - a * a).max(0.0).sqrt();
+    let u = (k.center - c.center) * (1.0 / d);
+    let base = c.center + u * a;
+    if h < EPSILON {
+        return vec![base];
+    }
+    let n = Point::new(-u.y, u.x);
+    vec![base + n * h, base - n * h]
+}
+
+/// Twice the signed area of `abc`; positive when counterclockwise.
+fn orient(a: Point, b: Point, c: Point) -> f64 {
+    (b - a).cross(c - a)
+}
+
+fn degenerate(a: Point, b: Point, c: Point) -> bool {
+    // Three coincident points have no scale to compare against, and are
+    // degenerate all the same
+    let scale = a.dist(b).max(b.dist(c)).max(c.dist(a));
+    orient(a, b, c).abs() <= EPSILON * scale * scale
+}
+
+pub fn centroid(points: &[Point]) -> Point {
+    let sum = points.iter().fold(Point::new(0.0, 0.0), |acc, &p| acc + p);
+    sum * (1.0 / points.len() as f64)
+}
+
+pub fn circumcenter(a: Point, b: Point, c: Point) -> Option<Point> {
+    if degenerate(a, b, c) {
+        return None;
+    }
+    intersect_lines(&perpendicular_bisector(a, b), &perpendicular_bisector(b, c))
+}
+
+pub fn circumcircle(a: Point, b: Point, c: Point) -> Option<Circle> {
+    let o = circumcenter(a, b, c)?;
+    Some(Circle::new(o, o.dist(a)))
+}
+
+pub fn incenter(a: Point, b: Point, c: Point) -> Option<Point> {
+    if degenerate(a, b, c) {
+        return None;
+    }
+    let (la, lb, lc) = (b.dist(c), c.dist(a), a.dist(b));
+    Some((a * la + b * lb + c * lc) * (1.0 / (la + lb + lc)))
+}
+
+pub fn incircle(a: Point, b: Point, c: Point) -> Option<Circle> {
+    let i = incenter(a, b, c)?;
+    Some(Circle::new(i, i.dist(Line::new(a, b).foot(i))))
+}
+
+/// The excenter opposite `a`.
+pub fn excenter(a: Point, b: Point, c: Point) -> Option<Point> {
+    if degenerate(a, b, c) {
+        return None;
+    }
+    let (la, lb, lc) = (b.dist(c), c.dist(a), a.dist(b));
+    Some((b * lb + c * lc - a * la) * (1.0 / (lb + lc - la)))
+}
+
+/// The excircle opposite `a`.
+pub fn excircle(a: Point, b: Point, c: Point) -> Option<Circle> {
+    let e = excenter(a, b, c)?;
+    Some(Circle::new(e, e.dist(Line::new(b, c).foot(e))))
+}
+
+pub fn orthocenter(a: Point, b: Point, c: Point) -> Option<Point> {
+    let o = circumcenter(a, b, c)?;
+    Some(a + b + c - o * 2.0)
+}
+
+pub fn nine_point_center(a: Point, b: Point, c: Point) -> Option<Point> {
+    let o = circumcenter(a, b, c)?;
+    let h = orthocenter(a, b, c)?;
+    Some(o.midpoint(h))
+}
+
+pub fn nine_point_circle(a: Point, b: Point, c: Point) -> Option<Circle> {
+    let n = nine_point_center(a, b, c)?;
+    Some(Circle::new(n, n.dist(a.midpoint(b))))
+}
+
+/// The vertices of a regular polygon with `n` sides that starts with the
+/// side `ab` and turns counterclockwise.
+pub fn regular_polygon(a: Point, b: Point, n: usize) -> Vec<Point> {
+    let turn = std::f64::consts::TAU / n as f64;
+    let mut vertices = vec![a, b];
+    while vertices.len() < n {
+        let k = vertices.len();
+        let edge = vertices[k - 1] - vertices[k - 2];
+        vertices.push(vertices[k - 1] + edge.rotate(turn));
+    }
+    vertices.truncate(n);
+    vertices
+}
+
+/// Area of a simple polygon by the shoelace formula.
+pub fn polygon_area(points: &[Point]) -> f64 {
+    let n = points.len();
+    let twice: f64 = (0..n).map(|i| points[i].cross(points[(i + 1) % n])).sum();
+    twice.abs() / 2.0
+}
+
+pub fn polygon_perimeter(points: &[Point]) -> f64 {
+    let n = points.len();
+    (0..n).map(|i| points[i].dist(points[(i + 1) % n])).sum()
+}
+
+/// The angle `abc` at `b`, in degrees between 0 and 180.
+pub fn angle(a: Point, b: Point, c: Point) -> f64 {
+    let u = a - b;
+    let v = c - b;
+    u.cross(v).abs().atan2(u.dot(v)).to_degrees()
+}
+
+/// The angle from `a` counterclockwise around `b` to `c`, in degrees from 0
+/// up to 360.
+pub fn turn(a: Point, b: Point, c: Point) -> f64 {
+    let u = a - b;
+    let v = c - b;
+    u.cross(v).atan2(u.dot(v)).to_degrees().rem_euclid(360.0)
+}
@@ -0,0 +1,267 @@
+//! `\include "file.gsl"` lines, which bring in setups shared between files.
+//!
+//! Includes are read when a file is loaded, before anything is parsed. The
+//! text of an included file is placed on its own lines right after the
+//! `\include` naming it, so its definitions and statements apply to what
+//! follows; a file named a second time is not read again. A name is looked
+//! for next to the file that includes it, then in each directory of the
+//! search path.
+//!
+//! [`Source`] remembers which file each part of the combined text came
+//! from, so a diagnostic is shown in the file it is about, followed by the
+//! chain of includes that brought that file in.
+
+use crate::diagnostic::{Diagnostic, SourceFile};
+use crate::lexer::{self, Span, TokenKind};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The environment variable listing directories to search for included
+/// files, separated like `PATH`.
+pub const PATH_VAR: &str = "GSL_PATH";
+
+/// `dirs`, then the directories in `GSL_PATH`.
+pub fn search_path(dirs: &[PathBuf]) -> Vec<PathBuf> {
+    let mut path = dirs.to_vec();
+    if let Some(var) = env::var_os(PATH_VAR) {
+        path.extend(env::split_paths(&var).filter(|dir| !dir.as_os_str().is_empty()));
+    }
+    path
+}
+
+struct File {
+    name: String,
+    text: String,
+    /// The canonical path, to recognise the file when it is named again.
+    path: Option<PathBuf>,
+    /// The file and span of the `\include` that brought it in.
+    included_at: Option<(usize, Span)>,
+}
+
+/// A run of the combined text copied from one file.
+struct Piece {
+    /// Where it starts in the combined text.
+    start: usize,
+    file: usize,
+    /// Where it starts in its file.
+    offset: usize,
+}
+
+/// A file with everything it includes read in.
+pub struct Source {
+    /// The combined text, ready to translate.
+    pub text: String,
+    /// Includes that could not be read, with spans into `text`.
+    pub diagnostics: Vec<Diagnostic>,
+    files: Vec<File>,
+    pieces: Vec<Piece>,
+}
+
+impl Source {
+    /// Read in the includes of `text`, which is called `name` and was read
+    /// from `path`, if it came from a file. Names not found next to the file
+    /// naming them are looked for in `search_path`.
+    pub fn new(name: &str, text: &str, path: Option<&Path>, search_path: &[PathBuf]) -> Self {
+        let mut source = Source {
+            text: String::new(),
+            diagnostics: Vec::new(),
+            files: vec![File {
+                name: name.to_string(),
+                text: text.to_string(),
+                path: path.and_then(|p| p.canonicalize().ok()),
+                included_at: None,
+            }],
+            pieces: Vec::new(),
+        };
+        let dir = path.and_then(Path::parent).unwrap_or(Path::new(""));
+        source.splice(0, dir, &mut vec![0], search_path);
+        source
+    }
+
+    /// Copy file `file` into the combined text, reading in what it includes.
+    /// `stack` holds the files being read, to catch an include cycle.
+    fn splice(&mut self, file: usize, dir: &Path, stack: &mut Vec<usize>, search_path: &[PathBuf]) {
+        let text = self.files[file].text.clone();
+        let mut copied = 0;
+        for (span, name) in includes(&text) {
+            self.copy(file, copied, span.end, &text);
+            copied = span.end;
+            let at = Span::new(self.text.len() - (span.end - span.start), self.text.len());
+
+            let Some(path) = find(&name, dir, search_path) else {
+                let mut places: Vec<String> = vec![dir_name(dir)];
+                places.extend(search_path.iter().map(|d| dir_name(d)));
+                self.diagnostics.push(
+                    Diagnostic::error(format!("cannot find `{}`", name), at).with_hint(format!(
+                        "looked in {}; add directories to search with -I or {}",
+                        places.join(", "),
+                        PATH_VAR
+                    )),
+                );
+                continue;
+            };
+            let canonical = path.canonicalize().ok();
+            let same = |f: &File| f.path.is_some() && f.path == canonical;
+            if let Some(i) = stack.iter().position(|&f| same(&self.files[f])) {
+                let mut cycle: Vec<&str> = stack[i..]
+                    .iter()
+                    .map(|&f| self.files[f].name.as_str())
+                    .collect();
+                let shown = path.display().to_string();
+                cycle.push(&shown);
+                self.diagnostics.push(Diagnostic::error(
+                    format!("include cycle: {}", cycle.join(" → ")),
+                    at,
+                ));
+                continue;
+            }
+            if self.files.iter().any(same) {
+                continue;
+            }
+            let contents = match fs::read_to_string(&path) {
+                Ok(contents) => contents,
+                Err(e) => {
+                    self.diagnostics.push(Diagnostic::error(
+                        format!("cannot read `{}`: {}", name, e),
+                        at,
+                    ));
+                    continue;
+                }
+            };
+
+            self.files.push(File {
+                name: path.display().to_string(),
+                text: contents,
+                path: canonical,
+                included_at: Some((file, span)),
+            });
+            let included = self.files.len() - 1;
+            self.text.push('\n');
+            stack.push(included);
+            let dir = path.parent().unwrap_or(Path::new(""));
+            self.splice(included, dir, stack, search_path);
+            stack.pop();
+            self.text.push('\n');
+        }
+        self.copy(file, copied, text.len(), &text);
+    }
+
+    fn copy(&mut self, file: usize, start: usize, end: usize, text: &str) {
+        self.pieces.push(Piece {
+            start: self.text.len(),
+            file,
+            offset: start,
+        });
+        self.text.push_str(&text[start..end]);
+    }
+
+    /// The file a span of the combined text is in, and where it is there.
+    fn locate(&self, span: Span) -> (usize, Span) {
+        let i = self
+            .pieces
+            .partition_point(|p| p.start <= span.start)
+            .saturating_sub(1);
+        let Some(piece) = self.pieces.get(i) else {
+            return (0, span);
+        };
+        let len = self.files[piece.file].text.len();
+        let start = (piece.offset + span.start - piece.start).min(len);
+        let end = (start + span.end - span.start).min(len);
+        (piece.file, Span::new(start, end))
+    }
+
+    /// The name of the loaded file.
+    pub fn name(&self) -> &str {
+        &self.files[0].name
+    }
+
+    /// `span` as a span of the loaded file: anything in an included file is
+    /// placed on the `\include` that led to it.
+    pub fn root_span(&self, span: Span) -> Span {
+        let (mut file, mut span) = self.locate(span);
+        while let Some((parent, at)) = self.files[file].included_at {
+            (file, span) = (parent, at);
+        }
+        span
+    }
+
+    /// Whether `span` is in the loaded file itself rather than one it
+    /// includes.
+    pub fn is_root(&self, span: Span) -> bool {
+        self.locate(span).0 == 0
+    }
+
+    /// `diagnostic` with its spans moved into the loaded file, as
+    /// [`Source::root_span`] does, saying which file it is about when that
+    /// is an included one.
+    pub fn root_diagnostic(&self, diagnostic: &Diagnostic) -> Diagnostic {
+        let mut diagnostic = diagnostic.clone();
+        let (file, _) = self.locate(diagnostic.span);
+        if file != 0 {
+            diagnostic.message = format!("{} (in `{}`)", diagnostic.message, self.files[file].name);
+        }
+        diagnostic.span = self.root_span(diagnostic.span);
+        for note in &mut diagnostic.notes {
+            note.span = self.root_span(note.span);
+        }
+        diagnostic
+    }
+
+    /// Render a diagnostic in the file it is about, followed by the include
+    /// chain that led there and any notes, each in its own file.
+    pub fn render(&self, diagnostic: &Diagnostic) -> String {
+        let (file, span) = self.locate(diagnostic.span);
+        let mut out = self.source_file(file).render(&Diagnostic {
+            span,
+            notes: Vec::new(),
+            ..diagnostic.clone()
+        });
+        let mut current = file;
+        while let Some((parent, at)) = self.files[current].included_at {
+            let message = format!("`{}` is included here", self.files[current].name);
+            out.push_str(&self.source_file(parent).render_note(&message, at));
+            current = parent;
+        }
+        for note in &diagnostic.notes {
+            let (file, span) = self.locate(note.span);
+            out.push_str(&self.source_file(file).render_note(&note.message, span));
+        }
+        out
+    }
+
+    fn source_file(&self, file: usize) -> SourceFile<'_> {
+        SourceFile::new(&self.files[file].name, &self.files[file].text)
+    }
+}
+
+/// Each `\include "name"` in `text`, with its span and the name.
+fn includes(text: &str) -> Vec<(Span, String)> {
+    let tokens = lexer::tokenize(text);
+    tokens
+        .windows(2)
+        .filter_map(|pair| match (&pair[0].kind, &pair[1].kind) {
+            (TokenKind::Command(command), TokenKind::Quoted(name)) if command == "include" => {
+                Some((pair[0].span.to(pair[1].span), name.clone()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// The first place `name` is found: next to the including file, then along
+/// the search path.
+fn find(name: &str, dir: &Path, search_path: &[PathBuf]) -> Option<PathBuf> {
+    std::iter::once(dir)
+        .chain(search_path.iter().map(PathBuf::as_path))
+        .map(|dir| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+fn dir_name(dir: &Path) -> String {
+    if dir.as_os_str().is_empty() {
+        ".".to_string()
+    } else {
+        dir.display().to_string()
+    }
+}
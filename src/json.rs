@@ -0,0 +1,625 @@
+//! JSON rendering of translations, for tools that want gslc's reading of each
+//! statement rather than its prose.
+
+use crate::ast::*;
+use crate::eval::format_number;
+use crate::translator::{Problem, Step, Translation};
+use std::fmt;
+
+/// A JSON value. Object keys keep their insertion order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    /// A number, kept in the spelling it was written with.
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn object<K: Into<String>>(fields: impl IntoIterator<Item = (K, Json)>) -> Json {
+        Json::Object(fields.into_iter().map(|(k, v)| (k.into(), v)).collect())
+    }
+
+    pub fn array<T>(items: impl IntoIterator<Item = T>, f: impl Fn(T) -> Json) -> Json {
+        Json::Array(items.into_iter().map(f).collect())
+    }
+
+    /// Parse a complete JSON text.
+    pub fn parse(text: &str) -> Result<Json, String> {
+        let mut reader = Reader {
+            chars: text.chars().collect(),
+            pos: 0,
+        };
+        let value = reader.value()?;
+        reader.skip_whitespace();
+        match reader.peek() {
+            None => Ok(value),
+            Some(c) => Err(format!("unexpected `{}` after the value", c)),
+        }
+    }
+
+    /// The value of `key`, if this is an object that has it.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_usize(&self) -> Option<usize> {
+        match self {
+            Json::Number(n) => n.parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn write(&self, f: &mut fmt::Formatter, indent: usize) -> fmt::Result {
+        let pad = "  ".repeat(indent + 1);
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Number(n) => f.write_str(n),
+            Json::String(s) => write_string(f, s),
+            Json::Array(items) if items.is_empty() => f.write_str("[]"),
+            Json::Array(items) => {
+                f.write_str("[\n")?;
+                for (i, item) in items.iter().enumerate() {
+                    f.write_str(&pad)?;
+                    item.write(f, indent + 1)?;
+                    f.write_str(if i + 1 < items.len() { ",\n" } else { "\n" })?;
+                }
+                write!(f, "{}]", "  ".repeat(indent))
+            }
+            Json::Object(fields) if fields.is_empty() => f.write_str("{}"),
+            Json::Object(fields) => {
+                f.write_str("{\n")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    f.write_str(&pad)?;
+                    write_string(f, key)?;
+                    f.write_str(": ")?;
+                    value.write(f, indent + 1)?;
+                    f.write_str(if i + 1 < fields.len() { ",\n" } else { "\n" })?;
+                }
+                write!(f, "{}}}", "  ".repeat(indent))
+            }
+        }
+    }
+}
+
+/// Pretty-printed with two-space indentation.
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Json {
+        Json::String(s.to_string())
+    }
+}
+
+impl From<&String> for Json {
+    fn from(s: &String) -> Json {
+        Json::String(s.clone())
+    }
+}
+
+impl From<bool> for Json {
+    fn from(b: bool) -> Json {
+        Json::Bool(b)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(n: usize) -> Json {
+        Json::Number(n.to_string())
+    }
+}
+
+struct Reader {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Reader {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self
+            .peek()
+            .is_some_and(|c| matches!(c, ' ' | '\t' | '\n' | '\r'))
+        {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, word: &str) -> Result<(), String> {
+        for expected in word.chars() {
+            if self.next() != Some(expected) {
+                return Err(format!("expected `{}`", word));
+            }
+        }
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('n') => self.expect("null").map(|_| Json::Null),
+            Some('t') => self.expect("true").map(|_| Json::Bool(true)),
+            Some('f') => self.expect("false").map(|_| Json::Bool(false)),
+            Some('"') => self.string().map(Json::String),
+            Some('[') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some(']') {
+                    self.pos += 1;
+                    return Ok(Json::Array(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    self.skip_whitespace();
+                    match self.next() {
+                        Some(',') => continue,
+                        Some(']') => return Ok(Json::Array(items)),
+                        _ => return Err("expected `,` or `]` in array".to_string()),
+                    }
+                }
+            }
+            Some('{') => {
+                self.pos += 1;
+                let mut fields = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some('}') {
+                    self.pos += 1;
+                    return Ok(Json::Object(fields));
+                }
+                loop {
+                    self.skip_whitespace();
+                    if self.peek() != Some('"') {
+                        return Err("expected a string key in object".to_string());
+                    }
+                    let key = self.string()?;
+                    self.skip_whitespace();
+                    self.expect(":")?;
+                    fields.push((key, self.value()?));
+                    self.skip_whitespace();
+                    match self.next() {
+                        Some(',') => continue,
+                        Some('}') => return Ok(Json::Object(fields)),
+                        _ => return Err("expected `,` or `}` in object".to_string()),
+                    }
+                }
+            }
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                let start = self.pos;
+                while self
+                    .peek()
+                    .is_some_and(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+                {
+                    self.pos += 1;
+                }
+                let number: String = self.chars[start..self.pos].iter().collect();
+                match number.parse::<f64>() {
+                    Ok(_) => Ok(Json::Number(number)),
+                    Err(_) => Err(format!("invalid number `{}`", number)),
+                }
+            }
+            Some(c) => Err(format!("unexpected `{}`", c)),
+            None => Err("unexpected end of input".to_string()),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.pos += 1;
+        let mut out = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(out),
+                Some('\\') => match self.next() {
+                    Some('"') => out.push('"'),
+                    Some('\\') => out.push('\\'),
+                    Some('/') => out.push('/'),
+                    Some('b') => out.push('\u{8}'),
+                    Some('f') => out.push('\u{c}'),
+                    Some('n') => out.push('\n'),
+                    Some('r') => out.push('\r'),
+                    Some('t') => out.push('\t'),
+                    Some('u') => {
+                        let high = self.hex4()?;
+                        let code = if (0xD800..0xDC00).contains(&high) {
+                            self.expect("\\u")?;
+                            let low = self.hex4()?;
+                            0x10000 + ((high - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF)
+                        } else {
+                            high
+                        };
+                        out.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+                    }
+                    _ => return Err("invalid escape in string".to_string()),
+                },
+                Some(c) => out.push(c),
+                None => return Err("unterminated string".to_string()),
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32, String> {
+        let digits: String = (0..4).filter_map(|_| self.next()).collect();
+        u32::from_str_radix(&digits, 16).map_err(|_| format!("invalid escape `\\u{}`", digits))
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    f.write_str("\"")
+}
+
+/// One object per translated step, in order.
+pub fn translation(translation: &Translation) -> Json {
+    Json::array(&translation.steps, step)
+}
+
+/// One object per problem in a file, with its number, id, metadata and steps.
+/// Metadata the file does not give is left out.
+pub fn problems(problems: &[Problem]) -> Json {
+    Json::array(problems, problem)
+}
+
+pub fn problem(problem: &Problem) -> Json {
+    let metadata = &problem.metadata;
+    let mut fields = Vec::new();
+    let text = [
+        ("title", &metadata.title),
+        ("source", &metadata.source),
+        ("difficulty", &metadata.difficulty),
+        ("answer", &metadata.answer),
+    ];
+    for (key, value) in text {
+        if let Some(value) = value {
+            fields.push((key, Json::from(value)));
+        }
+    }
+    if !metadata.tags.is_empty() {
+        fields.push(("tags", Json::array(&metadata.tags, Json::from)));
+    }
+    Json::object([
+        ("problem", Json::from(problem.number)),
+        ("id", Json::from(&problem.id())),
+        ("metadata", Json::object(fields)),
+        ("steps", translation(&problem.translation)),
+    ])
+}
+
+/// The shorthand, statement kind, operands and English for one step, plus
+/// the numeric answer for queries (`null` when the figure does not fix it).
+pub fn step(step: &Step) -> Json {
+    let mut fields = vec![
+        ("source", Json::from(&step.source)),
+        ("kind", Json::from(step.statement.kind_name())),
+        ("operands", operands(&step.statement)),
+        ("english", Json::from(&step.english)),
+        ("depth", Json::from(step.depth)),
+    ];
+    if let Some(theorem) = &step.statement.theorem {
+        fields.push(("theorem", Json::from(theorem)));
+    }
+    if let Some(opened) = step.closes {
+        fields.push(("closes", Json::from(opened)));
+    }
+    if let StmtKind::Query(_) = step.statement.kind {
+        let answer = step
+            .answer
+            .filter(|v| v.is_finite())
+            .map_or(Json::Null, |v| Json::Number(format_number(v)));
+        fields.push(("answer", answer));
+    }
+    fields.push((
+        "span",
+        Json::object([
+            ("start", Json::from(step.statement.span.start)),
+            ("end", Json::from(step.statement.span.end)),
+        ]),
+    ));
+    Json::object(fields)
+}
+
+/// A statement nested inside another, such as the claim of `\p:`.
+pub fn statement(stmt: &Statement) -> Json {
+    Json::object([
+        ("source", Json::String(stmt.to_string())),
+        ("kind", Json::from(stmt.kind_name())),
+        ("operands", operands(stmt)),
+    ])
+}
+
+fn names(points: &[String]) -> Json {
+    Json::array(points, Json::from)
+}
+
+/// A number as written when JSON allows that spelling, rewritten when only
+/// GSL does (`.5` is `0.5`, `007` is `7`), and otherwise a string.
+fn number_or_string(text: &str) -> Json {
+    let text = text.trim();
+    if is_json_number(text) {
+        return Json::Number(text.to_string());
+    }
+    match text.parse::<f64>() {
+        Ok(value) if value.is_finite() && !text.starts_with('+') => Json::Number(value.to_string()),
+        _ => Json::from(text),
+    }
+}
+
+/// Whether `text` follows JSON's number grammar,
+/// `-?(0|[1-9][0-9]*)(.[0-9]+)?([eE][+-]?[0-9]+)?`.
+fn is_json_number(text: &str) -> bool {
+    fn digits(text: &str) -> (&str, &str) {
+        let end = text
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(text.len());
+        text.split_at(end)
+    }
+    let text = text.strip_prefix('-').unwrap_or(text);
+    let (int, rest) = digits(text);
+    if int.is_empty() || (int.len() > 1 && int.starts_with('0')) {
+        return false;
+    }
+    let rest = match rest.strip_prefix('.') {
+        Some(fraction) => match digits(fraction) {
+            ("", _) => return false,
+            (_, rest) => rest,
+        },
+        None => rest,
+    };
+    match rest.strip_prefix(['e', 'E']) {
+        Some(exponent) => {
+            let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+            matches!(digits(exponent), (d, "") if !d.is_empty())
+        }
+        None => rest.is_empty(),
+    }
+}
+
+pub fn object(obj: &Object) -> Json {
+    let kind = match obj.kind {
+        ObjectKind::Figure => match obj.points.len() {
+            1 => "point",
+            2 => "segment",
+            _ => "polygon",
+        },
+        ObjectKind::Ray => "ray",
+        ObjectKind::Line => "line",
+        ObjectKind::Circle => "circle",
+        ObjectKind::Arc => "arc",
+        ObjectKind::Sector => "sector",
+        ObjectKind::Triangle => "triangle",
+    };
+    Json::object([
+        ("type", Json::from(kind)),
+        ("name", Json::String(obj.name())),
+        ("points", names(&obj.points)),
+    ])
+}
+
+fn objects(objs: &[Object]) -> Json {
+    Json::array(objs, object)
+}
+
+pub fn expr(expr: &Expr) -> Json {
+    match expr {
+        Expr::Number(n) => Json::object([
+            ("type", Json::from("number")),
+            ("value", number_or_string(n)),
+        ]),
+        Expr::Variable(v) => {
+            Json::object([("type", Json::from("variable")), ("name", Json::from(v))])
+        }
+        Expr::Constant(c) => {
+            Json::object([("type", Json::from("constant")), ("name", Json::from(c))])
+        }
+        Expr::Object(obj) => object(obj),
+        Expr::Area(obj) | Expr::Perimeter(obj) | Expr::Angle(obj) => {
+            Json::object([("type", Json::from(expr.measure())), ("of", object(obj))])
+        }
+        Expr::Binary(lhs, op, rhs) => Json::object([
+            ("type", Json::from("arithmetic")),
+            ("op", Json::String(op.to_string())),
+            ("lhs", self::expr(lhs)),
+            ("rhs", self::expr(rhs)),
+        ]),
+        Expr::Product(lhs, rhs) => Json::object([
+            ("type", Json::from("product")),
+            ("lhs", self::expr(lhs)),
+            ("rhs", self::expr(rhs)),
+        ]),
+    }
+}
+
+fn optional(value: Option<&Statement>) -> Json {
+    value.map_or(Json::Null, statement)
+}
+
+/// The parts of a statement, keyed by what they mean.
+pub fn operands(stmt: &Statement) -> Json {
+    match &stmt.kind {
+        StmtKind::Prove(claim)
+        | StmtKind::ProveByContradiction(claim)
+        | StmtKind::ProveQuery(claim) => Json::object([("claim", statement(claim))]),
+        StmtKind::Marker(marker) => {
+            let name = match marker {
+                ProofMarker::Qed => "qed",
+                ProofMarker::Contradiction => "contradiction",
+                ProofMarker::Because => "because",
+                ProofMarker::Therefore => "therefore",
+            };
+            Json::object([("marker", Json::from(name))])
+        }
+        StmtKind::Logic(op, lhs, rhs) => {
+            let name = match op {
+                LogicOp::And => "and",
+                LogicOp::Or => "or",
+                LogicOp::Implies => "implies",
+            };
+            Json::object([
+                ("op", Json::from(name)),
+                ("lhs", statement(lhs)),
+                ("rhs", statement(rhs)),
+            ])
+        }
+        StmtKind::Quantified(quantifier, body) => {
+            let name = match quantifier {
+                Quantifier::ForAll => "for_all",
+                Quantifier::Exists => "exists",
+            };
+            Json::object([("quantifier", Json::from(name)), ("body", statement(body))])
+        }
+        StmtKind::Casework { main, cases } => Json::object([
+            ("main", optional(main.as_deref())),
+            (
+                "cases",
+                cases.as_ref().map_or(Json::Null, |cases| {
+                    Json::array(cases, |case| {
+                        Json::object([
+                            ("condition", statement(&case.condition)),
+                            ("result", statement(&case.result)),
+                        ])
+                    })
+                }),
+            ),
+        ]),
+        StmtKind::EndCasework => Json::Object(Vec::new()),
+        StmtKind::Define(Definition::Macro { name, params, body }) => Json::object([
+            ("name", Json::from(name)),
+            ("params", Json::array(params, Json::from)),
+            ("body", Json::array(body, statement)),
+        ]),
+        StmtKind::Define(
+            Definition::Construction { code, name }
+            | Definition::Property { code, name }
+            | Definition::Theorem { code, name },
+        ) => Json::object([("code", Json::from(code)), ("name", Json::from(name))]),
+        StmtKind::Include(path) => Json::object([("path", Json::from(path))]),
+        StmtKind::Derived { code, args, target } => Json::object([
+            ("code", Json::from(code)),
+            ("args", objects(args)),
+            ("target", target.as_ref().map_or(Json::Null, Json::from)),
+        ]),
+        StmtKind::Graph(equation) => Json::object([("equation", Json::from(equation))]),
+        StmtKind::Point(decl) => point_operands(decl),
+        StmtKind::Linear(_, objs) => Json::object([("objects", objects(objs))]),
+        StmtKind::Polygon {
+            objects: objs,
+            properties,
+        } => Json::object([
+            ("objects", objects(objs)),
+            ("properties", names(properties)),
+        ]),
+        StmtKind::Circle(decl) => match decl {
+            CircleDecl::ThroughPoints(points) => Json::object([("through", names(points))]),
+            CircleDecl::CenterRadius { center, radius } => {
+                Json::object([("center", Json::from(center)), ("radius", expr(radius))])
+            }
+            CircleDecl::CenterThrough { center, point } => Json::object([
+                ("center", Json::from(center)),
+                ("through", names(std::slice::from_ref(point))),
+            ]),
+            CircleDecl::Through(point) => {
+                Json::object([("through", names(std::slice::from_ref(point)))])
+            }
+            CircleDecl::Named(name) => Json::object([("name", Json::from(name))]),
+        },
+        StmtKind::RegularPolygon(poly) => Json::object([
+            ("sides", number_or_string(&poly.sides)),
+            ("side", object(&poly.side)),
+            ("polygon", object(&poly.polygon)),
+        ]),
+        StmtKind::Citation(code) => Json::object([("theorem", Json::from(code))]),
+        StmtKind::Relation(lhs, op, rhs) => Json::object([
+            ("lhs", expr(lhs)),
+            ("op", Json::String(op.to_string())),
+            ("rhs", expr(rhs)),
+        ]),
+        StmtKind::Query(target) => Json::object([("target", expr(target))]),
+        StmtKind::Value(value) => Json::object([("value", expr(value))]),
+        StmtKind::Property {
+            object: obj,
+            property,
+            ..
+        } => Json::object([("object", object(obj)), ("property", Json::from(property))]),
+        StmtKind::Relationship {
+            objects: objs,
+            relation,
+            ..
+        } => Json::object([
+            ("objects", objects(objs)),
+            ("relation", Json::from(relation)),
+        ]),
+        StmtKind::Orientation {
+            object: obj,
+            clockwise,
+        } => Json::object([
+            ("object", object(obj)),
+            ("clockwise", Json::from(*clockwise)),
+        ]),
+    }
+}
+
+fn point_operands(decl: &PointDecl) -> Json {
+    match decl {
+        PointDecl::Free(points) => Json::object([("points", names(points))]),
+        PointDecl::Coordinates { name, coords } => Json::object([
+            ("point", Json::from(name)),
+            (
+                "coordinates",
+                Json::array(coords.split(','), number_or_string),
+            ),
+        ]),
+        PointDecl::OnObject {
+            name,
+            object: obj,
+            conditions,
+        } => Json::object([
+            ("point", Json::from(name)),
+            ("on", object(obj)),
+            ("conditions", Json::array(conditions, statement)),
+        ]),
+        PointDecl::InRegion { name, region } => {
+            Json::object([("point", Json::from(name)), ("inside", object(region))])
+        }
+        PointDecl::Intersection {
+            name,
+            objects: objs,
+        } => Json::object([("point", Json::from(name)), ("of", objects(objs))]),
+    }
+}
@@ -0,0 +1,597 @@
+//! LaTeX rendering of translations: each step becomes an `enumerate` item with
+//! figures, measures and relations typeset in math mode.
+
+use crate::ast::*;
+use crate::proof;
+use crate::translator::{orientation_word, shape_name, Problem, Translation, Translator};
+
+const PREAMBLE: &str = "\\documentclass{article}
+\\usepackage{amsmath}
+\\usepackage{amssymb}
+
+\\begin{document}
+
+";
+
+/// Renders statements as LaTeX, looking names up in a translator's tables.
+pub struct Latex<'a> {
+    translator: &'a Translator,
+}
+
+impl<'a> Latex<'a> {
+    pub fn new(translator: &'a Translator) -> Self {
+        Latex { translator }
+    }
+
+    /// An `enumerate` environment with one item per step. The steps of a
+    /// proof go in an `enumerate` of their own, nested under it.
+    pub fn fragment(&self, translation: &Translation) -> String {
+        let translator = self.translator.with_definitions(&translation.definitions);
+        let latex = Latex::new(&translator);
+        let mut out = String::from("\\begin{enumerate}\n");
+        let mut depth = 0;
+        for step in &translation.steps {
+            while depth < step.depth {
+                depth += 1;
+                out.push_str(&format!("{}\\begin{{enumerate}}\n", "  ".repeat(depth)));
+            }
+            while depth > step.depth {
+                out.push_str(&format!("{}\\end{{enumerate}}\n", "  ".repeat(depth)));
+                depth -= 1;
+            }
+            let mut item = latex.statement(&step.statement);
+            let claim = step
+                .closes
+                .and_then(|i| proof::claim(&translation.steps[i].statement));
+            if let Some(claim) = claim {
+                item = format!(
+                    "{}, which proves that {}.",
+                    item.trim_end_matches('.'),
+                    latex.clause(claim)
+                );
+            }
+            out.push_str(&format!("{}  \\item {}\n", "  ".repeat(depth), item));
+        }
+        while depth > 0 {
+            out.push_str(&format!("{}\\end{{enumerate}}\n", "  ".repeat(depth)));
+            depth -= 1;
+        }
+        out.push_str("\\end{enumerate}\n");
+        out
+    }
+
+    /// A complete document that compiles on its own.
+    pub fn document(&self, translation: &Translation) -> String {
+        format!(
+            "{}{}\n\\end{{document}}\n",
+            PREAMBLE,
+            self.fragment(translation)
+        )
+    }
+
+    /// Several problems from one file, each under a heading with its
+    /// metadata.
+    pub fn problems(&self, problems: &[Problem]) -> String {
+        let mut out = Vec::new();
+        for problem in problems {
+            let mut section = format!("\\subsection*{{{}}}\n", text(&problem.heading()));
+            let details: Vec<String> = problem
+                .metadata
+                .details()
+                .iter()
+                .map(|(label, value)| format!("\\textbf{{{}:}} {}", label, text(value)))
+                .collect();
+            if !details.is_empty() {
+                section.push_str(&format!("{}\n\n", details.join(" \\quad ")));
+            }
+            section.push_str(&self.fragment(&problem.translation));
+            out.push(section);
+        }
+        out.join("\n")
+    }
+
+    /// A complete document holding several problems.
+    pub fn problems_document(&self, problems: &[Problem]) -> String {
+        format!(
+            "{}{}\n\\end{{document}}\n",
+            PREAMBLE,
+            self.problems(problems)
+        )
+    }
+
+    /// One statement as a sentence of text with inline math.
+    pub fn statement(&self, stmt: &Statement) -> String {
+        let sentence = self.sentence(stmt);
+        match &stmt.theorem {
+            Some(code) => self.translator.cite(&sentence, code, text),
+            None => sentence,
+        }
+    }
+
+    fn sentence(&self, stmt: &Statement) -> String {
+        let t = self.translator;
+        match &stmt.kind {
+            StmtKind::Prove(inner) => format!("We will prove: {}", self.statement(inner)),
+            StmtKind::ProveByContradiction(inner) => {
+                format!("We will prove by contradiction: {}", self.statement(inner))
+            }
+            StmtKind::Marker(ProofMarker::Qed) => "And that is what was to be shown.".to_string(),
+            StmtKind::Marker(ProofMarker::Contradiction) => {
+                "Achieving a contradiction.".to_string()
+            }
+            StmtKind::Marker(ProofMarker::Because) => "Because".to_string(),
+            StmtKind::Marker(ProofMarker::Therefore) => "Therefore".to_string(),
+            StmtKind::Logic(op, lhs, rhs) => {
+                let word = match op {
+                    LogicOp::Or => "or",
+                    LogicOp::And => "and",
+                    LogicOp::Implies => "implies",
+                };
+                format!("{} {} {}", self.clause(lhs), word, self.clause(rhs))
+            }
+            StmtKind::Quantified(Quantifier::ForAll, body) => {
+                format!("For all {}", self.clause(body))
+            }
+            StmtKind::Quantified(Quantifier::Exists, body) => {
+                format!("There exists {}", self.clause(body))
+            }
+            StmtKind::Casework { main, cases } => {
+                let main = main
+                    .as_ref()
+                    .map(|m| format!("{}\\\\\n  ", self.statement(m)))
+                    .unwrap_or_default();
+                match cases {
+                    Some(cases) => {
+                        let cases: Vec<String> = cases
+                            .iter()
+                            .enumerate()
+                            .map(|(i, case)| {
+                                format!(
+                                    "Case {}: if {}, then {}",
+                                    i + 1,
+                                    self.clause(&case.condition),
+                                    self.clause(&case.result)
+                                )
+                            })
+                            .collect();
+                        format!("{}Begin casework: {}", main, cases.join("; "))
+                    }
+                    None => format!("{}Begin casework.", main),
+                }
+            }
+            StmtKind::EndCasework => "End casework.".to_string(),
+            StmtKind::Define(Definition::Macro { name, params, body }) => {
+                let body: Vec<String> = body.iter().map(|s| self.statement(s)).collect();
+                format!(
+                    "Let \\texttt{{{}:{}}} stand for: {}",
+                    text(name),
+                    params.concat(),
+                    body.join(" ")
+                )
+            }
+            StmtKind::Define(_) | StmtKind::Include(_) => text(&t.translate_statement(stmt)),
+            StmtKind::Derived { code, args, target } => {
+                let args = self.list(args, ", ");
+                let name = text(t.derived_name(code));
+                match target {
+                    Some(target) => {
+                        format!(
+                            "Construct the {} {} of {}.",
+                            name,
+                            math(&point(target)),
+                            args
+                        )
+                    }
+                    None => format!("Construct the {} of {}.", name, args),
+                }
+            }
+            StmtKind::Graph(eq) => format!("Graph the function {}.", math(eq)),
+            StmtKind::Point(decl) => self.point(decl),
+            StmtKind::Linear(kind, objects) => {
+                let (singular, plural, verb) = match kind {
+                    LinearKind::Segment => ("segment", "segments", "Connect"),
+                    LinearKind::Line => ("line", "lines", "Connect"),
+                    LinearKind::Ray => ("ray", "rays", "Construct"),
+                };
+                let noun = if objects.len() == 1 { singular } else { plural };
+                let objects: Vec<String> =
+                    objects.iter().map(|o| math(&linear(*kind, o))).collect();
+                format!("{} {} {}.", verb, noun, objects.join(", "))
+            }
+            StmtKind::Polygon {
+                objects,
+                properties,
+            } => {
+                let noun = if objects.len() == 1 {
+                    "polygon"
+                } else {
+                    "polygons"
+                };
+                let mut words: Vec<String> = properties
+                    .iter()
+                    .map(|p| text(t.property_name(p)))
+                    .collect();
+                words.push(noun.to_string());
+                format!(
+                    "Construct {} {}.",
+                    words.join(" "),
+                    self.list(objects, ", ")
+                )
+            }
+            StmtKind::Circle(decl) => self.circle(decl),
+            StmtKind::RegularPolygon(poly) => format!("Construct {}.", self.regular(poly)),
+            StmtKind::Citation(code) => format!("By {}.", t.theorem_phrase(code, text)),
+            StmtKind::Relation(lhs, op, rhs) => self.relation(lhs, *op, rhs),
+            StmtKind::Query(expr) => self.query(expr),
+            StmtKind::Value(expr) => math(&self.expr(expr)),
+            StmtKind::ProveQuery(inner) => format!("Prove that {}.", self.clause(inner)),
+            StmtKind::Property {
+                object,
+                property,
+                question,
+            } => {
+                let name = text(t.property_name(property));
+                if *question {
+                    format!("Is {} {}?", math(&figure(object)), name)
+                } else {
+                    format!("{} is {}.", math(&figure(object)), name)
+                }
+            }
+            StmtKind::Relationship {
+                objects,
+                relation,
+                question,
+                ..
+            } => {
+                let claim = self.relationship(objects, relation);
+                if *question {
+                    format!("Is it true that {}?", claim)
+                } else {
+                    format!("{}.", capitalize(&claim))
+                }
+            }
+            StmtKind::Orientation { object, clockwise } => format!(
+                "{} goes {}.",
+                math(&figure(object)),
+                orientation_word(*clockwise)
+            ),
+        }
+    }
+
+    /// A statement used inside a sentence, without its closing punctuation.
+    fn clause(&self, stmt: &Statement) -> String {
+        match &stmt.kind {
+            StmtKind::RegularPolygon(poly) => self.regular(poly),
+            StmtKind::Relationship {
+                objects, relation, ..
+            } => self.relationship(objects, relation),
+            StmtKind::Polygon {
+                objects,
+                properties,
+            } if !properties.is_empty() => {
+                let names: Vec<String> = properties
+                    .iter()
+                    .map(|p| text(self.translator.property_name(p)))
+                    .collect();
+                format!(
+                    "polygon {} is {}",
+                    self.list(objects, ", "),
+                    names.join(" and ")
+                )
+            }
+            _ => self.statement(stmt).trim_end_matches('.').to_string(),
+        }
+    }
+
+    fn list(&self, objects: &[Object], sep: &str) -> String {
+        objects
+            .iter()
+            .map(|o| math(&figure(o)))
+            .collect::<Vec<_>>()
+            .join(sep)
+    }
+
+    fn point(&self, decl: &PointDecl) -> String {
+        match decl {
+            PointDecl::Free(names) if names.len() == 1 => {
+                format!("Construct point {}.", math(&point(&names[0])))
+            }
+            PointDecl::Free(names) => {
+                let names: Vec<String> = names.iter().map(|n| math(&point(n))).collect();
+                format!("Construct points {}.", names.join(", "))
+            }
+            PointDecl::Intersection { name, objects } => {
+                let descs: Vec<String> = objects.iter().map(described).collect();
+                let (last, init) = descs.split_last().expect("intersection of two objects");
+                format!(
+                    "Let point {} be the intersection of {} and {}.",
+                    math(&point(name)),
+                    init.join(", "),
+                    last
+                )
+            }
+            PointDecl::Coordinates { name, coords } => format!(
+                "Let point {} be at coordinates {}.",
+                math(&point(name)),
+                math(&format!("({})", coords))
+            ),
+            PointDecl::InRegion { name, region } => format!(
+                "Construct point {} in the region {}.",
+                math(&point(name)),
+                math(&figure(region))
+            ),
+            PointDecl::OnObject {
+                name,
+                object,
+                conditions,
+            } => {
+                let base = format!(
+                    "Construct point {} on {}",
+                    math(&point(name)),
+                    math(&figure(object))
+                );
+                if conditions.is_empty() {
+                    return format!("{}.", base);
+                }
+                let conditions: Vec<String> = conditions.iter().map(|c| self.clause(c)).collect();
+                format!("{} such that {}.", base, conditions.join(", "))
+            }
+        }
+    }
+
+    fn circle(&self, decl: &CircleDecl) -> String {
+        match decl {
+            CircleDecl::ThroughPoints(points) => format!(
+                "Construct a circle through points {}, {}, and {}.",
+                math(&point(&points[0])),
+                math(&point(&points[1])),
+                math(&point(&points[2]))
+            ),
+            CircleDecl::CenterRadius { center, radius } => format!(
+                "Construct a circle with center {} and radius {}.",
+                math(&point(center)),
+                math(&self.expr(radius))
+            ),
+            CircleDecl::CenterThrough { center, point: p } => format!(
+                "Construct a circle with center {} passing through point {}.",
+                math(&point(center)),
+                math(&point(p))
+            ),
+            CircleDecl::Named(name) => format!("Construct circle {}.", math(&point(name))),
+            CircleDecl::Through(p) => format!(
+                "Construct a circle passing through point {}.",
+                math(&point(p))
+            ),
+        }
+    }
+
+    fn regular(&self, poly: &RegularPolygon) -> String {
+        format!(
+            "{} {} with side {}",
+            shape_name(&poly.sides),
+            math(&figure(&poly.polygon)),
+            math(&figure(&poly.side))
+        )
+    }
+
+    fn relation(&self, lhs: &Expr, op: CmpOp, rhs: &Expr) -> String {
+        let val = self.expr(rhs);
+        if op == CmpOp::Eq {
+            match lhs {
+                Expr::Object(obj) if obj.kind == ObjectKind::Arc => {
+                    return format!("Arc {} has length {}.", math(&arc(obj)), math(&val));
+                }
+                Expr::Object(obj) if obj.kind == ObjectKind::Sector => {
+                    return format!(
+                        "The area of sector {} is {}.",
+                        math(&points(obj)),
+                        math(&val)
+                    );
+                }
+                Expr::Perimeter(obj) => {
+                    return format!(
+                        "Let the perimeter of {} be {}.",
+                        math(&figure(obj)),
+                        math(&val)
+                    );
+                }
+                _ => {}
+            }
+        }
+        let val = match lhs {
+            Expr::Angle(_) => degrees(rhs, val),
+            _ => val,
+        };
+        format!(
+            "{}.",
+            math(&format!("{} {} {}", self.expr(lhs), cmp(op), val))
+        )
+    }
+
+    fn query(&self, expr: &Expr) -> String {
+        match expr {
+            Expr::Object(obj) if obj.kind == ObjectKind::Arc => {
+                format!("What is the length of arc {}?", math(&arc(obj)))
+            }
+            Expr::Object(obj) if obj.kind == ObjectKind::Sector => {
+                format!("What is the area of sector {}?", math(&points(obj)))
+            }
+            Expr::Perimeter(obj) => {
+                format!("What is the perimeter of {}?", math(&figure(obj)))
+            }
+            other => format!("What is {}?", math(&self.expr(other))),
+        }
+    }
+
+    fn relationship(&self, objects: &[Object], relation: &str) -> String {
+        let name = self.translator.relationship_name(relation);
+        let symbol = match name {
+            "parallel" => "\\parallel",
+            "perpendicular" => "\\perp",
+            "congruent" => "\\cong",
+            "similar" => "\\sim",
+            _ => {
+                return format!("{} are {}", self.list(objects, " and "), text(name));
+            }
+        };
+        let parts: Vec<String> = objects.iter().map(figure).collect();
+        math(&parts.join(&format!(" {} ", symbol)))
+    }
+
+    /// An expression in math mode, without the surrounding `$`.
+    pub fn expr(&self, expr: &Expr) -> String {
+        match expr {
+            Expr::Number(n) => n.clone(),
+            Expr::Variable(v) => greek(v),
+            Expr::Constant(c) => self.constant(c),
+            Expr::Object(obj) => match obj.kind {
+                ObjectKind::Arc => arc(obj),
+                ObjectKind::Figure => points(obj),
+                _ => figure(obj),
+            },
+            Expr::Area(obj) => format!("[{}]", points(obj)),
+            Expr::Perimeter(obj) => format!("\\text{{perimeter}}({})", points(obj)),
+            Expr::Angle(obj) if obj.kind == ObjectKind::Arc => arc(obj),
+            Expr::Angle(obj) => format!("\\angle {}", points(obj)),
+            Expr::Binary(l, ArithOp::Div, r) => {
+                format!("\\frac{{{}}}{{{}}}", self.expr(l), self.expr(r))
+            }
+            Expr::Binary(l, op, r) => format!("{} {} {}", self.expr(l), op, self.expr(r)),
+            Expr::Product(l, r) => format!("{} {}", self.expr(l), self.expr(r)),
+        }
+    }
+
+    /// `\P`, `π` and friends by way of the translator's constant table.
+    fn constant(&self, c: &str) -> String {
+        let name = self.translator.constants.get(c).unwrap_or(c);
+        if name.contains("(pi)") {
+            "\\pi".to_string()
+        } else if name.contains("(tau)") {
+            "\\tau".to_string()
+        } else if name.contains("(phi)") {
+            "\\varphi".to_string()
+        } else {
+            format!("\\text{{{}}}", text(c))
+        }
+    }
+}
+
+fn math(s: &str) -> String {
+    format!("${}$", s)
+}
+
+/// Point names; primes and digits are already valid math.
+pub(crate) fn point(name: &str) -> String {
+    greek(name)
+}
+
+fn points(obj: &Object) -> String {
+    obj.points.iter().map(|p| point(p)).collect()
+}
+
+fn arc(obj: &Object) -> String {
+    format!("\\overset{{\\frown}}{{{}}}", points(obj))
+}
+
+fn linear(kind: LinearKind, obj: &Object) -> String {
+    match kind {
+        LinearKind::Segment => format!("\\overline{{{}}}", points(obj)),
+        LinearKind::Line => format!("\\overleftrightarrow{{{}}}", points(obj)),
+        LinearKind::Ray => format!("\\overrightarrow{{{}}}", points(obj)),
+    }
+}
+
+/// An object as a figure: two bare points are a segment.
+fn figure(obj: &Object) -> String {
+    match obj.kind {
+        ObjectKind::Figure if obj.points.len() == 2 => linear(LinearKind::Segment, obj),
+        ObjectKind::Figure => points(obj),
+        ObjectKind::Ray => linear(LinearKind::Ray, obj),
+        ObjectKind::Line => linear(LinearKind::Line, obj),
+        ObjectKind::Circle => format!("\\odot {}", points(obj)),
+        ObjectKind::Arc => arc(obj),
+        ObjectKind::Sector => format!("\\text{{sector }} {}", points(obj)),
+        ObjectKind::Triangle => format!("\\triangle {}", points(obj)),
+    }
+}
+
+/// An object named with its kind in words, as in "line AB".
+fn described(obj: &Object) -> String {
+    let noun = match obj.kind {
+        ObjectKind::Ray => "ray",
+        ObjectKind::Line => "line",
+        ObjectKind::Circle => "circle",
+        ObjectKind::Arc => "arc",
+        ObjectKind::Sector => "sector",
+        ObjectKind::Triangle => "triangle",
+        ObjectKind::Figure => "segment",
+    };
+    format!("{} {}", noun, math(&figure(obj)))
+}
+
+fn degrees(rhs: &Expr, val: String) -> String {
+    match rhs {
+        Expr::Number(_) => format!("{}^\\circ", val),
+        _ => val,
+    }
+}
+
+fn cmp(op: CmpOp) -> &'static str {
+    match op {
+        CmpOp::Eq => "=",
+        CmpOp::NotEq => "\\neq",
+        CmpOp::Lt => "<",
+        CmpOp::Le => "\\leq",
+        CmpOp::Gt => ">",
+        CmpOp::Ge => "\\geq",
+    }
+}
+
+/// Greek letters as commands, so output compiles without Unicode support.
+fn greek(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            'α' => "\\alpha ".to_string(),
+            'β' => "\\beta ".to_string(),
+            'γ' => "\\gamma ".to_string(),
+            'δ' => "\\delta ".to_string(),
+            'θ' => "\\theta ".to_string(),
+            'λ' => "\\lambda ".to_string(),
+            'μ' => "\\mu ".to_string(),
+            'ρ' => "\\rho ".to_string(),
+            'σ' => "\\sigma ".to_string(),
+            'ω' => "\\omega ".to_string(),
+            'Γ' => "\\Gamma ".to_string(),
+            'Ω' => "\\Omega ".to_string(),
+            c => c.to_string(),
+        })
+        .collect::<String>()
+        .trim_end()
+        .to_string()
+}
+
+/// Escape characters that are special in LaTeX text mode.
+fn text(s: &str) -> String {
+    let mut out = String::new();
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\textbackslash{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '~' => out.push_str("\\textasciitilde{}"),
+            '^' => out.push_str("\\textasciicircum{}"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
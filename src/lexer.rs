@@ -0,0 +1,554 @@
+/// A byte range into the source text a token or statement came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+
+    /// The smallest span covering both `self` and `other`.
+    pub fn to(self, other: Span) -> Span {
+        Span {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    /// `\\` problem wrapper.
+    Wrap,
+    /// Construction sigil such as `P:`, `CCO:` or `9C:` (text without the colon).
+    Prefix(String),
+    /// Backslash command such as `\p`, `\qC`, `\bc` or `\P` (text without the backslash).
+    Command(String),
+    /// Run of point names, e.g. `ABC` or `A1B1`.
+    Name(String),
+    /// Single lowercase (or non-Latin) letter: object prefixes, `x`, variables.
+    Lower(char),
+    Number(String),
+    /// Theorem code such as `_PY` (text includes the underscore).
+    Theorem(String),
+    /// Raw contents of a `{...}` group.
+    Braced(String),
+    /// Contents of a `"..."` file name, as in `\include "setup.gsl"`.
+    Quoted(String),
+    /// `π`, `τ` or `φ`.
+    Constant(char),
+    /// `\?` proof query suffix.
+    ProveQuery,
+    Slash,
+    /// A line break where a statement could end, which separates statements
+    /// like `/` does.
+    Newline,
+    Colon,
+    Semi,
+    Comma,
+    Dot,
+    DotDot,
+    Question,
+    Eq,
+    NotEq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Pipe,
+    OrOr,
+    AndAnd,
+    Implies,
+    Bang,
+    Star,
+    Plus,
+    Minus,
+    LBracket,
+    RBracket,
+    LParen,
+    RParen,
+    CaseOpen,
+    CaseClose,
+    /// `|A` / `∀` at the start of a statement.
+    ForAll,
+    /// `|E` / `∃` at the start of a statement.
+    Exists,
+    /// Single-character symbol: `∠`, `△`, `∥`, `⊥`, `≅`, `~`, `□`, `↯`, `∵`, `∴`.
+    Symbol(char),
+    /// `@key: value` line heading a problem in a file, such as
+    /// `@problem p1: A right triangle` or `@tags: area, circles`.
+    Meta(String, String),
+    /// Text the lexer could not make sense of, with an explanation.
+    Invalid(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span,
+}
+
+pub fn tokenize(input: &str) -> Vec<Token> {
+    Lexer::new(input).run().0
+}
+
+/// Where the comments are: `%%` and `#` run to the end of their line (or to
+/// the `\\` closing a one-line problem), and
+/// `/* ... */` to its closing marker. All are skipped by [`tokenize`].
+pub fn comments(input: &str) -> Vec<Span> {
+    Lexer::new(input).run().1
+}
+
+/// The text of a comment without its markers.
+pub fn comment_body(comment: &str) -> &str {
+    let body = if let Some(block) = comment.strip_prefix("/*") {
+        block.strip_suffix("*/").unwrap_or(block)
+    } else if let Some(line) = comment.strip_prefix("%%") {
+        line
+    } else {
+        comment.strip_prefix('#').unwrap_or(comment)
+    };
+    body.trim()
+}
+
+struct Lexer<'a> {
+    src: &'a str,
+    pos: usize,
+    tokens: Vec<Token>,
+    comments: Vec<Span>,
+    /// Open `(` and `[` groups, inside which line breaks are just whitespace.
+    depth: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(src: &'a str) -> Self {
+        Lexer {
+            src,
+            pos: 0,
+            tokens: Vec::new(),
+            comments: Vec::new(),
+            depth: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn peek_at(&self, n: usize) -> Option<char> {
+        self.src[self.pos..].chars().nth(n)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.pos += ch.len_utf8();
+        Some(ch)
+    }
+
+    fn push(&mut self, kind: TokenKind, start: usize) {
+        match kind {
+            TokenKind::LParen | TokenKind::LBracket => self.depth += 1,
+            TokenKind::RParen | TokenKind::RBracket => self.depth = self.depth.saturating_sub(1),
+            TokenKind::Wrap => self.depth = 0,
+            _ => {}
+        }
+        self.tokens.push(Token {
+            kind,
+            span: Span::new(start, self.pos),
+        });
+    }
+
+    /// True when the next token would begin a new statement.
+    fn at_statement_start(&self) -> bool {
+        matches!(
+            self.tokens.last().map(|t| &t.kind),
+            None | Some(TokenKind::Slash) | Some(TokenKind::Newline) | Some(TokenKind::Wrap)
+        )
+    }
+
+    /// True when a line break here ends a statement. A line that stops on
+    /// an operator or other punctuation carries on to the next one.
+    fn at_line_end(&self) -> bool {
+        let Some(last) = self.tokens.last() else {
+            return false;
+        };
+        self.depth == 0
+            && !matches!(
+                last.kind,
+                TokenKind::Slash
+                    | TokenKind::Newline
+                    | TokenKind::Wrap
+                    | TokenKind::Meta(..)
+                    | TokenKind::Prefix(_)
+                    | TokenKind::Colon
+                    | TokenKind::Semi
+                    | TokenKind::Comma
+                    | TokenKind::Dot
+                    | TokenKind::DotDot
+                    | TokenKind::Eq
+                    | TokenKind::NotEq
+                    | TokenKind::Lt
+                    | TokenKind::Le
+                    | TokenKind::Gt
+                    | TokenKind::Ge
+                    | TokenKind::Pipe
+                    | TokenKind::OrOr
+                    | TokenKind::AndAnd
+                    | TokenKind::Implies
+                    | TokenKind::Bang
+                    | TokenKind::Star
+                    | TokenKind::Plus
+                    | TokenKind::Minus
+                    | TokenKind::ForAll
+                    | TokenKind::Exists
+                    | TokenKind::Symbol('∠' | '△' | '∥' | '⊥' | '≅' | '~')
+            )
+    }
+
+    fn run(mut self) -> (Vec<Token>, Vec<Span>) {
+        while let Some(ch) = self.peek() {
+            let start = self.pos;
+            if ch.is_whitespace() {
+                self.bump();
+                // A group left open does not run past a line that starts a
+                // new statement, so the rest of the problem still splits
+                // into lines
+                if ch == '\n' && self.depth > 0 && self.statement_ahead() {
+                    self.depth = 0;
+                }
+                if ch == '\n' && self.at_line_end() {
+                    self.push(TokenKind::Newline, start);
+                }
+                continue;
+            }
+
+            if ch == '#' || (ch == '%' && self.peek_at(1) == Some('%')) {
+                let line = self.src[start..].split('\n').next().unwrap_or("");
+                // A comment at the end of a one-line problem stops before the
+                // `\\` that closes it
+                let len = match line.trim_end().strip_suffix("\\\\") {
+                    Some(body) => body.len(),
+                    None => line.len(),
+                };
+                self.pos += len;
+                let end = start + self.src[start..self.pos].trim_end().len();
+                self.comments.push(Span::new(start, end));
+                continue;
+            }
+
+            if ch == '@' && self.at_line_start() {
+                self.lex_meta(start);
+                continue;
+            }
+
+            if ch == '/' && self.peek_at(1) == Some('*') {
+                match self.src[start + 2..].find("*/") {
+                    Some(len) => {
+                        self.pos = start + 2 + len + 2;
+                        self.comments.push(Span::new(start, self.pos));
+                    }
+                    None => {
+                        self.pos = self.src.len();
+                        self.error("unclosed block comment; close it with `*/`", start);
+                    }
+                }
+                continue;
+            }
+
+            if ch == '\\' {
+                self.lex_backslash(start);
+                continue;
+            }
+
+            if ch.is_ascii_uppercase() || ch.is_ascii_digit() {
+                if let Some(prefix) = self.prefix_ahead() {
+                    self.pos += prefix.len() + 1;
+                    self.push(TokenKind::Prefix(prefix), start);
+                    continue;
+                }
+            }
+
+            if ch.is_ascii_uppercase() {
+                self.bump();
+                while let Some(c) = self.peek() {
+                    if c.is_ascii_uppercase() || c.is_ascii_digit() || c == '\'' {
+                        self.bump();
+                    } else {
+                        break;
+                    }
+                }
+                let text = self.src[start..self.pos].to_string();
+                self.push(TokenKind::Name(text), start);
+                continue;
+            }
+
+            if ch.is_ascii_digit() {
+                self.lex_number(start);
+                continue;
+            }
+
+            if ch == '_' {
+                self.bump();
+                while let Some(c) = self.peek() {
+                    if c.is_ascii_uppercase() || c.is_ascii_digit() {
+                        self.bump();
+                    } else {
+                        break;
+                    }
+                }
+                if self.pos - start == 1 {
+                    self.error("expected a theorem code after `_`", start);
+                } else {
+                    let text = self.src[start..self.pos].to_string();
+                    self.push(TokenKind::Theorem(text), start);
+                }
+                continue;
+            }
+
+            if ch == '{' {
+                self.lex_braced(start);
+                continue;
+            }
+
+            if ch == '"' {
+                self.lex_quoted(start);
+                continue;
+            }
+
+            if ch == '|' && self.at_statement_start() {
+                match self.peek_at(1) {
+                    Some('A') => {
+                        self.pos += 2;
+                        self.push(TokenKind::ForAll, start);
+                        continue;
+                    }
+                    Some('E') => {
+                        self.pos += 2;
+                        self.push(TokenKind::Exists, start);
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+
+            if let Some(kind) = self.lex_punct() {
+                self.push(kind, start);
+                continue;
+            }
+
+            self.bump();
+            let kind = match ch {
+                'π' | 'τ' | 'φ' => TokenKind::Constant(ch),
+                '∀' => TokenKind::ForAll,
+                '∃' => TokenKind::Exists,
+                '∨' => TokenKind::OrOr,
+                '∧' => TokenKind::AndAnd,
+                '⊃' => TokenKind::Implies,
+                '≠' => TokenKind::NotEq,
+                '≥' => TokenKind::Ge,
+                '≤' => TokenKind::Le,
+                '∠' | '△' | '∥' | '⊥' | '≅' | '~' | '□' | '↯' | '∵' | '∴' => {
+                    TokenKind::Symbol(ch)
+                }
+                c if c.is_alphabetic() && !c.is_uppercase() => TokenKind::Lower(c),
+                c => {
+                    self.error(&format!("unexpected character `{}`", c), start);
+                    continue;
+                }
+            };
+            self.push(kind, start);
+        }
+        (self.tokens, self.comments)
+    }
+
+    /// True when only whitespace comes before this point on its line.
+    fn at_line_start(&self) -> bool {
+        let line = self.src[..self.pos].rsplit('\n').next().unwrap_or("");
+        line.trim().is_empty()
+    }
+
+    fn lex_meta(&mut self, start: usize) {
+        let len = self.src[start..]
+            .find('\n')
+            .unwrap_or(self.src.len() - start);
+        let line = self.src[start + 1..start + len].trim_end();
+        self.pos = start + 1 + line.len();
+        let key_len = line
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+            .unwrap_or(line.len());
+        if key_len == 0 {
+            self.error(
+                "expected a metadata key after `@`, as in `@source: ...`",
+                start,
+            );
+            return;
+        }
+        let (key, value) = line.split_at(key_len);
+        let value = value.trim_start();
+        let value = value.strip_prefix(':').unwrap_or(value).trim();
+        self.push(TokenKind::Meta(key.to_string(), value.to_string()), start);
+    }
+
+    fn error(&mut self, message: &str, start: usize) {
+        self.push(TokenKind::Invalid(message.to_string()), start);
+    }
+
+    /// A run of uppercase letters and digits immediately followed by `:`.
+    /// True when the next line starts with a construction prefix such as
+    /// `S:`, a command such as `\p:`, or a `\\` wrapper.
+    fn statement_ahead(&self) -> bool {
+        let rest = self.src[self.pos..].trim_start();
+        let len = rest
+            .find(|c: char| !(c.is_ascii_uppercase() || c.is_ascii_digit()))
+            .unwrap_or(rest.len());
+        (len > 0 && rest[len..].starts_with(':')) || rest.starts_with('\\')
+    }
+
+    fn prefix_ahead(&self) -> Option<String> {
+        let rest = &self.src[self.pos..];
+        let len = rest
+            .find(|c: char| !(c.is_ascii_uppercase() || c.is_ascii_digit()))
+            .unwrap_or(rest.len());
+        if len > 0 && rest[len..].starts_with(':') {
+            Some(rest[..len].to_string())
+        } else {
+            None
+        }
+    }
+
+    fn lex_backslash(&mut self, start: usize) {
+        self.bump();
+        match self.peek() {
+            Some('\\') => {
+                self.bump();
+                self.push(TokenKind::Wrap, start);
+            }
+            Some('?') => {
+                self.bump();
+                self.push(TokenKind::ProveQuery, start);
+            }
+            Some('>') if self.peek_at(1) == Some('>') => {
+                self.pos += 2;
+                self.push(TokenKind::CaseClose, start);
+            }
+            Some(c) if c.is_ascii_alphabetic() => {
+                while let Some(c) = self.peek() {
+                    if c.is_ascii_alphabetic() {
+                        self.bump();
+                    } else {
+                        break;
+                    }
+                }
+                let text = self.src[start + 1..self.pos].to_string();
+                self.push(TokenKind::Command(text), start);
+            }
+            _ => self.error("expected a command name after `\\`", start),
+        }
+    }
+
+    fn lex_number(&mut self, start: usize) {
+        while matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+            self.bump();
+        }
+        if self.peek() == Some('.') && matches!(self.peek_at(1), Some(c) if c.is_ascii_digit()) {
+            self.bump();
+            while matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+                self.bump();
+            }
+        }
+        let text = self.src[start..self.pos].to_string();
+        self.push(TokenKind::Number(text), start);
+    }
+
+    fn lex_braced(&mut self, start: usize) {
+        self.bump();
+        let content_start = self.pos;
+        let mut depth = 1;
+        while let Some(c) = self.peek() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+            self.bump();
+        }
+        if depth != 0 {
+            self.error("unclosed `{`", start);
+            return;
+        }
+        let text = self.src[content_start..self.pos].to_string();
+        self.bump();
+        self.push(TokenKind::Braced(text), start);
+    }
+
+    /// A `"..."` file name, which stays on one line.
+    fn lex_quoted(&mut self, start: usize) {
+        self.bump();
+        let content_start = self.pos;
+        while let Some(c) = self.peek() {
+            if c == '"' || c == '\n' {
+                break;
+            }
+            self.bump();
+        }
+        if self.peek() != Some('"') {
+            self.error("unclosed `\"`", start);
+            return;
+        }
+        let text = self.src[content_start..self.pos].to_string();
+        self.bump();
+        self.push(TokenKind::Quoted(text), start);
+    }
+
+    fn lex_punct(&mut self) -> Option<TokenKind> {
+        let rest = &self.src[self.pos..];
+        const TWO: [(&str, TokenKind); 9] = [
+            ("..", TokenKind::DotDot),
+            ("!=", TokenKind::NotEq),
+            ("<=", TokenKind::Le),
+            (">=", TokenKind::Ge),
+            ("<<", TokenKind::CaseOpen),
+            (">>", TokenKind::CaseClose),
+            ("||", TokenKind::OrOr),
+            ("&&", TokenKind::AndAnd),
+            ("=>", TokenKind::Implies),
+        ];
+        for (text, kind) in TWO {
+            if rest.starts_with(text) {
+                self.pos += 2;
+                return Some(kind);
+            }
+        }
+        let kind = match rest.chars().next()? {
+            '/' => TokenKind::Slash,
+            ':' => TokenKind::Colon,
+            ';' => TokenKind::Semi,
+            ',' => TokenKind::Comma,
+            '.' => TokenKind::Dot,
+            '?' => TokenKind::Question,
+            '=' => TokenKind::Eq,
+            '<' => TokenKind::Lt,
+            '>' => TokenKind::Gt,
+            '|' => TokenKind::Pipe,
+            '!' => TokenKind::Bang,
+            '*' => TokenKind::Star,
+            '+' => TokenKind::Plus,
+            '-' => TokenKind::Minus,
+            '[' => TokenKind::LBracket,
+            ']' => TokenKind::RBracket,
+            '(' => TokenKind::LParen,
+            ')' => TokenKind::RParen,
+            _ => return None,
+        };
+        self.pos += 1;
+        Some(kind)
+    }
+}
@@ -0,0 +1,60 @@
+//! Geometry Shorthand Language (GSL) compiler.
+//!
+//! The [`Translator`] is the main entry point: it parses a problem, checks it
+//! and turns each statement into English.
+//!
+//! ```
+//! let translator = gslc::Translator::new();
+//! let translation = translator.translate("\\\\P:A,B/S:AB\\\\");
+//! assert!(!translation.has_errors());
+//! assert_eq!(
+//!     translation.english(),
+//!     ["Construct points A, B.", "Connect segment AB."]
+//! );
+//! ```
+//!
+//! # API stability
+//!
+//! Everything reachable from this crate root follows semantic versioning:
+//! [`Translator`], [`Translation`], [`Step`], [`Problem`] and its [`Metadata`], the syntax tree in [`ast`],
+//! [`parse`] and [`parser::ParseError`], [`check`] and the [`checker`] module,
+//! [`diagnostic`], [`mod@include`] for reading files with what they include,
+//! the numeric [`eval`] and [`geometry`] modules, the
+//! [`latex::Latex`], [`svg`], [`tikz`], [`asy`] and [`geogebra`] backends,
+//! [`reverse`] translation from English, [`unpronounce`] for reading
+//! pronunciations back, [`theorem`] for what citable theorems say, the [`formatter`] and [`linter`],
+//! the [`lsp`] server, and the [`json`] value type along with the structure
+//! it gives a translation. Adding a variant to an AST or diagnostic enum, a
+//! field to a JSON object, or a lint rule is a minor change; renaming or
+//! removing anything public is a major one. The wording of English output and
+//! diagnostic messages, and where free points are placed, are not part of the
+//! stable API.
+
+pub mod ast;
+pub mod asy;
+pub mod checker;
+pub mod diagnostic;
+pub mod eval;
+pub mod formatter;
+pub mod geogebra;
+pub mod geometry;
+pub mod include;
+pub mod json;
+pub mod latex;
+pub mod lexer;
+pub mod linter;
+pub mod lsp;
+mod macros;
+pub mod parser;
+mod pronounce;
+mod proof;
+pub mod reverse;
+pub mod svg;
+pub mod theorem;
+pub mod tikz;
+mod translator;
+pub mod unpronounce;
+
+pub use checker::check;
+pub use parser::parse;
+pub use translator::{Metadata, Problem, Step, Translation, Translator, METADATA_KEYS};
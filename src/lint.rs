@@ -0,0 +1,147 @@
+//! `gslc lint`: report likely mistakes in GSL files.
+
+use gslc::include::{self, Source};
+use gslc::linter::{self, Config, RULES};
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Looked for in the current directory and each directory above it.
+const CONFIG_FILE: &str = ".gslclint";
+
+const USAGE: &str =
+    "usage: gslc lint [--config <file>] [-A|-W|-D <rule>] [-I <dir>] [--rules] [file.gsl ...]";
+
+/// Lint the named files, or standard input when there are none. Returns the
+/// exit code: 1 if anything was reported as an error.
+pub fn run(args: &[String]) -> i32 {
+    let mut config_path = None;
+    let mut overrides = Vec::new();
+    let mut files = Vec::new();
+    let mut include_dirs = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rules" => {
+                for rule in RULES {
+                    println!(
+                        "{:<22} {:<6} {}",
+                        rule.name,
+                        rule.default.name(),
+                        rule.summary
+                    );
+                }
+                return 0;
+            }
+            "--config" => match args.next() {
+                Some(path) => config_path = Some(PathBuf::from(path)),
+                None => {
+                    eprintln!("Error: --config requires a filename");
+                    return 2;
+                }
+            },
+            flag @ ("-A" | "-W" | "-D") => {
+                let level = match flag {
+                    "-A" => "allow",
+                    "-W" => "warn",
+                    _ => "deny",
+                };
+                match args.next() {
+                    Some(rule) => overrides.push((rule.clone(), level)),
+                    None => {
+                        eprintln!("Error: {} requires a rule name", flag);
+                        return 2;
+                    }
+                }
+            }
+            "-I" => match args.next() {
+                Some(dir) => include_dirs.push(PathBuf::from(dir)),
+                None => {
+                    eprintln!("Error: -I requires a directory");
+                    return 2;
+                }
+            },
+            _ if arg.starts_with('-') => {
+                eprintln!("Error: unknown option `{}`\n{}", arg, USAGE);
+                return 2;
+            }
+            _ => files.push(arg.clone()),
+        }
+    }
+
+    let mut config = match config_path.or_else(find_config) {
+        Some(path) => {
+            let text = fs::read_to_string(&path).unwrap_or_else(|e| {
+                eprintln!("Error reading {}: {}", path.display(), e);
+                std::process::exit(2);
+            });
+            Config::parse(&text).unwrap_or_else(|e| {
+                eprintln!("Error in {}: {}", path.display(), e);
+                std::process::exit(2);
+            })
+        }
+        None => Config::default(),
+    };
+    for (rule, level) in overrides {
+        if let Err(e) = config.set(&rule, level) {
+            eprintln!("Error: {}", e);
+            return 2;
+        }
+    }
+
+    let search_path = include::search_path(&include_dirs);
+    if files.is_empty() {
+        let mut input = String::new();
+        if let Err(e) = io::stdin().read_to_string(&mut input) {
+            eprintln!("Error reading input: {}", e);
+            return 1;
+        }
+        return i32::from(!report("<stdin>", None, &input, &config, &search_path));
+    }
+    let mut status = 0;
+    for path in &files {
+        match fs::read_to_string(path) {
+            Ok(input) => {
+                if !report(path, Some(Path::new(path)), &input, &config, &search_path) {
+                    status = 1;
+                }
+            }
+            Err(e) => {
+                eprintln!("Error reading {}: {}", path, e);
+                status = 1;
+            }
+        }
+    }
+    status
+}
+
+fn find_config() -> Option<PathBuf> {
+    let dir = env::current_dir().ok()?;
+    dir.ancestors()
+        .map(|dir| dir.join(CONFIG_FILE))
+        .find(|path| path.is_file())
+}
+
+/// Print the findings for one file; returns false if any is an error.
+/// Included files are read in, but what is found in them is left for when
+/// they are linted themselves.
+fn report(
+    name: &str,
+    path: Option<&Path>,
+    input: &str,
+    config: &Config,
+    search_path: &[PathBuf],
+) -> bool {
+    let source = Source::new(name, input, path, search_path);
+    let mut diagnostics = source.diagnostics.clone();
+    diagnostics.extend(
+        linter::lint(&source.text, config)
+            .into_iter()
+            .filter(|d| source.is_root(d.span)),
+    );
+    for diagnostic in &diagnostics {
+        eprintln!("{}", source.render(diagnostic));
+    }
+    !diagnostics.iter().any(|d| d.is_error())
+}
@@ -0,0 +1,492 @@
+//! Lints: problems that translate fine but are probably mistakes.
+//!
+//! Each finding belongs to a named rule whose level is set by a [`Config`]:
+//! `allow` drops it, `warn` reports a warning and `deny` an error. A
+//! comment reading `allow(rule, ...)` silences rules on its own lines and
+//! the line after it.
+
+use crate::ast::*;
+use crate::checker::{self, SymbolKind, SymbolTable};
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::lexer::{self, Span};
+use crate::macros::Scope;
+use crate::parser;
+use crate::Translator;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Allow,
+    Warn,
+    Deny,
+}
+
+impl Level {
+    pub fn parse(name: &str) -> Option<Level> {
+        match name {
+            "allow" => Some(Level::Allow),
+            "warn" => Some(Level::Warn),
+            "deny" => Some(Level::Deny),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Level::Allow => "allow",
+            Level::Warn => "warn",
+            Level::Deny => "deny",
+        }
+    }
+}
+
+pub struct Rule {
+    pub name: &'static str,
+    pub default: Level,
+    pub summary: &'static str,
+}
+
+pub const RULES: &[Rule] = &[
+    Rule {
+        name: "ssa-congruence",
+        default: Level::Warn,
+        summary: "`_SSA` cited, though SSA does not prove congruence in general",
+    },
+    Rule {
+        name: "regular-polygon-side",
+        default: Level::Deny,
+        summary: "`R:` side that is not an edge of the polygon",
+    },
+    Rule {
+        name: "repeated-vertex",
+        default: Level::Deny,
+        summary: "polygon that lists a vertex twice",
+    },
+    Rule {
+        name: "unused-point",
+        default: Level::Warn,
+        summary: "point constructed but never used",
+    },
+    Rule {
+        name: "unconstructed-query",
+        default: Level::Warn,
+        summary: "query about a segment, angle or polygon that is never drawn",
+    },
+    Rule {
+        name: "single-case",
+        default: Level::Warn,
+        summary: "casework `<<...>>` with only one case",
+    },
+];
+
+fn rule(name: &str) -> Option<&'static Rule> {
+    RULES.iter().find(|rule| rule.name == name)
+}
+
+/// The level of every rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    levels: HashMap<&'static str, Level>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            levels: RULES.iter().map(|rule| (rule.name, rule.default)).collect(),
+        }
+    }
+}
+
+impl Config {
+    /// Read a config file on top of the defaults: one `rule = level` per
+    /// line, with `#` starting a comment.
+    pub fn parse(text: &str) -> Result<Config, String> {
+        let mut config = Config::default();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (name, level) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected `rule = level`", i + 1))?;
+            config
+                .set(name.trim(), level.trim())
+                .map_err(|e| format!("line {}: {}", i + 1, e))?;
+        }
+        Ok(config)
+    }
+
+    /// Set one rule, as a config line or a command-line flag does.
+    pub fn set(&mut self, name: &str, level: &str) -> Result<(), String> {
+        let rule = rule(name).ok_or_else(|| format!("unknown lint rule `{}`", name))?;
+        let level = Level::parse(level)
+            .ok_or_else(|| format!("unknown level `{}`; expected allow, warn or deny", level))?;
+        self.levels.insert(rule.name, level);
+        Ok(())
+    }
+
+    pub fn level(&self, name: &str) -> Level {
+        self.levels.get(name).copied().unwrap_or(Level::Allow)
+    }
+}
+
+/// Lint a file, each of its problems on its own. Statements that fail to
+/// parse are reported as errors and otherwise skipped.
+pub fn lint(input: &str, config: &Config) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut linter = Linter {
+        symbols: SymbolTable::default(),
+        translator: Translator::new(),
+        findings: Vec::new(),
+    };
+    let mut scope = Scope::default();
+    for problem in parser::parse_problems(input) {
+        let mut statements = Vec::new();
+        for parsed in problem.statements {
+            match parsed {
+                Ok(stmt) => statements.push(stmt),
+                Err(err) => diagnostics.push(Diagnostic::from(&err)),
+            }
+        }
+        let statements = scope.expand(&linter.translator, statements, &mut diagnostics);
+        linter.symbols = checker::symbols(&statements);
+        linter.run(&statements);
+    }
+
+    let suppressions = suppressions(input, &mut diagnostics);
+    let line_of = |offset: usize| input[..offset].matches('\n').count();
+    for (name, diagnostic) in linter.findings {
+        let suppressed = suppressions.iter().any(|(line, rules)| {
+            *line == line_of(diagnostic.span.start) && rules.iter().any(|r| r == name)
+        });
+        let severity = match config.level(name) {
+            _ if suppressed => continue,
+            Level::Allow => continue,
+            Level::Warn => Severity::Warning,
+            Level::Deny => Severity::Error,
+        };
+        diagnostics.push(Diagnostic {
+            severity,
+            message: format!("{} [{}]", diagnostic.message, name),
+            ..diagnostic
+        });
+    }
+    scope.annotate(&mut diagnostics);
+    diagnostics.sort_by_key(|d| d.span.start);
+    diagnostics
+}
+
+/// The lines each `allow(...)` comment covers, and the rules it names.
+/// Names that are not rules are reported.
+fn suppressions(input: &str, diagnostics: &mut Vec<Diagnostic>) -> Vec<(usize, Vec<String>)> {
+    let mut covered = Vec::new();
+    for comment in lexer::comments(input) {
+        let text = lexer::comment_body(&input[comment.start..comment.end]);
+        let Some(list) = text
+            .strip_prefix("allow(")
+            .and_then(|rest| rest.strip_suffix(')'))
+        else {
+            continue;
+        };
+        let names: Vec<String> = list.split(',').map(|n| n.trim().to_string()).collect();
+        for name in &names {
+            if rule(name).is_none() {
+                diagnostics.push(
+                    Diagnostic::warning(format!("unknown lint rule `{}`", name), comment)
+                        .with_hint(format!("expected one of {}", rule_names())),
+                );
+            }
+        }
+        let first = input[..comment.start].matches('\n').count();
+        let last = input[..comment.end].matches('\n').count();
+        for line in first..=last + 1 {
+            covered.push((line, names.clone()));
+        }
+    }
+    covered
+}
+
+fn rule_names() -> String {
+    RULES
+        .iter()
+        .map(|rule| format!("`{}`", rule.name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+struct Linter {
+    symbols: SymbolTable,
+    translator: Translator,
+    findings: Vec<(&'static str, Diagnostic)>,
+}
+
+impl Linter {
+    fn report(&mut self, rule: &'static str, diagnostic: Diagnostic) {
+        self.findings.push((rule, diagnostic));
+    }
+
+    fn run(&mut self, statements: &[Statement]) {
+        for stmt in statements {
+            stmt.walk(&mut |s| self.statement(s));
+        }
+        self.unused_points(statements);
+    }
+
+    fn statement(&mut self, stmt: &Statement) {
+        let span = stmt.span;
+        let cited = match &stmt.kind {
+            StmtKind::Citation(code) => Some(code.as_str()),
+            _ => stmt.theorem.as_deref(),
+        };
+        if cited == Some("_SSA") {
+            let name = &self.translator.theorems["_SSA"];
+            self.report(
+                "ssa-congruence",
+                Diagnostic::warning(
+                    format!("`_SSA` ({}) does not prove congruence in general", name),
+                    span,
+                )
+                .with_hint("cite `_HL` for right triangles, or `_SSC`, `_SAC`, `_ASA`, `_AAS`"),
+            );
+        }
+        match &stmt.kind {
+            StmtKind::RegularPolygon(poly) => {
+                self.repeated_vertex(&poly.polygon, span);
+                let vertices = &poly.polygon.points;
+                if let [a, b] = poly.side.points.as_slice() {
+                    let n = vertices.len();
+                    let is_edge = (0..n).any(|i| {
+                        let (p, q) = (&vertices[i], &vertices[(i + 1) % n]);
+                        (p == a && q == b) || (p == b && q == a)
+                    });
+                    if !is_edge && n >= 2 {
+                        self.report(
+                            "regular-polygon-side",
+                            Diagnostic::warning(
+                                format!(
+                                    "side `{}` is not an edge of `{}`",
+                                    poly.side.name(),
+                                    poly.polygon.name()
+                                ),
+                                span,
+                            )
+                            .with_hint(format!(
+                                "use two neighbouring vertices, e.g. `R:{};{}{}={}`",
+                                poly.sides,
+                                vertices[0],
+                                vertices[1],
+                                poly.polygon.name()
+                            )),
+                        );
+                    }
+                }
+            }
+            StmtKind::Polygon { objects, .. } => {
+                for object in objects {
+                    self.repeated_vertex(object, span);
+                }
+            }
+            StmtKind::Query(expr) => self.query(expr, span),
+            StmtKind::Casework {
+                cases: Some(cases), ..
+            } if cases.len() == 1 => {
+                let case = &cases[0];
+                self.report(
+                    "single-case",
+                    Diagnostic::warning("casework with only one case", span).with_hint(format!(
+                        "state it directly as `{}=>{}`",
+                        case.condition, case.result
+                    )),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    fn repeated_vertex(&mut self, polygon: &Object, span: Span) {
+        let mut seen = HashSet::new();
+        if let Some(point) = polygon.points.iter().find(|p| !seen.insert(*p)) {
+            self.report(
+                "repeated-vertex",
+                Diagnostic::warning(
+                    format!(
+                        "polygon `{}` lists vertex `{}` twice",
+                        polygon.name(),
+                        point
+                    ),
+                    span,
+                ),
+            );
+        }
+    }
+
+    /// Whether a segment is drawn, as a segment, line, ray or polygon side.
+    fn drawn(&self, points: &[String]) -> bool {
+        let reversed: Vec<String> = points.iter().rev().cloned().collect();
+        [SymbolKind::Segment, SymbolKind::Line, SymbolKind::Ray]
+            .into_iter()
+            .any(|kind| {
+                self.symbols.lookup(kind, points).is_some()
+                    || self.symbols.lookup(kind, &reversed).is_some()
+            })
+    }
+
+    fn query(&mut self, expr: &Expr, span: Span) {
+        let (what, missing, hint) = match expr {
+            Expr::Object(obj) if obj.kind == ObjectKind::Figure && obj.points.len() == 2 => {
+                if self.drawn(&obj.points) {
+                    return;
+                }
+                (
+                    format!("segment `{}`", obj.name()),
+                    obj.name(),
+                    format!("S:{}", obj.name()),
+                )
+            }
+            Expr::Angle(obj) if obj.kind != ObjectKind::Arc && obj.points.len() == 3 => {
+                let [a, b, c] = [&obj.points[0], &obj.points[1], &obj.points[2]];
+                let sides: Vec<String> = [[b, a], [b, c]]
+                    .into_iter()
+                    .filter(|[p, q]| !self.drawn(&[(*p).clone(), (*q).clone()]))
+                    .map(|[p, q]| format!("{}{}", p, q))
+                    .collect();
+                if sides.is_empty() {
+                    return;
+                }
+                (
+                    format!("angle `{}`", obj.name()),
+                    sides.join("`, `"),
+                    format!("S:{}", sides.join(",")),
+                )
+            }
+            Expr::Area(obj) | Expr::Perimeter(obj)
+                if obj.kind == ObjectKind::Figure && obj.points.len() >= 3 =>
+            {
+                let n = obj.points.len();
+                let sides_drawn = (0..n)
+                    .all(|i| self.drawn(&[obj.points[i].clone(), obj.points[(i + 1) % n].clone()]));
+                if sides_drawn
+                    || self
+                        .symbols
+                        .lookup(SymbolKind::Polygon, &obj.points)
+                        .is_some()
+                {
+                    return;
+                }
+                (
+                    format!("polygon `{}`", obj.name()),
+                    obj.name(),
+                    format!("J:{}", obj.name()),
+                )
+            }
+            _ => return,
+        };
+        self.report(
+            "unconstructed-query",
+            Diagnostic::warning(
+                format!("query about {}, but `{}` is never drawn", what, missing),
+                span,
+            )
+            .with_hint(format!("construct it first, e.g. `{}`", hint)),
+        );
+    }
+
+    fn unused_points(&mut self, statements: &[Statement]) {
+        let mut declared: Vec<(&str, Span)> = Vec::new();
+        let mut used: HashSet<&str> = HashSet::new();
+        for stmt in statements {
+            stmt.walk(&mut |s| {
+                declared.extend(declared_points(s).into_iter().map(|p| (p, s.span)));
+                used.extend(mentioned_points(s));
+            });
+        }
+        for (point, span) in declared {
+            if !used.contains(point) {
+                self.report(
+                    "unused-point",
+                    Diagnostic::warning(
+                        format!("point `{}` is constructed but never used", point),
+                        span,
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Points a statement introduces by name.
+fn declared_points(stmt: &Statement) -> Vec<&str> {
+    match &stmt.kind {
+        StmtKind::Point(PointDecl::Free(names)) => names.iter().map(String::as_str).collect(),
+        StmtKind::Point(
+            PointDecl::Coordinates { name, .. }
+            | PointDecl::OnObject { name, .. }
+            | PointDecl::InRegion { name, .. }
+            | PointDecl::Intersection { name, .. },
+        ) => vec![name],
+        StmtKind::Derived {
+            code,
+            target: Some(target),
+            ..
+        } if checker::derived_target(code) == Some(SymbolKind::Point) => vec![target],
+        _ => Vec::new(),
+    }
+}
+
+/// Points a statement refers to, not counting statements nested inside it
+/// or the points it introduces.
+fn mentioned_points(stmt: &Statement) -> Vec<&str> {
+    let mut points: Vec<&str> = Vec::new();
+    match &stmt.kind {
+        StmtKind::Point(PointDecl::OnObject { object, .. }) => object_points([object], &mut points),
+        StmtKind::Point(PointDecl::InRegion { region, .. }) => object_points([region], &mut points),
+        StmtKind::Point(PointDecl::Intersection { objects, .. })
+        | StmtKind::Linear(_, objects)
+        | StmtKind::Polygon { objects, .. }
+        | StmtKind::Relationship { objects, .. } => object_points(objects, &mut points),
+        StmtKind::Derived { args, .. } => object_points(args, &mut points),
+        StmtKind::RegularPolygon(poly) => object_points([&poly.side, &poly.polygon], &mut points),
+        StmtKind::Property { object, .. } | StmtKind::Orientation { object, .. } => {
+            object_points([object], &mut points)
+        }
+        StmtKind::Circle(decl) => match decl {
+            CircleDecl::ThroughPoints(names) => points.extend(names.iter().map(String::as_str)),
+            CircleDecl::CenterRadius { center, radius } => {
+                points.push(center);
+                expr_points(radius, &mut points);
+            }
+            CircleDecl::CenterThrough { center, point } => {
+                points.extend([center, point].map(String::as_str))
+            }
+            CircleDecl::Through(point) => points.push(point),
+            CircleDecl::Named(_) => {}
+        },
+        StmtKind::Relation(lhs, _, rhs) => {
+            expr_points(lhs, &mut points);
+            expr_points(rhs, &mut points);
+        }
+        StmtKind::Query(expr) | StmtKind::Value(expr) => expr_points(expr, &mut points),
+        _ => {}
+    }
+    points
+}
+
+fn object_points<'a>(objects: impl IntoIterator<Item = &'a Object>, points: &mut Vec<&'a str>) {
+    for object in objects {
+        points.extend(object.points.iter().map(String::as_str));
+    }
+}
+
+fn expr_points<'a>(expr: &'a Expr, points: &mut Vec<&'a str>) {
+    match expr {
+        Expr::Object(obj) | Expr::Area(obj) | Expr::Perimeter(obj) | Expr::Angle(obj) => {
+            object_points([obj], points)
+        }
+        Expr::Binary(lhs, _, rhs) | Expr::Product(lhs, rhs) => {
+            expr_points(lhs, points);
+            expr_points(rhs, points);
+        }
+        Expr::Number(_) | Expr::Variable(_) | Expr::Constant(_) => {}
+    }
+}
@@ -0,0 +1,501 @@
+//! A Language Server Protocol server for `.gsl` files, spoken over any byte
+//! stream (stdio for `gslc lsp`). Documents are synced in full; every change
+//! is re-translated to publish diagnostics.
+
+use crate::ast::Statement;
+use crate::checker::{self, SymbolKind};
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::include::{self, Source};
+use crate::json::Json;
+use crate::lexer::Span;
+use crate::parser;
+use crate::translator::Translator;
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+/// Statement prefixes offered by completion, with what they construct.
+const STATEMENTS: [(&str, &str); 8] = [
+    ("P:", "point"),
+    ("S:", "segment"),
+    ("L:", "line"),
+    ("W:", "ray"),
+    ("C:", "circle"),
+    ("J:", "polygon"),
+    ("R:", "regular polygon"),
+    ("G:", "graph"),
+];
+
+/// Serve requests from `input` until the client sends `exit` or closes the
+/// stream.
+pub fn serve(mut input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    let mut server = Server {
+        translator: Translator::new(),
+        documents: HashMap::new(),
+    };
+    while let Some(body) = read_message(&mut input)? {
+        let message = match Json::parse(&body) {
+            Ok(message) => message,
+            Err(e) => {
+                let error = error_response(Json::Null, -32700, &e);
+                write_message(&mut output, &error)?;
+                continue;
+            }
+        };
+        if method(&message) == Some("exit") {
+            break;
+        }
+        for reply in server.handle(&message) {
+            write_message(&mut output, &reply)?;
+        }
+    }
+    Ok(())
+}
+
+/// One message body, or `None` at the end of the stream.
+fn read_message(input: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            if length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+    let mut body = vec![0; length.unwrap_or(0)];
+    input.read_exact(&mut body)?;
+    Ok(Some(String::from_utf8_lossy(&body).into_owned()))
+}
+
+fn write_message(output: &mut impl Write, message: &Json) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()
+}
+
+fn method(message: &Json) -> Option<&str> {
+    message.get("method").and_then(Json::as_str)
+}
+
+fn response(id: Json, result: Json) -> Json {
+    Json::object([
+        ("jsonrpc", Json::from("2.0")),
+        ("id", id),
+        ("result", result),
+    ])
+}
+
+fn error_response(id: Json, code: i32, message: &str) -> Json {
+    Json::object([
+        ("jsonrpc", Json::from("2.0")),
+        ("id", id),
+        (
+            "error",
+            Json::object([
+                ("code", Json::Number(code.to_string())),
+                ("message", Json::from(message)),
+            ]),
+        ),
+    ])
+}
+
+fn notification(method: &str, params: Json) -> Json {
+    Json::object([
+        ("jsonrpc", Json::from("2.0")),
+        ("method", Json::from(method)),
+        ("params", params),
+    ])
+}
+
+/// The LSP position of a byte offset: a line and a UTF-16 column.
+fn position(text: &str, offset: usize) -> Json {
+    let offset = offset.min(text.len());
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let line = before.matches('\n').count();
+    let character: usize = before[line_start..].chars().map(char::len_utf16).sum();
+    Json::object([
+        ("line", Json::from(line)),
+        ("character", Json::from(character)),
+    ])
+}
+
+/// The byte offset of an LSP position, clamped to the text.
+fn offset(text: &str, position: &Json) -> usize {
+    let line = position.get("line").and_then(Json::as_usize).unwrap_or(0);
+    let character = position
+        .get("character")
+        .and_then(Json::as_usize)
+        .unwrap_or(0);
+    let mut start = 0;
+    for _ in 0..line {
+        match text[start..].find('\n') {
+            Some(i) => start += i + 1,
+            None => return text.len(),
+        }
+    }
+    let mut units = 0;
+    for (i, c) in text[start..].char_indices() {
+        if units >= character || c == '\n' {
+            return start + i;
+        }
+        units += c.len_utf16();
+    }
+    text.len()
+}
+
+fn range(text: &str, span: Span) -> Json {
+    Json::object([
+        ("start", position(text, span.start)),
+        ("end", position(text, span.end)),
+    ])
+}
+
+fn location(uri: &str, text: &str, span: Span) -> Json {
+    Json::object([("uri", Json::from(uri)), ("range", range(text, span))])
+}
+
+fn statements(text: &str) -> Vec<Statement> {
+    parser::parse(text).into_iter().flatten().collect()
+}
+
+struct Server {
+    translator: Translator,
+    documents: HashMap<String, String>,
+}
+
+impl Server {
+    /// Replies and notifications for one client message.
+    fn handle(&mut self, message: &Json) -> Vec<Json> {
+        let id = message.get("id").cloned();
+        let params = message.get("params").cloned().unwrap_or(Json::Null);
+        let uri = params
+            .get("textDocument")
+            .and_then(|d| d.get("uri"))
+            .and_then(Json::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let result = match method(message).unwrap_or_default() {
+            "initialize" => capabilities(),
+            "shutdown" => Json::Null,
+            "textDocument/didOpen" => {
+                let text = params
+                    .get("textDocument")
+                    .and_then(|d| d.get("text"))
+                    .and_then(Json::as_str)
+                    .unwrap_or_default();
+                return self.update(uri, text.to_string());
+            }
+            "textDocument/didChange" => {
+                let text = match params.get("contentChanges") {
+                    Some(Json::Array(changes)) => changes
+                        .last()
+                        .and_then(|c| c.get("text"))
+                        .and_then(Json::as_str),
+                    _ => None,
+                };
+                return match text {
+                    Some(text) => self.update(uri, text.to_string()),
+                    None => Vec::new(),
+                };
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                return vec![publish(&uri, Vec::new())];
+            }
+            "textDocument/hover" => self.with_document(&uri, &params, Server::hover),
+            "textDocument/completion" => self.with_document(&uri, &params, Server::completion),
+            "textDocument/definition" => self.with_document(&uri, &params, Server::definition),
+            "textDocument/documentSymbol" => match self.documents.get(&uri) {
+                Some(text) => document_symbols(text),
+                None => Json::Null,
+            },
+            other => {
+                // Notifications we do not act on need no reply.
+                return match id {
+                    Some(id) => vec![error_response(
+                        id,
+                        -32601,
+                        &format!("unsupported method `{}`", other),
+                    )],
+                    None => Vec::new(),
+                };
+            }
+        };
+        match id {
+            Some(id) => vec![response(id, result)],
+            None => Vec::new(),
+        }
+    }
+
+    fn update(&mut self, uri: String, text: String) -> Vec<Json> {
+        let source = load(&uri, &text);
+        let problems = self.translator.translate_problems(&source.text);
+        let diagnostics = source
+            .diagnostics
+            .iter()
+            .chain(problems.iter().flat_map(|p| &p.translation.diagnostics))
+            .map(|d| diagnostic(&uri, &text, &source.root_diagnostic(d)))
+            .collect();
+        let published = publish(&uri, diagnostics);
+        self.documents.insert(uri, text);
+        vec![published]
+    }
+
+    /// Run a positional request against the document it names.
+    fn with_document(
+        &self,
+        uri: &str,
+        params: &Json,
+        request: fn(&Server, &str, &str, usize) -> Json,
+    ) -> Json {
+        match (self.documents.get(uri), params.get("position")) {
+            (Some(text), Some(at)) => request(self, uri, text, offset(text, at)),
+            _ => Json::Null,
+        }
+    }
+
+    /// The English for the statement under the cursor.
+    fn hover(&self, uri: &str, text: &str, at: usize) -> Json {
+        let source = load(uri, text);
+        let problems = self.translator.translate_problems(&source.text);
+        let Some((step, span)) = problems
+            .iter()
+            .flat_map(|p| &p.translation.steps)
+            .filter(|s| source.is_root(s.statement.span))
+            .map(|s| (s, source.root_span(s.statement.span)))
+            .find(|(_, span)| span.start <= at && at <= span.end)
+        else {
+            return Json::Null;
+        };
+        let mut value = format!("```gsl\n{}\n```\n{}", step.source, step.english);
+        if let Some(answer) = step.answer_text() {
+            value.push_str(&format!(" → {}", answer));
+        }
+        Json::object([
+            (
+                "contents",
+                Json::object([
+                    ("kind", Json::from("markdown")),
+                    ("value", Json::from(&value)),
+                ]),
+            ),
+            ("range", range(text, span)),
+        ])
+    }
+
+    /// Codes from the translator's tables, and any the document defines,
+    /// that extend the word before the cursor.
+    fn completion(&self, uri: &str, text: &str, at: usize) -> Json {
+        let before = &text[..at];
+        let start = before
+            .char_indices()
+            .rev()
+            .take_while(|&(_, c)| c.is_alphanumeric() || c == '_' || c == '\\')
+            .last()
+            .map_or(at, |(i, _)| i);
+        let word = &text[start..at];
+        let after_star = before[..start].ends_with('*');
+
+        // (label, detail, CompletionItemKind)
+        let mut items: Vec<(String, &str, usize)> = Vec::new();
+        let definitions = self
+            .translator
+            .translate_problems(&load(uri, text).text)
+            .pop()
+            .map(|problem| problem.translation.definitions)
+            .unwrap_or_default();
+        let tables = self.translator.with_definitions(&definitions);
+        if after_star {
+            items.extend(
+                tables
+                    .properties
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v, 10)),
+            );
+            items.extend(
+                tables
+                    .relationships
+                    .iter()
+                    .filter(|(k, _)| k.is_ascii())
+                    .map(|(k, v)| (k.to_string(), v, 24)),
+            );
+        } else if word.starts_with('_') {
+            items.extend(tables.theorems.iter().map(|(k, v)| (k.to_string(), v, 18)));
+        } else if word.starts_with('\\') {
+            items.extend(
+                tables
+                    .constants
+                    .iter()
+                    .filter(|(k, _)| k.starts_with('\\'))
+                    .map(|(k, v)| (k.to_string(), v, 21)),
+            );
+        } else {
+            items.extend(STATEMENTS.iter().map(|(k, v)| (k.to_string(), *v, 14)));
+            items.extend(
+                tables
+                    .derived_constructions
+                    .iter()
+                    .map(|(k, v)| (format!("{}:", k), v, 3)),
+            );
+        }
+        items.retain(|(label, _, _)| label.starts_with(word));
+        items.sort();
+        items.dedup_by(|a, b| a.0 == b.0);
+        Json::array(items, |(label, detail, kind)| {
+            Json::object([
+                ("label", Json::from(&label)),
+                ("detail", Json::from(detail)),
+                ("kind", Json::from(kind)),
+            ])
+        })
+    }
+
+    /// Where the point under the cursor was constructed.
+    fn definition(&self, uri: &str, text: &str, at: usize) -> Json {
+        let Some(name) = point_at(text, at) else {
+            return Json::Null;
+        };
+        let symbols = checker::symbols(&statements(text));
+        match symbols.lookup(SymbolKind::Point, &[name]) {
+            Some(span) => location(uri, text, span),
+            None => Json::Null,
+        }
+    }
+}
+
+fn capabilities() -> Json {
+    Json::object([
+        (
+            "capabilities",
+            Json::object([
+                ("textDocumentSync", Json::from(1usize)),
+                ("hoverProvider", Json::from(true)),
+                (
+                    "completionProvider",
+                    Json::object([(
+                        "triggerCharacters",
+                        Json::array(["_", "*", "\\"], Json::from),
+                    )]),
+                ),
+                ("definitionProvider", Json::from(true)),
+                ("documentSymbolProvider", Json::from(true)),
+            ]),
+        ),
+        (
+            "serverInfo",
+            Json::object([
+                ("name", Json::from("gslc")),
+                ("version", Json::from(env!("CARGO_PKG_VERSION"))),
+            ]),
+        ),
+    ])
+}
+
+fn publish(uri: &str, diagnostics: Vec<Json>) -> Json {
+    notification(
+        "textDocument/publishDiagnostics",
+        Json::object([
+            ("uri", Json::from(uri)),
+            ("diagnostics", Json::Array(diagnostics)),
+        ]),
+    )
+}
+
+/// The document with what it includes read in, when it is a file on disk.
+fn load(uri: &str, text: &str) -> Source {
+    let path = uri.strip_prefix("file://").map(Path::new);
+    Source::new(uri, text, path, &include::search_path(&[]))
+}
+
+fn diagnostic(uri: &str, text: &str, d: &Diagnostic) -> Json {
+    let mut message = d.message.clone();
+    if let Some(hint) = &d.hint {
+        message.push_str(&format!("\nhint: {}", hint));
+    }
+    let severity = match d.severity {
+        Severity::Error => 1usize,
+        Severity::Warning => 2,
+    };
+    let mut fields = vec![
+        ("range", range(text, d.span)),
+        ("severity", Json::from(severity)),
+        ("source", Json::from("gslc")),
+        ("message", Json::from(&message)),
+    ];
+    if !d.notes.is_empty() {
+        fields.push((
+            "relatedInformation",
+            Json::array(&d.notes, |note| {
+                Json::object([
+                    ("location", location(uri, text, note.span)),
+                    ("message", Json::from(&note.message)),
+                ])
+            }),
+        ));
+    }
+    Json::object(fields)
+}
+
+/// Every constructed object, as LSP `DocumentSymbol`s in source order.
+fn document_symbols(text: &str) -> Json {
+    let symbols = checker::symbols(&statements(text));
+    Json::array(symbols.iter(), |(kind, name, span)| {
+        // LSP symbol kinds: 13 is Variable, 19 is Object.
+        let lsp_kind = if kind == SymbolKind::Point {
+            13usize
+        } else {
+            19
+        };
+        Json::object([
+            ("name", Json::from(name)),
+            ("detail", Json::from(kind.noun())),
+            ("kind", Json::from(lsp_kind)),
+            ("range", range(text, span)),
+            ("selectionRange", range(text, span)),
+        ])
+    })
+}
+
+/// The point name (a capital letter with any digits or primes after it)
+/// under or just before `at`.
+fn point_at(text: &str, at: usize) -> Option<String> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut i = chars
+        .iter()
+        .position(|&(o, _)| o >= at)
+        .unwrap_or(chars.len());
+    if i == chars.len() || !is_point_char(chars[i].1) {
+        i = i.checked_sub(1)?;
+    }
+    while i > 0 && !chars[i].1.is_ascii_uppercase() && is_point_char(chars[i].1) {
+        i -= 1;
+    }
+    if !chars[i].1.is_ascii_uppercase() {
+        return None;
+    }
+    let name: String = std::iter::once(chars[i].1)
+        .chain(
+            chars[i + 1..]
+                .iter()
+                .map(|&(_, c)| c)
+                .take_while(|&c| c.is_ascii_digit() || c == '\''),
+        )
+        .collect();
+    Some(name)
+}
+
+fn is_point_char(c: char) -> bool {
+    c.is_ascii_uppercase() || c.is_ascii_digit() || c == '\''
+}
@@ -0,0 +1,439 @@
+//! `\def` lines: macros that stand for several statements, and codes added
+//! to the translator's tables.
+//!
+//! A definition applies to the statements after it, in its own problem and
+//! every later one in the file. Macros are expanded before translation: a
+//! call such as `FOOT:DEF` is replaced by the macro's statements with its
+//! parameters renamed to the points it was called with. Points in the body
+//! that are not parameters keep their names. Each expanded statement spans
+//! the call, and anything reported about it gets a note pointing at the
+//! definition.
+
+use crate::ast::*;
+use crate::diagnostic::{Diagnostic, Note};
+use crate::lexer::Span;
+use crate::Translator;
+use std::borrow::Cow;
+
+/// Calls nested deeper than this are taken to be a macro calling itself.
+const MAX_DEPTH: usize = 16;
+
+/// Construction prefixes the parser reads itself, which a macro cannot take.
+const RESERVED: &[&str] = &["P", "S", "L", "W", "J", "C", "R", "G"];
+
+/// The table a defined code goes in. Macros are called like constructions,
+/// so they share their names.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Construction,
+    Property,
+    Theorem,
+}
+
+fn entry(definition: &Definition) -> (&str, Kind) {
+    match definition {
+        Definition::Macro { name, .. } => (name, Kind::Construction),
+        Definition::Construction { code, .. } => (code, Kind::Construction),
+        Definition::Property { code, .. } => (code, Kind::Property),
+        Definition::Theorem { code, .. } => (code, Kind::Theorem),
+    }
+}
+
+struct Macro {
+    name: String,
+    params: Vec<String>,
+    body: Vec<Statement>,
+    span: Span,
+}
+
+/// The definitions in force at some point of a file.
+#[derive(Default)]
+pub(crate) struct Scope {
+    macros: Vec<Macro>,
+    /// Codes defined for the tables, and where.
+    codes: Vec<(Definition, Span)>,
+    /// Each expanded call: where it is, and which macro it expanded.
+    expansions: Vec<(Span, usize)>,
+}
+
+impl Scope {
+    /// Take in the definitions among `statements`, which are left out of the
+    /// result along with any includes, and replace each macro call with what
+    /// it stands for.
+    pub(crate) fn expand(
+        &mut self,
+        translator: &Translator,
+        statements: Vec<Statement>,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> Vec<Statement> {
+        let mut expanded = Vec::new();
+        for stmt in statements {
+            match stmt.kind {
+                StmtKind::Define(definition) => {
+                    self.define(translator, definition, stmt.span, diagnostics)
+                }
+                // Read in when the file was loaded
+                StmtKind::Include(_) => {}
+                _ => {
+                    self.call(stmt, 0, &mut expanded, diagnostics);
+                }
+            }
+        }
+        expanded
+    }
+
+    /// The codes defined so far for the translator's tables.
+    pub(crate) fn definitions(&self) -> Vec<Definition> {
+        self.codes.iter().map(|(d, _)| d.clone()).collect()
+    }
+
+    /// Report codes in `stmt` that are defined, but only further on.
+    pub(crate) fn check_order(&self, stmt: &Statement, diagnostics: &mut Vec<Diagnostic>) {
+        stmt.walk(&mut |s| {
+            let mut used: Vec<(&str, Kind)> = match &s.kind {
+                StmtKind::Derived { code, .. } => vec![(code, Kind::Construction)],
+                StmtKind::Citation(code) => vec![(code, Kind::Theorem)],
+                StmtKind::Property { property, .. } => vec![(property, Kind::Property)],
+                StmtKind::Relationship { relation, .. } => vec![(relation, Kind::Property)],
+                StmtKind::Polygon { properties, .. } => properties
+                    .iter()
+                    .map(|p| (p.as_str(), Kind::Property))
+                    .collect(),
+                _ => Vec::new(),
+            };
+            used.extend(s.theorem.as_deref().map(|code| (code, Kind::Theorem)));
+            for (code, kind) in used {
+                let later = self.codes.iter().find(|(definition, span)| {
+                    entry(definition) == (code, kind) && span.start > s.span.start
+                });
+                if let Some((_, span)) = later {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            format!("`{}` is used before it is defined", code),
+                            s.span,
+                        )
+                        .with_note("defined here", *span),
+                    );
+                }
+            }
+        });
+    }
+
+    /// Point each diagnostic about an expanded call at the macro's
+    /// definition as well.
+    pub(crate) fn annotate(&self, diagnostics: &mut [Diagnostic]) {
+        for diagnostic in diagnostics {
+            let call = self.expansions.iter().find(|(call, _)| {
+                call.start <= diagnostic.span.start && diagnostic.span.end <= call.end
+            });
+            if let Some(&(_, index)) = call {
+                let definition = &self.macros[index];
+                if diagnostic.notes.iter().any(|n| n.span == definition.span) {
+                    continue;
+                }
+                diagnostic.notes.push(Note {
+                    message: format!("in the expansion of `{}`, defined here", definition.name),
+                    span: definition.span,
+                });
+            }
+        }
+    }
+
+    fn define(
+        &mut self,
+        translator: &Translator,
+        definition: Definition,
+        span: Span,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let (code, kind) = entry(&definition);
+        if kind == Kind::Construction && RESERVED.contains(&code) {
+            diagnostics.push(
+                Diagnostic::error(format!("`{}:` is built into GSL", code), span)
+                    .with_hint("choose a name that is not a construction prefix"),
+            );
+            return;
+        }
+        let built_in = match kind {
+            Kind::Construction => translator.derived_constructions.get(code),
+            Kind::Property => translator
+                .properties
+                .get(code)
+                .or_else(|| translator.relationships.get(code)),
+            Kind::Theorem => translator.theorems.get(code),
+        };
+        if let Some(name) = built_in {
+            diagnostics.push(
+                Diagnostic::error(format!("`{}` already means {}", code, name), span)
+                    .with_hint("choose a code that is not already in use"),
+            );
+            return;
+        }
+        let earlier = self
+            .macros
+            .iter()
+            .map(|m| (m.name.as_str(), Kind::Construction, m.span))
+            .chain(self.codes.iter().map(|(d, span)| {
+                let (code, kind) = entry(d);
+                (code, kind, *span)
+            }))
+            .find(|&(c, k, _)| c == code && k == kind);
+        if let Some((_, _, earlier)) = earlier {
+            diagnostics.push(
+                Diagnostic::error(format!("`{}` is defined twice", code), span)
+                    .with_note("first defined here", earlier),
+            );
+            return;
+        }
+
+        match definition {
+            Definition::Macro { name, params, body } => {
+                if let Some(param) = params
+                    .iter()
+                    .enumerate()
+                    .find_map(|(i, p)| params[..i].contains(p).then_some(p))
+                {
+                    diagnostics.push(Diagnostic::error(
+                        format!("`{}` names the parameter `{}` twice", name, param),
+                        span,
+                    ));
+                    return;
+                }
+                self.macros.push(Macro {
+                    name,
+                    params,
+                    body,
+                    span,
+                });
+            }
+            definition => self.codes.push((definition, span)),
+        }
+    }
+
+    /// Push `stmt`, or what it expands to if it calls a macro, onto `out`.
+    /// Returns false, having pushed nothing, for a macro that expands into
+    /// itself, so the partial expansion is not checked and reported on too.
+    fn call(
+        &mut self,
+        stmt: Statement,
+        depth: usize,
+        out: &mut Vec<Statement>,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> bool {
+        let StmtKind::Derived { code, args, target } = &stmt.kind else {
+            out.push(stmt);
+            return true;
+        };
+        let Some(index) = self.macros.iter().position(|m| &m.name == code) else {
+            out.push(stmt);
+            return true;
+        };
+        let definition = &self.macros[index];
+        let note = format!("`{}` is defined here", definition.name);
+        if depth >= MAX_DEPTH {
+            diagnostics.push(
+                Diagnostic::error(format!("`{}` expands into itself", code), stmt.span)
+                    .with_note(note, definition.span),
+            );
+            return false;
+        }
+        if target.is_some() {
+            diagnostics.push(
+                Diagnostic::error(
+                    format!("macro `{}` does not name a result", code),
+                    stmt.span,
+                )
+                .with_hint("pass the point to name as one of its parameters")
+                .with_note(note, definition.span),
+            );
+            return true;
+        }
+        let points: Vec<&String> = args.iter().flat_map(|arg| &arg.points).collect();
+        if points.len() != definition.params.len() {
+            diagnostics.push(
+                Diagnostic::error(
+                    format!(
+                        "`{}` takes {} points ({}), but {} were given",
+                        code,
+                        definition.params.len(),
+                        definition.params.concat(),
+                        points.len()
+                    ),
+                    stmt.span,
+                )
+                .with_note(note, definition.span),
+            );
+            return true;
+        }
+
+        let rename = |point: &str| {
+            definition
+                .params
+                .iter()
+                .position(|p| p == point)
+                .map_or_else(|| point.to_string(), |i| points[i].clone())
+        };
+        let body: Vec<Statement> = definition
+            .body
+            .iter()
+            .map(|body| {
+                let mut body = body.clone();
+                rename_statement(&mut body, &rename, stmt.span);
+                body
+            })
+            .collect();
+        let mut expanded = Vec::new();
+        for body in body {
+            if !self.call(body, depth + 1, &mut expanded, diagnostics) {
+                return false;
+            }
+        }
+        if depth == 0 {
+            self.expansions.push((stmt.span, index));
+        }
+        out.append(&mut expanded);
+        true
+    }
+}
+
+impl Translator {
+    /// This translator with codes from `\def` lines added to its tables.
+    pub fn with_definitions(&self, definitions: &[Definition]) -> Cow<'_, Translator> {
+        if definitions.is_empty() {
+            return Cow::Borrowed(self);
+        }
+        let mut translator = self.clone();
+        for definition in definitions.iter().cloned() {
+            match definition {
+                Definition::Construction { code, name } => {
+                    translator.derived_constructions.insert(code, name)
+                }
+                Definition::Property { code, name } => translator.properties.insert(code, name),
+                Definition::Theorem { code, name } => translator.theorems.insert(code, name),
+                Definition::Macro { .. } => {}
+            }
+        }
+        Cow::Owned(translator)
+    }
+}
+
+/// Rename every point in `stmt` and give it, and everything nested in it,
+/// the span `span`.
+fn rename_statement(stmt: &mut Statement, rename: &impl Fn(&str) -> String, span: Span) {
+    stmt.span = span;
+    if stmt.theorem.is_some() {
+        stmt.theorem_span = span;
+    }
+    let names = |names: &mut Vec<String>| {
+        for name in names {
+            *name = rename(name);
+        }
+    };
+    match &mut stmt.kind {
+        StmtKind::Prove(inner)
+        | StmtKind::ProveByContradiction(inner)
+        | StmtKind::Quantified(_, inner)
+        | StmtKind::ProveQuery(inner) => rename_statement(inner, rename, span),
+        StmtKind::Logic(_, lhs, rhs) => {
+            rename_statement(lhs, rename, span);
+            rename_statement(rhs, rename, span);
+        }
+        StmtKind::Casework { main, cases } => {
+            if let Some(main) = main {
+                rename_statement(main, rename, span);
+            }
+            for case in cases.iter_mut().flatten() {
+                rename_statement(&mut case.condition, rename, span);
+                rename_statement(&mut case.result, rename, span);
+            }
+        }
+        StmtKind::Derived { args, target, .. } => {
+            rename_objects(args, rename);
+            if let Some(target) = target {
+                *target = rename(target);
+            }
+        }
+        StmtKind::Point(decl) => match decl {
+            PointDecl::Free(points) => names(points),
+            PointDecl::Coordinates { name, .. } => *name = rename(name),
+            PointDecl::OnObject {
+                name,
+                object,
+                conditions,
+            } => {
+                *name = rename(name);
+                rename_object(object, rename);
+                for condition in conditions {
+                    rename_statement(condition, rename, span);
+                }
+            }
+            PointDecl::InRegion { name, region } => {
+                *name = rename(name);
+                rename_object(region, rename);
+            }
+            PointDecl::Intersection { name, objects } => {
+                *name = rename(name);
+                rename_objects(objects, rename);
+            }
+        },
+        StmtKind::Linear(_, objects)
+        | StmtKind::Polygon { objects, .. }
+        | StmtKind::Relationship { objects, .. } => rename_objects(objects, rename),
+        StmtKind::Circle(decl) => match decl {
+            CircleDecl::ThroughPoints(points) => names(points),
+            CircleDecl::CenterRadius { center, radius } => {
+                *center = rename(center);
+                rename_expr(radius, rename);
+            }
+            CircleDecl::CenterThrough { center, point } => {
+                *center = rename(center);
+                *point = rename(point);
+            }
+            CircleDecl::Through(point) => *point = rename(point),
+            CircleDecl::Named(_) => {}
+        },
+        StmtKind::RegularPolygon(poly) => {
+            rename_object(&mut poly.side, rename);
+            rename_object(&mut poly.polygon, rename);
+        }
+        StmtKind::Relation(lhs, _, rhs) => {
+            rename_expr(lhs, rename);
+            rename_expr(rhs, rename);
+        }
+        StmtKind::Query(expr) | StmtKind::Value(expr) => rename_expr(expr, rename),
+        StmtKind::Property { object, .. } | StmtKind::Orientation { object, .. } => {
+            rename_object(object, rename)
+        }
+        StmtKind::Marker(_)
+        | StmtKind::EndCasework
+        | StmtKind::Graph(_)
+        | StmtKind::Citation(_)
+        | StmtKind::Define(_)
+        | StmtKind::Include(_) => {}
+    }
+}
+
+fn rename_objects(objects: &mut [Object], rename: &impl Fn(&str) -> String) {
+    for object in objects {
+        rename_object(object, rename);
+    }
+}
+
+fn rename_object(object: &mut Object, rename: &impl Fn(&str) -> String) {
+    for point in &mut object.points {
+        *point = rename(point);
+    }
+}
+
+fn rename_expr(expr: &mut Expr, rename: &impl Fn(&str) -> String) {
+    match expr {
+        Expr::Object(obj) | Expr::Area(obj) | Expr::Perimeter(obj) | Expr::Angle(obj) => {
+            rename_object(obj, rename)
+        }
+        Expr::Binary(lhs, _, rhs) | Expr::Product(lhs, rhs) => {
+            rename_expr(lhs, rename);
+            rename_expr(rhs, rename);
+        }
+        Expr::Number(_) | Expr::Variable(_) | Expr::Constant(_) => {}
+    }
+}
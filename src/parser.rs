@@ -0,0 +1,1106 @@
+use crate::ast::*;
+use crate::lexer::{tokenize, Span, Token, TokenKind};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    /// The offending token.
+    pub span: Span,
+    /// The whole statement the error occurred in.
+    pub statement: Span,
+    /// The expected syntax of the construct being parsed.
+    pub hint: Option<String>,
+}
+
+/// Parse a problem into statements. A statement that fails to parse yields
+/// an error and parsing resumes at the next `/`. Metadata lines are ignored.
+pub fn parse(input: &str) -> Vec<Result<Statement, ParseError>> {
+    let tokens = tokenize(input)
+        .into_iter()
+        .filter(|t| !matches!(t.kind, TokenKind::Meta(..)))
+        .collect();
+    Parser::new(tokens, input.len()).program()
+}
+
+/// One `@key: value` line heading a problem.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub key: String,
+    pub value: String,
+    pub span: Span,
+}
+
+/// One problem of a file: its metadata lines and its statements.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedProblem {
+    pub fields: Vec<Field>,
+    pub statements: Vec<Result<Statement, ParseError>>,
+}
+
+/// Parse a file that may hold several `\\...\\` problems, each into its
+/// own list of statements. Metadata lines belong to the problem they come
+/// before, and an `@problem` line starts a new one even without wrappers.
+/// Spans stay relative to the whole file. Input without wrappers or
+/// `@problem` lines is one problem.
+pub fn parse_problems(input: &str) -> Vec<ParsedProblem> {
+    let mut groups = vec![Vec::new()];
+    let mut open = false;
+    for token in tokenize(input) {
+        let current = groups.last_mut().unwrap();
+        let starts = !open
+            && matches!(&token.kind, TokenKind::Meta(key, _) if key == "problem")
+            && current
+                .iter()
+                .any(|t: &Token| !matches!(t.kind, TokenKind::Meta(..) | TokenKind::Newline));
+        if starts {
+            groups.push(Vec::new());
+        }
+        let closes = token.kind == TokenKind::Wrap && open;
+        if token.kind == TokenKind::Wrap {
+            open = !open;
+        }
+        groups.last_mut().unwrap().push(token);
+        if closes {
+            groups.push(Vec::new());
+        }
+    }
+    groups
+        .into_iter()
+        .map(|tokens| {
+            let (meta, tokens): (Vec<Token>, Vec<Token>) = tokens
+                .into_iter()
+                .partition(|t| matches!(t.kind, TokenKind::Meta(..)));
+            let fields = meta
+                .into_iter()
+                .map(|t| match t.kind {
+                    TokenKind::Meta(key, value) => Field {
+                        key,
+                        value,
+                        span: t.span,
+                    },
+                    _ => unreachable!(),
+                })
+                .collect();
+            ParsedProblem {
+                fields,
+                statements: Parser::new(tokens, input.len()).program(),
+            }
+        })
+        .filter(|problem| !problem.fields.is_empty() || !problem.statements.is_empty())
+        .collect()
+}
+
+struct Failure {
+    message: String,
+    span: Span,
+    hint: Option<&'static str>,
+}
+
+type PResult<T> = Result<T, Failure>;
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    eof: Span,
+    /// Syntax reminder for the construct currently being parsed.
+    hint: Option<&'static str>,
+}
+
+fn is_separator(kind: &TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::Slash | TokenKind::Newline | TokenKind::Wrap
+    )
+}
+
+fn describe(kind: &TokenKind) -> String {
+    match kind {
+        TokenKind::Wrap => "`\\\\`".to_string(),
+        TokenKind::Prefix(p) => format!("`{}:`", p),
+        TokenKind::Command(c) => format!("`\\{}`", c),
+        TokenKind::Name(n) => format!("`{}`", n),
+        TokenKind::Lower(c) | TokenKind::Constant(c) | TokenKind::Symbol(c) => {
+            format!("`{}`", c)
+        }
+        TokenKind::Number(n) => format!("`{}`", n),
+        TokenKind::Theorem(t) => format!("`{}`", t),
+        TokenKind::Braced(b) => format!("`{{{}}}`", b),
+        TokenKind::Quoted(q) => format!("`\"{}\"`", q),
+        TokenKind::ProveQuery => "`\\?`".to_string(),
+        TokenKind::Slash => "`/`".to_string(),
+        TokenKind::Newline => "end of line".to_string(),
+        TokenKind::Colon => "`:`".to_string(),
+        TokenKind::Semi => "`;`".to_string(),
+        TokenKind::Comma => "`,`".to_string(),
+        TokenKind::Dot => "`.`".to_string(),
+        TokenKind::DotDot => "`..`".to_string(),
+        TokenKind::Question => "`?`".to_string(),
+        TokenKind::Eq => "`=`".to_string(),
+        TokenKind::NotEq => "`!=`".to_string(),
+        TokenKind::Lt => "`<`".to_string(),
+        TokenKind::Le => "`<=`".to_string(),
+        TokenKind::Gt => "`>`".to_string(),
+        TokenKind::Ge => "`>=`".to_string(),
+        TokenKind::Pipe => "`|`".to_string(),
+        TokenKind::OrOr => "`||`".to_string(),
+        TokenKind::AndAnd => "`&&`".to_string(),
+        TokenKind::Implies => "`=>`".to_string(),
+        TokenKind::Bang => "`!`".to_string(),
+        TokenKind::Star => "`*`".to_string(),
+        TokenKind::Plus => "`+`".to_string(),
+        TokenKind::Minus => "`-`".to_string(),
+        TokenKind::LBracket => "`[`".to_string(),
+        TokenKind::RBracket => "`]`".to_string(),
+        TokenKind::LParen => "`(`".to_string(),
+        TokenKind::RParen => "`)`".to_string(),
+        TokenKind::CaseOpen => "`<<`".to_string(),
+        TokenKind::CaseClose => "`>>`".to_string(),
+        TokenKind::ForAll => "`|A`".to_string(),
+        TokenKind::Exists => "`|E`".to_string(),
+        TokenKind::Meta(key, _) => format!("`@{}`", key),
+        TokenKind::Invalid(_) => "invalid input".to_string(),
+    }
+}
+
+fn construction_hint(prefix: &str) -> &'static str {
+    match prefix {
+        "P" => "points are written `P:A`, `P:A,B`, `P:A{x,y}`, `P:C.AB`, `P:C.AB|condition`, `P:C..ABC` or `P:X=ABxCD`",
+        "S" | "L" | "W" => "segments, lines and rays are written `S:AB`, `L:AB`, `W:AB`; separate several with `,`",
+        "J" => "polygons are written `J:ABC`, optionally with properties as in `J:ABC*R`",
+        "C" => "circles are written `C:O;r`, `C:O;A`, `C:A;B;C`, `C:A` or `C:=name`",
+        "R" => "regular polygons are written `R:n;AB=NAME`, e.g. `R:4;AB=ABCD`",
+        "G" => "graphs are written `G:{y=x^2}`",
+        _ => "derived constructions are written `CODE:ABC`, optionally naming the result as in `CCO:ABC=O`",
+    }
+}
+
+/// Split a run such as `A1B'C` into its point names.
+pub fn split_points(name: &str) -> Vec<String> {
+    let mut points: Vec<String> = Vec::new();
+    for ch in name.chars() {
+        match points.last_mut() {
+            Some(last) if !ch.is_ascii_uppercase() => last.push(ch),
+            _ => points.push(ch.to_string()),
+        }
+    }
+    points
+}
+
+impl Parser {
+    fn new(tokens: Vec<Token>, len: usize) -> Self {
+        Parser {
+            tokens,
+            pos: 0,
+            eof: Span::new(len, len),
+            hint: None,
+        }
+    }
+
+    fn peek(&self) -> Option<&TokenKind> {
+        self.tokens.get(self.pos).map(|t| &t.kind)
+    }
+
+    fn peek_at(&self, n: usize) -> Option<&TokenKind> {
+        self.tokens.get(self.pos + n).map(|t| &t.kind)
+    }
+
+    fn span(&self) -> Span {
+        self.tokens
+            .get(self.pos)
+            .map(|t| t.span)
+            .unwrap_or(self.eof)
+    }
+
+    fn prev_span(&self) -> Span {
+        self.pos
+            .checked_sub(1)
+            .and_then(|i| self.tokens.get(i))
+            .map(|t| t.span)
+            .unwrap_or(self.eof)
+    }
+
+    /// True when the token after the current one starts exactly where it ends.
+    fn next_is_adjacent(&self) -> bool {
+        match (self.tokens.get(self.pos), self.tokens.get(self.pos + 1)) {
+            (Some(a), Some(b)) => a.span.end == b.span.start,
+            _ => false,
+        }
+    }
+
+    fn at_end(&self) -> bool {
+        self.peek().is_none_or(is_separator)
+    }
+
+    fn bump(&mut self) -> Option<TokenKind> {
+        let tok = self.tokens.get(self.pos)?.kind.clone();
+        self.pos += 1;
+        Some(tok)
+    }
+
+    fn eat(&mut self, kind: &TokenKind) -> bool {
+        if self.peek() == Some(kind) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn fail<T>(&self, message: String, span: Span) -> PResult<T> {
+        Err(Failure {
+            message,
+            span,
+            hint: self.hint,
+        })
+    }
+
+    fn unexpected<T>(&self, expected: &str) -> PResult<T> {
+        let (found, span) = match self.peek() {
+            Some(TokenKind::Invalid(message)) => return self.fail(message.clone(), self.span()),
+            Some(kind) if !is_separator(kind) => (describe(kind), self.span()),
+            // Point just past the last token rather than at the separator.
+            _ => {
+                let end = self.prev_span().end;
+                ("end of statement".to_string(), Span::new(end, end))
+            }
+        };
+        self.fail(format!("expected {}, found {}", expected, found), span)
+    }
+
+    /// Run `f` with `hint` describing the syntax it expects.
+    fn with_hint<T>(
+        &mut self,
+        hint: &'static str,
+        f: impl FnOnce(&mut Self) -> PResult<T>,
+    ) -> PResult<T> {
+        let saved = self.hint.replace(hint);
+        let result = f(self);
+        self.hint = saved;
+        result
+    }
+
+    /// The span from `start` to the end of the last consumed token.
+    fn since(&self, start: Span) -> Span {
+        start.to(self.prev_span())
+    }
+
+    fn expect(&mut self, kind: TokenKind, expected: &str) -> PResult<()> {
+        if self.eat(&kind) {
+            Ok(())
+        } else {
+            self.unexpected(expected)
+        }
+    }
+
+    fn program(mut self) -> Vec<Result<Statement, ParseError>> {
+        let mut statements = Vec::new();
+        loop {
+            while self.peek().is_some_and(is_separator) {
+                self.pos += 1;
+            }
+            if self.peek().is_none() {
+                break;
+            }
+
+            let start = self.span();
+            self.hint = None;
+            let result = self.statement().and_then(|stmt| {
+                let stmt = self.citation(stmt);
+                // `∴` and `∵` may share a line with the statement they introduce
+                let introduces = matches!(
+                    stmt.kind,
+                    StmtKind::Marker(ProofMarker::Because | ProofMarker::Therefore)
+                );
+                if self.at_end() || introduces {
+                    Ok(stmt)
+                } else {
+                    self.unexpected("`/` before the next statement")
+                }
+            });
+            match result {
+                Ok(stmt) => statements.push(Ok(stmt)),
+                Err(failure) => {
+                    while !self.at_end() {
+                        self.pos += 1;
+                    }
+                    statements.push(Err(ParseError {
+                        message: failure.message,
+                        span: failure.span,
+                        statement: self.since(start),
+                        hint: failure.hint.map(str::to_string),
+                    }));
+                }
+            }
+        }
+        statements
+    }
+
+    /// `stmt` with the theorem cited after it, as in `AC=5_PY`. Markers and
+    /// citations cannot be justified themselves.
+    fn citation(&mut self, mut stmt: Statement) -> Statement {
+        if matches!(stmt.kind, StmtKind::Marker(_) | StmtKind::Citation(_)) {
+            return stmt;
+        }
+        if let Some(TokenKind::Theorem(code)) = self.peek().cloned() {
+            self.pos += 1;
+            stmt.theorem = Some(code);
+            stmt.theorem_span = self.prev_span();
+            stmt.span = stmt.span.to(self.prev_span());
+        }
+        stmt
+    }
+
+    fn statement(&mut self) -> PResult<Statement> {
+        let start = self.span();
+        let lhs = self.disjunction()?;
+        if self.eat(&TokenKind::Implies) {
+            let rhs = self.statement()?;
+            return Ok(Statement::new(
+                StmtKind::Logic(LogicOp::Implies, Box::new(lhs), Box::new(rhs)),
+                self.since(start),
+            ));
+        }
+        Ok(lhs)
+    }
+
+    fn disjunction(&mut self) -> PResult<Statement> {
+        let start = self.span();
+        let mut lhs = self.conjunction()?;
+        while self.eat(&TokenKind::OrOr) {
+            let rhs = self.conjunction()?;
+            lhs = Statement::new(
+                StmtKind::Logic(LogicOp::Or, Box::new(lhs), Box::new(rhs)),
+                self.since(start),
+            );
+        }
+        Ok(lhs)
+    }
+
+    fn conjunction(&mut self) -> PResult<Statement> {
+        let start = self.span();
+        let mut lhs = self.casework()?;
+        while self.eat(&TokenKind::AndAnd) {
+            let rhs = self.casework()?;
+            lhs = Statement::new(
+                StmtKind::Logic(LogicOp::And, Box::new(lhs), Box::new(rhs)),
+                self.since(start),
+            );
+        }
+        Ok(lhs)
+    }
+
+    fn casework(&mut self) -> PResult<Statement> {
+        let start = self.span();
+        let main = if self.peek() == Some(&TokenKind::CaseOpen) {
+            None
+        } else {
+            let stmt = self.base()?;
+            if self.peek() != Some(&TokenKind::CaseOpen) {
+                return Ok(stmt);
+            }
+            Some(Box::new(stmt))
+        };
+        self.expect(TokenKind::CaseOpen, "`<<`")?;
+
+        if self.at_end() {
+            return Ok(Statement::new(
+                StmtKind::Casework { main, cases: None },
+                self.since(start),
+            ));
+        }
+        let cases = self.with_hint(
+            "casework is written `STATEMENT<<(condition;result),(condition;result)>>`",
+            |p| {
+                let mut cases = Vec::new();
+                loop {
+                    p.expect(TokenKind::LParen, "`(` to open a case")?;
+                    let condition = p.statement()?;
+                    p.expect(TokenKind::Semi, "`;` between a case's condition and result")?;
+                    let result = p.statement()?;
+                    p.expect(TokenKind::RParen, "`)` to close the case")?;
+                    cases.push(Case { condition, result });
+                    if !p.eat(&TokenKind::Comma) {
+                        break;
+                    }
+                }
+                p.expect(TokenKind::CaseClose, "`>>` to close the casework")?;
+                Ok(cases)
+            },
+        )?;
+        Ok(Statement::new(
+            StmtKind::Casework {
+                main,
+                cases: Some(cases),
+            },
+            self.since(start),
+        ))
+    }
+
+    /// What follows `\def`.
+    fn definition(&mut self) -> PResult<StmtKind> {
+        let definition = match (self.peek().cloned(), self.peek_at(1).cloned()) {
+            (Some(TokenKind::Prefix(name)), Some(TokenKind::Name(params))) => {
+                self.pos += 2;
+                Definition::Macro {
+                    name,
+                    params: split_points(&params),
+                    body: self.macro_body()?,
+                }
+            }
+            (Some(TokenKind::Prefix(code)), _) => {
+                self.pos += 1;
+                Definition::Construction {
+                    code,
+                    name: self.braced_name()?,
+                }
+            }
+            (Some(TokenKind::Star), Some(TokenKind::Name(code))) => {
+                self.pos += 2;
+                Definition::Property {
+                    code,
+                    name: self.braced_name()?,
+                }
+            }
+            (Some(TokenKind::Theorem(code)), _) => {
+                self.pos += 1;
+                Definition::Theorem {
+                    code,
+                    name: self.braced_name()?,
+                }
+            }
+            _ => return self.unexpected("`NAME:`, `*CODE` or `_CODE` after `\\def`"),
+        };
+        Ok(StmtKind::Define(definition))
+    }
+
+    /// `{name}` giving the English for a defined code.
+    fn braced_name(&mut self) -> PResult<String> {
+        match self.peek().cloned() {
+            Some(TokenKind::Braced(name)) if !name.trim().is_empty() => {
+                self.pos += 1;
+                Ok(name.trim().to_string())
+            }
+            _ => self.unexpected("the English name in braces, as in `{Nagel point}`"),
+        }
+    }
+
+    /// `{statements}`, parsed with spans into the whole source.
+    fn macro_body(&mut self) -> PResult<Vec<Statement>> {
+        let Some(TokenKind::Braced(body)) = self.peek().cloned() else {
+            return self.unexpected("the statements it stands for in braces");
+        };
+        let span = self.span();
+        self.pos += 1;
+        let offset = span.start + 1;
+        let tokens = tokenize(&body)
+            .into_iter()
+            .map(|token| Token {
+                span: Span::new(token.span.start + offset, token.span.end + offset),
+                ..token
+            })
+            .collect();
+        let mut statements = Vec::new();
+        for parsed in Parser::new(tokens, offset + body.len()).program() {
+            match parsed {
+                Ok(stmt) => statements.push(stmt),
+                Err(err) => {
+                    return Err(Failure {
+                        message: err.message,
+                        span: err.span,
+                        hint: None,
+                    })
+                }
+            }
+        }
+        if statements.is_empty() {
+            return Err(Failure {
+                message: "a macro must stand for at least one statement".to_string(),
+                span,
+                hint: None,
+            });
+        }
+        Ok(statements)
+    }
+
+    /// A single statement without logical connectives or casework.
+    fn base(&mut self) -> PResult<Statement> {
+        let start = self.span();
+        let kind = match self.peek().cloned() {
+            Some(TokenKind::Command(cmd)) => {
+                self.pos += 1;
+                match cmd.as_str() {
+                    "p" | "pC" => {
+                        self.expect(TokenKind::Colon, "`:` after the proof marker")?;
+                        let inner = Box::new(self.statement()?);
+                        if cmd == "p" {
+                            StmtKind::Prove(inner)
+                        } else {
+                            StmtKind::ProveByContradiction(inner)
+                        }
+                    }
+                    "q" => StmtKind::Marker(ProofMarker::Qed),
+                    "qC" => StmtKind::Marker(ProofMarker::Contradiction),
+                    "bc" => StmtKind::Marker(ProofMarker::Because),
+                    "th" => StmtKind::Marker(ProofMarker::Therefore),
+                    "def" => self.with_hint(
+                        "definitions are written `\\def NAME:ABC{statements}`, `\\def CODE:{name}`, `\\def *CODE{name}` or `\\def _CODE{name}`",
+                        |p| p.definition(),
+                    )?,
+                    "include" => match self.peek().cloned() {
+                        Some(TokenKind::Quoted(path)) if !path.trim().is_empty() => {
+                            self.pos += 1;
+                            StmtKind::Include(path)
+                        }
+                        _ => return self.unexpected("a file name in quotes after `\\include`"),
+                    },
+                    _ => {
+                        self.pos -= 1;
+                        return self.expr_statement();
+                    }
+                }
+            }
+            Some(TokenKind::Symbol('□')) => self.marker(ProofMarker::Qed),
+            Some(TokenKind::Symbol('↯')) => self.marker(ProofMarker::Contradiction),
+            Some(TokenKind::Symbol('∵')) => self.marker(ProofMarker::Because),
+            Some(TokenKind::Symbol('∴')) => self.marker(ProofMarker::Therefore),
+            Some(TokenKind::ForAll) => {
+                self.pos += 1;
+                StmtKind::Quantified(Quantifier::ForAll, Box::new(self.statement()?))
+            }
+            Some(TokenKind::Exists) => {
+                self.pos += 1;
+                StmtKind::Quantified(Quantifier::Exists, Box::new(self.statement()?))
+            }
+            Some(TokenKind::CaseClose) => {
+                self.pos += 1;
+                StmtKind::EndCasework
+            }
+            Some(TokenKind::Theorem(code)) => {
+                self.pos += 1;
+                StmtKind::Citation(code)
+            }
+            Some(TokenKind::Prefix(prefix)) => {
+                self.pos += 1;
+                self.with_hint(construction_hint(&prefix), |p| p.construction(&prefix))?
+            }
+            _ => return self.expr_statement(),
+        };
+        Ok(Statement::new(kind, self.since(start)))
+    }
+
+    fn marker(&mut self, marker: ProofMarker) -> StmtKind {
+        self.pos += 1;
+        StmtKind::Marker(marker)
+    }
+
+    fn construction(&mut self, prefix: &str) -> PResult<StmtKind> {
+        match prefix {
+            "P" => Ok(StmtKind::Point(self.point_decl()?)),
+            "S" => Ok(StmtKind::Linear(LinearKind::Segment, self.object_list()?)),
+            "L" => Ok(StmtKind::Linear(LinearKind::Line, self.object_list()?)),
+            "W" => Ok(StmtKind::Linear(LinearKind::Ray, self.object_list()?)),
+            "J" => {
+                let objects = self.object_list()?;
+                let mut properties = Vec::new();
+                while self.eat(&TokenKind::Star) {
+                    properties.push(self.code("a property such as `R` or `CV`")?);
+                }
+                Ok(StmtKind::Polygon {
+                    objects,
+                    properties,
+                })
+            }
+            "C" => Ok(StmtKind::Circle(self.circle_decl()?)),
+            "R" => Ok(StmtKind::RegularPolygon(self.regular_polygon()?)),
+            "G" => match self.peek().cloned() {
+                Some(TokenKind::Braced(eq)) => {
+                    self.pos += 1;
+                    Ok(StmtKind::Graph(eq))
+                }
+                _ => self.unexpected("an equation in braces, e.g. `G:{y=x^2}`"),
+            },
+            code => {
+                let mut args = vec![self.object()?];
+                while self.eat(&TokenKind::Semi) {
+                    args.push(self.object()?);
+                }
+                let target = if self.eat(&TokenKind::Eq) {
+                    Some(self.point_name()?)
+                } else {
+                    None
+                };
+                Ok(StmtKind::Derived {
+                    code: code.to_string(),
+                    args,
+                    target,
+                })
+            }
+        }
+    }
+
+    fn point_name(&mut self) -> PResult<String> {
+        match self.peek().cloned() {
+            Some(TokenKind::Name(name)) if split_points(&name).len() == 1 => {
+                self.pos += 1;
+                Ok(name)
+            }
+            Some(TokenKind::Name(name)) => self.fail(
+                format!(
+                    "`{}` names several points; expected a single point name",
+                    name
+                ),
+                self.span(),
+            ),
+            _ => self.unexpected("a point name"),
+        }
+    }
+
+    fn point_decl(&mut self) -> PResult<PointDecl> {
+        let name = self.point_name()?;
+        match self.peek() {
+            Some(TokenKind::Comma) => {
+                let mut names = vec![name];
+                while self.eat(&TokenKind::Comma) {
+                    names.push(self.point_name()?);
+                }
+                Ok(PointDecl::Free(names))
+            }
+            Some(TokenKind::Pipe) if matches!(self.peek_at(1), Some(TokenKind::Braced(_))) => {
+                self.pos += 1;
+                self.point_decl_coordinates(name)
+            }
+            Some(TokenKind::Braced(_)) => self.point_decl_coordinates(name),
+            Some(TokenKind::Eq) => {
+                self.pos += 1;
+                let mut objects = vec![self.object()?];
+                while self.peek() == Some(&TokenKind::Lower('x')) {
+                    self.pos += 1;
+                    objects.push(self.object()?);
+                }
+                if objects.len() < 2 {
+                    return self.unexpected("`x` between the intersected objects");
+                }
+                Ok(PointDecl::Intersection { name, objects })
+            }
+            Some(TokenKind::DotDot) => {
+                self.pos += 1;
+                let region = self.object()?;
+                Ok(PointDecl::InRegion { name, region })
+            }
+            Some(TokenKind::Dot) => {
+                self.pos += 1;
+                let object = self.object()?;
+                let mut conditions = Vec::new();
+                if self.eat(&TokenKind::Pipe) {
+                    conditions.push(self.base()?);
+                    while self.eat(&TokenKind::Comma) {
+                        conditions.push(self.base()?);
+                    }
+                }
+                Ok(PointDecl::OnObject {
+                    name,
+                    object,
+                    conditions,
+                })
+            }
+            _ => Ok(PointDecl::Free(vec![name])),
+        }
+    }
+
+    fn point_decl_coordinates(&mut self, name: String) -> PResult<PointDecl> {
+        match self.bump() {
+            Some(TokenKind::Braced(coords)) => Ok(PointDecl::Coordinates { name, coords }),
+            _ => unreachable!(),
+        }
+    }
+
+    fn circle_decl(&mut self) -> PResult<CircleDecl> {
+        if self.eat(&TokenKind::Eq) {
+            let name = match self.bump() {
+                Some(TokenKind::Name(n)) => n,
+                Some(TokenKind::Lower(c)) => c.to_string(),
+                _ => {
+                    self.pos -= 1;
+                    return self.unexpected("a circle name after `=`");
+                }
+            };
+            return Ok(CircleDecl::Named(name));
+        }
+
+        let first = match self.peek().cloned() {
+            Some(TokenKind::Name(name)) => {
+                self.pos += 1;
+                name
+            }
+            _ => return self.unexpected("a point name"),
+        };
+        if !self.eat(&TokenKind::Semi) {
+            let points = split_points(&first);
+            return match points.len() {
+                1 => Ok(CircleDecl::Through(first)),
+                3 => Ok(CircleDecl::ThroughPoints(points)),
+                _ => self.fail(
+                    format!(
+                        "a circle is given by a center and radius, or by three points, not `{}`",
+                        first
+                    ),
+                    self.prev_span(),
+                ),
+            };
+        }
+        if split_points(&first).len() != 1 {
+            return self.fail(
+                format!("expected a single point name, found `{}`", first),
+                self.prev_span(),
+            );
+        }
+
+        if let Some(TokenKind::Name(second)) = self.peek().cloned() {
+            self.pos += 1;
+            if self.eat(&TokenKind::Semi) {
+                let third = self.point_name()?;
+                return Ok(CircleDecl::ThroughPoints(vec![first, second, third]));
+            }
+            return Ok(CircleDecl::CenterThrough {
+                center: first,
+                point: second,
+            });
+        }
+        let radius = self.expr(false)?;
+        Ok(CircleDecl::CenterRadius {
+            center: first,
+            radius,
+        })
+    }
+
+    fn regular_polygon(&mut self) -> PResult<RegularPolygon> {
+        let sides = match self.peek().cloned() {
+            Some(TokenKind::Number(n)) => {
+                self.pos += 1;
+                n
+            }
+            _ => return self.unexpected("the number of sides, e.g. `R:4;AB=ABCD`"),
+        };
+        self.expect(TokenKind::Semi, "`;` after the number of sides")?;
+        let side = self.object()?;
+        self.expect(TokenKind::Eq, "`=` before the polygon's name")?;
+        let polygon = self.object()?;
+        Ok(RegularPolygon {
+            sides,
+            side,
+            polygon,
+        })
+    }
+
+    fn object_list(&mut self) -> PResult<Vec<Object>> {
+        let mut objects = vec![self.object()?];
+        while self.eat(&TokenKind::Comma) {
+            objects.push(self.object()?);
+        }
+        Ok(objects)
+    }
+
+    fn object(&mut self) -> PResult<Object> {
+        let kind = match self.peek() {
+            Some(TokenKind::Lower(c)) if self.next_is_adjacent() => match c {
+                'w' => Some(ObjectKind::Ray),
+                'l' => Some(ObjectKind::Line),
+                'c' => Some(ObjectKind::Circle),
+                'a' => Some(ObjectKind::Arc),
+                'q' => Some(ObjectKind::Sector),
+                _ => None,
+            },
+            Some(TokenKind::Symbol('△')) => Some(ObjectKind::Triangle),
+            _ => None,
+        };
+        if kind.is_some() {
+            self.pos += 1;
+        }
+        match self.peek().cloned() {
+            Some(TokenKind::Name(name)) => {
+                self.pos += 1;
+                Ok(Object {
+                    kind: kind.unwrap_or(ObjectKind::Figure),
+                    points: split_points(&name),
+                })
+            }
+            _ => self.unexpected("point names"),
+        }
+    }
+
+    fn is_object_start(&self) -> bool {
+        match self.peek() {
+            Some(TokenKind::Name(_)) | Some(TokenKind::Symbol('△')) => true,
+            Some(TokenKind::Lower(c)) => {
+                matches!(c, 'w' | 'l' | 'c' | 'a' | 'q')
+                    && self.next_is_adjacent()
+                    && matches!(self.peek_at(1), Some(TokenKind::Name(_)))
+            }
+            _ => false,
+        }
+    }
+
+    fn code(&mut self, expected: &str) -> PResult<String> {
+        match self.peek().cloned() {
+            Some(TokenKind::Name(code)) => {
+                self.pos += 1;
+                Ok(code)
+            }
+            Some(TokenKind::Symbol(c)) if matches!(c, '∥' | '⊥' | '≅' | '~') => {
+                self.pos += 1;
+                Ok(c.to_string())
+            }
+            _ => self.unexpected(expected),
+        }
+    }
+
+    fn expr_statement(&mut self) -> PResult<Statement> {
+        let start = self.span();
+        if self.hint.is_none() {
+            return self.with_hint(
+                "statements such as `AB=5`, `[ABC]?`, `<ABC=90` or `ABC*EQ` need a comparison, `*` or `?`",
+                |p| p.expr_statement(),
+            );
+        }
+        let lhs = self.expr(true)?;
+        let kind = match self.peek().cloned() {
+            Some(TokenKind::Question) => {
+                self.pos += 1;
+                StmtKind::Query(lhs)
+            }
+            // The subject of casework, which `casework` goes on to read
+            Some(TokenKind::CaseOpen) => StmtKind::Value(lhs),
+            Some(TokenKind::Star) => {
+                let object = self.expr_object(lhs)?;
+                self.pos += 1;
+                match self.peek() {
+                    Some(TokenKind::Plus) | Some(TokenKind::Minus) => {
+                        let clockwise = self.bump() == Some(TokenKind::Plus);
+                        StmtKind::Orientation { object, clockwise }
+                    }
+                    _ => {
+                        let property = self.code("a property such as `EQ`, or `+`/`-`")?;
+                        StmtKind::Property {
+                            object,
+                            property,
+                            question: self.eat(&TokenKind::Question),
+                        }
+                    }
+                }
+            }
+            Some(TokenKind::Semi) => {
+                let mut objects = vec![self.expr_object(lhs)?];
+                while self.eat(&TokenKind::Semi) {
+                    objects.push(self.object()?);
+                }
+                self.expect(
+                    TokenKind::Star,
+                    "`*` and a relationship such as `P` or `PR`",
+                )?;
+                let relation = self.code("a relationship such as `P` or `PR`")?;
+                StmtKind::Relationship {
+                    objects,
+                    relation,
+                    infix: false,
+                    question: self.eat(&TokenKind::Question),
+                }
+            }
+            Some(TokenKind::Symbol(c)) if matches!(c, '∥' | '⊥' | '≅' | '~') => {
+                let mut objects = vec![self.expr_object(lhs)?];
+                while self.eat(&TokenKind::Symbol(c)) {
+                    objects.push(self.object()?);
+                }
+                StmtKind::Relationship {
+                    objects,
+                    relation: c.to_string(),
+                    infix: true,
+                    question: self.eat(&TokenKind::Question),
+                }
+            }
+            Some(
+                TokenKind::Eq
+                | TokenKind::NotEq
+                | TokenKind::Lt
+                | TokenKind::Le
+                | TokenKind::Gt
+                | TokenKind::Ge,
+            ) => {
+                let op = match self.bump() {
+                    Some(TokenKind::Eq) => CmpOp::Eq,
+                    Some(TokenKind::NotEq) => CmpOp::NotEq,
+                    Some(TokenKind::Lt) => CmpOp::Lt,
+                    Some(TokenKind::Le) => CmpOp::Le,
+                    Some(TokenKind::Gt) => CmpOp::Gt,
+                    _ => CmpOp::Ge,
+                };
+                let rhs = self.expr(false)?;
+                StmtKind::Relation(lhs, op, rhs)
+            }
+            _ => return self.unexpected("`?`, `=`, `*` or a comparison"),
+        };
+        let stmt = Statement::new(kind, self.since(start));
+        if self.eat(&TokenKind::ProveQuery) {
+            return Ok(Statement::new(
+                StmtKind::ProveQuery(Box::new(stmt)),
+                self.since(start),
+            ));
+        }
+        Ok(stmt)
+    }
+
+    fn expr_object(&self, expr: Expr) -> PResult<Object> {
+        match expr {
+            Expr::Object(object) => Ok(object),
+            other => self.fail(
+                format!(
+                    "expected points before {}, found `{}`",
+                    describe(self.peek().unwrap()),
+                    other
+                ),
+                self.prev_span(),
+            ),
+        }
+    }
+
+    /// `lhs` is true on the left of a comparison, where a `/` can only be a
+    /// ratio; on the right it is a ratio only when a number or constant follows.
+    fn expr(&mut self, lhs: bool) -> PResult<Expr> {
+        let mut expr = self.ratio(lhs)?;
+        loop {
+            let op = match self.peek() {
+                Some(TokenKind::Plus) => ArithOp::Add,
+                Some(TokenKind::Minus) => ArithOp::Sub,
+                _ => return Ok(expr),
+            };
+            self.pos += 1;
+            let rhs = self.ratio(lhs)?;
+            expr = Expr::Binary(Box::new(expr), op, Box::new(rhs));
+        }
+    }
+
+    fn ratio(&mut self, lhs: bool) -> PResult<Expr> {
+        let mut expr = self.product()?;
+        while self.peek() == Some(&TokenKind::Slash) && self.slash_is_ratio(lhs) {
+            self.pos += 1;
+            let rhs = self.product()?;
+            expr = Expr::Binary(Box::new(expr), ArithOp::Div, Box::new(rhs));
+        }
+        Ok(expr)
+    }
+
+    /// A number or constant followed, with no space between, by what it
+    /// multiplies: `2x`, `3\P`, `2CD`, `2[ABC]`.
+    fn product(&mut self) -> PResult<Expr> {
+        let mut expr = self.atom()?;
+        while matches!(
+            expr,
+            Expr::Number(_) | Expr::Constant(_) | Expr::Product(..)
+        ) && self.factor_follows()
+        {
+            let factor = self.atom()?;
+            expr = Expr::Product(Box::new(expr), Box::new(factor));
+        }
+        Ok(expr)
+    }
+
+    fn factor_follows(&self) -> bool {
+        let adjacent = self.pos > 0 && self.prev_span().end == self.span().start;
+        adjacent
+            && match self.peek() {
+                Some(
+                    TokenKind::Name(_)
+                    | TokenKind::Lower(_)
+                    | TokenKind::Constant(_)
+                    | TokenKind::LBracket
+                    | TokenKind::LParen
+                    | TokenKind::Symbol('∠'),
+                ) => true,
+                Some(TokenKind::Command(c)) => matches!(c.as_str(), "P" | "T" | "G"),
+                _ => false,
+            }
+    }
+
+    fn slash_is_ratio(&self, lhs: bool) -> bool {
+        match self.peek_at(1) {
+            Some(TokenKind::Number(_)) | Some(TokenKind::Constant(_)) => true,
+            Some(TokenKind::Command(c)) => matches!(c.as_str(), "P" | "T" | "G"),
+            Some(
+                TokenKind::Name(_)
+                | TokenKind::LBracket
+                | TokenKind::LParen
+                | TokenKind::Lt
+                | TokenKind::Symbol('∠')
+                | TokenKind::Lower(_),
+            ) => lhs,
+            _ => false,
+        }
+    }
+
+    fn atom(&mut self) -> PResult<Expr> {
+        if self.is_object_start() {
+            return Ok(Expr::Object(self.object()?));
+        }
+        match self.peek().cloned() {
+            Some(TokenKind::Number(n)) => {
+                self.pos += 1;
+                Ok(Expr::Number(n))
+            }
+            Some(TokenKind::Minus) if matches!(self.peek_at(1), Some(TokenKind::Number(_))) => {
+                self.pos += 1;
+                match self.bump() {
+                    Some(TokenKind::Number(n)) => Ok(Expr::Number(format!("-{}", n))),
+                    _ => unreachable!(),
+                }
+            }
+            Some(TokenKind::Constant(c)) => {
+                self.pos += 1;
+                let ascii = match c {
+                    'π' => "\\P",
+                    'τ' => "\\T",
+                    _ => "\\G",
+                };
+                Ok(Expr::Constant(ascii.to_string()))
+            }
+            Some(TokenKind::Command(c)) if matches!(c.as_str(), "P" | "T" | "G") => {
+                self.pos += 1;
+                Ok(Expr::Constant(format!("\\{}", c)))
+            }
+            Some(TokenKind::Command(c)) => {
+                self.fail(format!("unknown command `\\{}`", c), self.span())
+            }
+            Some(TokenKind::LBracket) => {
+                self.pos += 1;
+                self.with_hint("areas are written `[ABC]`", |p| {
+                    let object = p.object()?;
+                    p.expect(TokenKind::RBracket, "`]` to close the area")?;
+                    Ok(Expr::Area(object))
+                })
+            }
+            Some(TokenKind::LParen) => {
+                self.pos += 1;
+                self.with_hint("perimeters are written `(ABC)`", |p| {
+                    let object = p.object()?;
+                    p.expect(TokenKind::RParen, "`)` to close the perimeter")?;
+                    Ok(Expr::Perimeter(object))
+                })
+            }
+            Some(TokenKind::Lt) | Some(TokenKind::Symbol('∠')) => {
+                self.pos += 1;
+                Ok(Expr::Angle(self.object()?))
+            }
+            Some(TokenKind::Lower(c)) => {
+                self.pos += 1;
+                let mut name = c.to_string();
+                while let Some(TokenKind::Lower(next)) = self.peek().cloned() {
+                    if self.prev_span().end != self.span().start || self.is_object_start() {
+                        break;
+                    }
+                    name.push(next);
+                    self.pos += 1;
+                }
+                Ok(Expr::Variable(name))
+            }
+            _ => self.unexpected("a value or point names"),
+        }
+    }
+}
@@ -0,0 +1,769 @@
+//! How shorthand is read aloud.
+//!
+//! Each statement is parsed and said a piece at a time, so a code or a name
+//! is one word however many characters it has: the letters of a code are
+//! joined with `-`, making `CCO:ABC` `c-c-o kuh a b c`, `AB;CD*PD`
+//! `a b suh c d xing p-d` and `_PY` `by p-y`, while a point is its letter
+//! and `A1` is `a-1`. Object prefixes, commands and constants have words of
+//! their own (`arc`, `uh-p`, `pi`), symbols have the syllables in
+//! [`SYLLABLES`], and other characters, such as digits and `{`, are said as
+//! they are. A statement that does not parse is spelled out a character at
+//! a time.
+
+use crate::ast::*;
+use crate::lexer::{self, TokenKind};
+use crate::parser;
+use crate::Translator;
+
+/// The syllable for each symbol, with the GSL it stands for and how it
+/// is said in IPA.
+pub(crate) const SYLLABLES: &[(&str, &str, &str)] = &[
+    ("duh-duh", "..", "dʌ.dʌ"),
+    ("kuh", ":", "kʌ"),
+    ("suh", ";", "sʌ"),
+    ("muh", ",", "mʌ"),
+    ("duh", ".", "dʌ"),
+    ("kwuh", "?", "kwʌ"),
+    ("eh", "=", "ɛ"),
+    ("shuh", "|", "ʃʌ"),
+    ("xing", "*", "ksɪŋ"),
+    ("ix", "x", "ɪks"),
+    ("by", "_", "baɪ"),
+    ("not", "!", "nɑt"),
+    ("arc", "a", "ɑɹk"),
+    ("sect", "q", "sɛkt"),
+    ("line", "l", "laɪn"),
+    ("circ", "c", "sɝk"),
+    ("ray", "w", "ɹeɪ"),
+    ("tri", "△", "tɹaɪ"),
+    ("pall", "∥", "pɔl"),
+    ("perp", "⊥", "pɝp"),
+    ("ang", "∠", "æŋ"),
+    ("sim", "~", "sɪm"),
+    ("cong", "≅", "kɑŋ"),
+    ("area", "[", "ˈɛɹiə"),
+    ("pairim", "(", "ˈpɛɹɪm"),
+    ("mn", "/", "mən"),
+    ("uh", "\\\\", "ʌ"),
+];
+
+/// The word for each backslash command other than the constants.
+pub(crate) const COMMANDS: &[(&str, &str)] = &[
+    ("uh-p", "\\p"),
+    ("uh-pc", "\\pC"),
+    ("uh-q", "\\q"),
+    ("uh-qc", "\\qC"),
+    ("uh-bc", "\\bc"),
+    ("uh-th", "\\th"),
+    ("uh-def", "\\def"),
+    ("uh-include", "\\include"),
+    ("uh-kwuh", "\\?"),
+];
+
+/// A lowercase letter standing for a number, other than `x`, is said after
+/// this word, as in `var r`.
+pub(crate) const VARIABLE: &str = "var";
+
+/// IPA for the other words: `var` and the constants' names.
+const WORDS: &[(&str, &str)] = &[
+    (VARIABLE, "vɑɹ"),
+    ("pi", "paɪ"),
+    ("tau", "taʊ"),
+    ("phi", "faɪ"),
+];
+
+/// IPA for the letters and digits names and codes are spelled with.
+const CHARACTERS: &[(char, &str)] = &[
+    ('a', "eɪ"),
+    ('b', "biː"),
+    ('c', "siː"),
+    ('d', "diː"),
+    ('e', "iː"),
+    ('f', "ɛf"),
+    ('g', "dʒiː"),
+    ('h', "eɪtʃ"),
+    ('i', "aɪ"),
+    ('j', "dʒeɪ"),
+    ('k', "keɪ"),
+    ('l', "ɛl"),
+    ('m', "ɛm"),
+    ('n', "ɛn"),
+    ('o', "oʊ"),
+    ('p', "piː"),
+    ('q', "kjuː"),
+    ('r', "ɑɹ"),
+    ('s', "ɛs"),
+    ('t', "tiː"),
+    ('u', "juː"),
+    ('v', "viː"),
+    ('w', "ˈdʌbəl.juː"),
+    ('x', "ɛks"),
+    ('y', "waɪ"),
+    ('z', "ziː"),
+    ('0', "ˈzɪɹoʊ"),
+    ('1', "wʌn"),
+    ('2', "tuː"),
+    ('3', "θɹiː"),
+    ('4', "fɔɹ"),
+    ('5', "faɪv"),
+    ('6', "sɪks"),
+    ('7', "ˈsɛvən"),
+    ('8', "eɪt"),
+    ('9', "naɪn"),
+    ('\'', "pɹaɪm"),
+];
+
+impl Translator {
+    /// Spell out how shorthand is read aloud, on one line or numbered by
+    /// statement.
+    pub fn pronounce(&self, input: &str, steps: bool) -> String {
+        self.speak(input).lay_out(steps, Word::text)
+    }
+
+    /// [`Translator::pronounce`] written in IPA, for speech engines that
+    /// take phonetic input.
+    pub fn pronounce_ipa(&self, input: &str, steps: bool) -> String {
+        self.speak(input).lay_out(steps, Word::ipa)
+    }
+
+    /// The pronunciation as an SSML document for a text-to-speech engine:
+    /// each syllable is a `<phoneme>` with its IPA, names and codes are read
+    /// out letter by letter, and a `<break>` separates statements.
+    pub fn pronounce_ssml(&self, input: &str) -> String {
+        let speech = self.speak(input);
+        let mut out = String::from(
+            "<speak version=\"1.0\" xmlns=\"http://www.w3.org/2001/10/synthesis\" xml:lang=\"en-US\">\n",
+        );
+        for (i, problem) in speech.problems.iter().enumerate() {
+            if i > 0 {
+                out.push_str("<break strength=\"strong\"/>\n");
+            }
+            out.push_str("<p>\n");
+            for (j, statement) in problem.iter().enumerate() {
+                if j > 0 {
+                    out.push_str("<break strength=\"medium\"/>\n");
+                }
+                let words: Vec<String> = statement.iter().map(Word::ssml).collect();
+                out.push_str(&words.join(" "));
+                out.push('\n');
+            }
+            out.push_str("</p>\n");
+        }
+        out.push_str("</speak>");
+        out
+    }
+
+    /// The words of each statement of each problem in `input`.
+    fn speak(&self, input: &str) -> Speech {
+        let wrapped = lexer::tokenize(input)
+            .iter()
+            .any(|token| token.kind == TokenKind::Wrap);
+        let mut problems = Vec::new();
+        for problem in parser::parse_problems(input) {
+            let statements: Vec<Vec<Word>> = problem
+                .statements
+                .iter()
+                .map(|parsed| {
+                    let mut speaker = Speaker::new(self);
+                    match parsed {
+                        Ok(stmt) => speaker.statement(stmt),
+                        Err(e) => speaker.spell(&input[e.statement.start..e.statement.end]),
+                    }
+                    speaker.words
+                })
+                .filter(|words| !words.is_empty())
+                .collect();
+            if !statements.is_empty() {
+                problems.push(statements);
+            }
+        }
+        Speech { wrapped, problems }
+    }
+}
+
+/// A pronunciation before it is written out.
+struct Speech {
+    /// Whether the input put its problems in `\\...\\`, which are then
+    /// read as `uh`.
+    wrapped: bool,
+    problems: Vec<Vec<Vec<Word>>>,
+}
+
+impl Speech {
+    /// One line, or one numbered line per statement, writing each word
+    /// with `write`.
+    fn lay_out(&self, steps: bool, write: fn(&Word) -> String) -> String {
+        let statement = |words: &Vec<Word>| words.iter().map(write).collect::<Vec<_>>().join(" ");
+        let separator = format!(" {} ", write(&Word::syllable("/")));
+        if steps {
+            self.problems
+                .concat()
+                .iter()
+                .enumerate()
+                .map(|(i, words)| format!("{}. {}", i + 1, statement(words)))
+                .collect::<Vec<_>>()
+                .join("\n")
+        } else if self.wrapped {
+            let wrap = write(&Word::syllable("\\\\"));
+            self.problems
+                .iter()
+                .map(|problem| {
+                    let statements: Vec<String> = problem.iter().map(statement).collect();
+                    format!("{} {} {}", wrap, statements.join(&separator), wrap)
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        } else {
+            let statements: Vec<String> = self.problems.concat().iter().map(statement).collect();
+            statements.join(&separator)
+        }
+    }
+}
+
+/// One unit of a pronunciation.
+#[derive(Debug, Clone, PartialEq)]
+enum Word {
+    /// A syllable, command or other word with IPA of its own.
+    Said(String),
+    /// A point name or code, read letter by letter.
+    Name(String),
+    /// Text said as written, such as a number or `{`.
+    Text(String),
+}
+
+impl Word {
+    fn syllable(gsl: &str) -> Word {
+        let (word, _, _) = SYLLABLES.iter().find(|(_, g, _)| *g == gsl).unwrap();
+        Word::Said(word.to_string())
+    }
+
+    /// The word as `pronounce` writes it; a name's characters are joined
+    /// by `-`, as in `c-c-o`.
+    fn text(&self) -> String {
+        match self {
+            Word::Said(word) | Word::Text(word) => word.clone(),
+            Word::Name(name) => {
+                let chars: Vec<String> = name
+                    .chars()
+                    .flat_map(char::to_lowercase)
+                    .map(String::from)
+                    .collect();
+                chars.join("-")
+            }
+        }
+    }
+
+    fn ipa(&self) -> String {
+        match self {
+            Word::Said(word) => {
+                let known = SYLLABLES
+                    .iter()
+                    .find(|(spoken, _, _)| spoken == word)
+                    .map(|(_, _, ipa)| *ipa)
+                    .or_else(|| WORDS.iter().find(|(w, _)| w == word).map(|(_, ipa)| *ipa));
+                match (known, word.strip_prefix("uh-")) {
+                    (Some(ipa), _) => ipa.to_string(),
+                    // A command: `uh` and its name spelled out
+                    (None, Some(command)) => {
+                        format!("{} {}", Word::syllable("\\\\").ipa(), spell_ipa(command))
+                    }
+                    (None, None) => word.clone(),
+                }
+            }
+            Word::Name(name) => spell_ipa(name),
+            Word::Text(text) => text.clone(),
+        }
+    }
+
+    fn ssml(&self) -> String {
+        match self {
+            Word::Said(word) => format!(
+                "<phoneme alphabet=\"ipa\" ph=\"{}\">{}</phoneme>",
+                escape(&self.ipa()),
+                escape(word)
+            ),
+            Word::Name(name) => format!(
+                "<say-as interpret-as=\"characters\">{}</say-as>",
+                escape(name)
+            ),
+            Word::Text(text) => escape(text),
+        }
+    }
+}
+
+/// IPA for each letter and digit of `name`, one after another.
+fn spell_ipa(name: &str) -> String {
+    let sounds: Vec<String> = name
+        .chars()
+        .flat_map(char::to_lowercase)
+        .map(|c| match CHARACTERS.iter().find(|(ch, _)| *ch == c) {
+            Some((_, ipa)) => ipa.to_string(),
+            None => c.to_string(),
+        })
+        .collect();
+    sounds.join(" ")
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The word for a named constant such as `\P`: what its table entry gives
+/// in parentheses, `pi`.
+pub(crate) fn constant_word<'a>(translator: &'a Translator, constant: &str) -> Option<&'a str> {
+    let name = translator.constants.get(constant)?;
+    let (_, rest) = name.split_once('(')?;
+    rest.strip_suffix(')')
+}
+
+/// Collects the words for one statement.
+struct Speaker<'a> {
+    translator: &'a Translator,
+    words: Vec<Word>,
+}
+
+impl<'a> Speaker<'a> {
+    fn new(translator: &'a Translator) -> Self {
+        Speaker {
+            translator,
+            words: Vec::new(),
+        }
+    }
+
+    /// Text said as written.
+    fn say(&mut self, text: &str) {
+        self.words.push(Word::Text(text.to_string()));
+    }
+
+    /// A word with IPA of its own, such as `var` or `pi`.
+    fn word(&mut self, word: &str) {
+        self.words.push(Word::Said(word.to_string()));
+    }
+
+    fn syllable(&mut self, gsl: &str) {
+        let (word, _, _) = SYLLABLES.iter().find(|(_, g, _)| *g == gsl).unwrap();
+        self.words.push(Word::Said(word.to_string()));
+    }
+
+    fn command(&mut self, gsl: &str) {
+        let (word, _) = COMMANDS.iter().find(|(_, g)| *g == gsl).unwrap();
+        self.words.push(Word::Said(word.to_string()));
+    }
+
+    fn name(&mut self, name: &str) {
+        self.words.push(Word::Name(name.to_string()));
+    }
+
+    fn list<T>(&mut self, items: &[T], separator: &str, mut say: impl FnMut(&mut Self, &T)) {
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                self.syllable(separator);
+            }
+            say(self, item);
+        }
+    }
+
+    fn points(&mut self, points: &[String]) {
+        for point in points {
+            self.name(point);
+        }
+    }
+
+    fn object(&mut self, object: &Object) {
+        match object.kind {
+            ObjectKind::Figure => {}
+            ObjectKind::Ray => self.syllable("w"),
+            ObjectKind::Line => self.syllable("l"),
+            ObjectKind::Circle => self.syllable("c"),
+            ObjectKind::Arc => self.syllable("a"),
+            ObjectKind::Sector => self.syllable("q"),
+            ObjectKind::Triangle => self.syllable("△"),
+        }
+        self.points(&object.points);
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Number(n) => self.spell(n),
+            Expr::Variable(v) if v == "x" => self.syllable("x"),
+            Expr::Variable(v) => {
+                self.word(VARIABLE);
+                self.name(v);
+            }
+            Expr::Constant(c) => match constant_word(self.translator, c) {
+                Some(word) => self.word(word),
+                None => self.spell(c),
+            },
+            Expr::Object(object) => self.object(object),
+            Expr::Area(object) => {
+                self.syllable("[");
+                self.object(object);
+            }
+            Expr::Perimeter(object) => {
+                self.syllable("(");
+                self.object(object);
+            }
+            Expr::Angle(object) => {
+                self.syllable("∠");
+                self.object(object);
+            }
+            Expr::Binary(lhs, op, rhs) => {
+                self.expr(lhs);
+                match op {
+                    ArithOp::Add => self.say("+"),
+                    ArithOp::Sub => self.say("-"),
+                    ArithOp::Div => self.syllable("/"),
+                }
+                self.expr(rhs);
+            }
+            Expr::Product(lhs, rhs) => {
+                self.expr(lhs);
+                self.expr(rhs);
+            }
+        }
+    }
+
+    /// A construction prefix such as `P:` or `CCO:`.
+    fn prefix(&mut self, code: &str) {
+        self.name(code);
+        self.syllable(":");
+    }
+
+    fn statement(&mut self, stmt: &Statement) {
+        self.claim(stmt);
+        if let Some(code) = &stmt.theorem {
+            self.theorem(code);
+        }
+    }
+
+    /// The words for `stmt` without the theorem cited for it.
+    fn claim(&mut self, stmt: &Statement) {
+        match &stmt.kind {
+            StmtKind::Prove(inner) | StmtKind::ProveByContradiction(inner) => {
+                let command = match stmt.kind {
+                    StmtKind::Prove(_) => "\\p",
+                    _ => "\\pC",
+                };
+                self.command(command);
+                self.syllable(":");
+                self.statement(inner);
+            }
+            StmtKind::Marker(marker) => self.command(match marker {
+                ProofMarker::Qed => "\\q",
+                ProofMarker::Contradiction => "\\qC",
+                ProofMarker::Because => "\\bc",
+                ProofMarker::Therefore => "\\th",
+            }),
+            StmtKind::Logic(op, lhs, rhs) => {
+                self.statement(lhs);
+                match op {
+                    LogicOp::And => self.say("&&"),
+                    LogicOp::Or => {
+                        self.syllable("|");
+                        self.syllable("|");
+                    }
+                    LogicOp::Implies => {
+                        self.syllable("=");
+                        self.say(">");
+                    }
+                }
+                self.statement(rhs);
+            }
+            StmtKind::Quantified(quantifier, body) => {
+                self.syllable("|");
+                self.name(match quantifier {
+                    Quantifier::ForAll => "A",
+                    Quantifier::Exists => "E",
+                });
+                self.statement(body);
+            }
+            StmtKind::Casework { main, cases } => {
+                if let Some(main) = main {
+                    self.statement(main);
+                }
+                self.say("<<");
+                if let Some(cases) = cases {
+                    self.list(cases, ",", |s, case| {
+                        s.syllable("(");
+                        s.statement(&case.condition);
+                        s.syllable(";");
+                        s.statement(&case.result);
+                    });
+                    self.say(">>");
+                }
+            }
+            StmtKind::EndCasework => self.say(">>"),
+            StmtKind::Derived { code, args, target } => {
+                self.prefix(code);
+                self.list(args, ";", Self::object);
+                if let Some(target) = target {
+                    self.syllable("=");
+                    self.name(target);
+                }
+            }
+            StmtKind::Graph(equation) => {
+                self.prefix("G");
+                self.say("{");
+                self.spell(equation);
+                self.say("}");
+            }
+            StmtKind::Point(decl) => {
+                self.prefix("P");
+                self.point(decl);
+            }
+            StmtKind::Linear(kind, objects) => {
+                self.prefix(match kind {
+                    LinearKind::Segment => "S",
+                    LinearKind::Line => "L",
+                    LinearKind::Ray => "W",
+                });
+                self.list(objects, ",", Self::object);
+            }
+            StmtKind::Polygon {
+                objects,
+                properties,
+            } => {
+                self.prefix("J");
+                self.list(objects, ",", Self::object);
+                for property in properties {
+                    self.syllable("*");
+                    self.name(property);
+                }
+            }
+            StmtKind::Circle(decl) => {
+                self.prefix("C");
+                match decl {
+                    CircleDecl::ThroughPoints(points) => {
+                        self.list(points, ";", |s, point| s.name(point))
+                    }
+                    CircleDecl::CenterRadius { center, radius } => {
+                        self.name(center);
+                        self.syllable(";");
+                        self.expr(radius);
+                    }
+                    CircleDecl::CenterThrough { center, point } => {
+                        self.name(center);
+                        self.syllable(";");
+                        self.name(point);
+                    }
+                    CircleDecl::Through(point) => self.name(point),
+                    CircleDecl::Named(name) => {
+                        self.syllable("=");
+                        self.spell(name);
+                    }
+                }
+            }
+            StmtKind::RegularPolygon(poly) => {
+                self.prefix("R");
+                self.spell(&poly.sides);
+                self.syllable(";");
+                self.object(&poly.side);
+                self.syllable("=");
+                self.object(&poly.polygon);
+            }
+            StmtKind::Citation(code) => self.theorem(code),
+            StmtKind::Relation(lhs, op, rhs) => {
+                self.expr(lhs);
+                match op {
+                    CmpOp::Eq => self.syllable("="),
+                    CmpOp::NotEq => {
+                        self.syllable("!");
+                        self.syllable("=");
+                    }
+                    CmpOp::Lt | CmpOp::Le => self.say("<"),
+                    CmpOp::Gt | CmpOp::Ge => self.say(">"),
+                }
+                if matches!(op, CmpOp::Le | CmpOp::Ge) {
+                    self.syllable("=");
+                }
+                self.expr(rhs);
+            }
+            StmtKind::Query(expr) => {
+                self.expr(expr);
+                self.syllable("?");
+            }
+            StmtKind::Value(expr) => self.expr(expr),
+            StmtKind::ProveQuery(inner) => {
+                self.statement(inner);
+                self.command("\\?");
+            }
+            StmtKind::Property {
+                object,
+                property,
+                question,
+            } => {
+                self.object(object);
+                self.syllable("*");
+                self.name(property);
+                if *question {
+                    self.syllable("?");
+                }
+            }
+            StmtKind::Relationship {
+                objects,
+                relation,
+                infix,
+                question,
+            } => {
+                if *infix {
+                    self.list(objects, relation, Self::object);
+                } else {
+                    self.list(objects, ";", Self::object);
+                    self.syllable("*");
+                    self.name(relation);
+                }
+                if *question {
+                    self.syllable("?");
+                }
+            }
+            StmtKind::Orientation { object, clockwise } => {
+                self.object(object);
+                self.syllable("*");
+                self.say(if *clockwise { "+" } else { "-" });
+            }
+            StmtKind::Define(definition) => {
+                self.command("\\def");
+                self.definition(definition);
+            }
+            StmtKind::Include(path) => {
+                self.command("\\include");
+                self.verbatim('"', path, '"');
+            }
+        }
+    }
+
+    fn point(&mut self, decl: &PointDecl) {
+        match decl {
+            PointDecl::Free(names) => self.list(names, ",", |s, name| s.name(name)),
+            PointDecl::Coordinates { name, coords } => {
+                self.name(name);
+                self.say("{");
+                self.spell(coords);
+                self.say("}");
+            }
+            PointDecl::OnObject {
+                name,
+                object,
+                conditions,
+            } => {
+                self.name(name);
+                self.syllable(".");
+                self.object(object);
+                if !conditions.is_empty() {
+                    self.syllable("|");
+                    self.list(conditions, ",", Self::statement);
+                }
+            }
+            PointDecl::InRegion { name, region } => {
+                self.name(name);
+                self.syllable("..");
+                self.object(region);
+            }
+            PointDecl::Intersection { name, objects } => {
+                self.name(name);
+                self.syllable("=");
+                self.list(objects, "x", Self::object);
+            }
+        }
+    }
+
+    fn definition(&mut self, definition: &Definition) {
+        match definition {
+            Definition::Macro { name, params, body } => {
+                self.prefix(name);
+                self.points(params);
+                self.say("{");
+                self.list(body, "/", Self::statement);
+                self.say("}");
+            }
+            Definition::Construction { code, name } => {
+                self.prefix(code);
+                self.verbatim('{', name, '}');
+            }
+            Definition::Property { code, name } => {
+                self.syllable("*");
+                self.name(code);
+                self.verbatim('{', name, '}');
+            }
+            Definition::Theorem { code, name } => {
+                self.theorem(code);
+                self.verbatim('{', name, '}');
+            }
+        }
+    }
+
+    /// A theorem code such as `_PY`: `by` and the letters after it.
+    fn theorem(&mut self, code: &str) {
+        self.syllable("_");
+        self.name(code.trim_start_matches('_'));
+    }
+
+    /// Text that is read as written, such as a file name or the name of a
+    /// defined code, inside its delimiters.
+    fn verbatim(&mut self, open: char, text: &str, close: char) {
+        let text = format!("{}{}{}", open, text.trim(), close);
+        self.words.extend(
+            text.split_whitespace()
+                .map(|word| Word::Text(word.to_string())),
+        );
+    }
+
+    /// Shorthand said one character at a time, for equations, coordinates
+    /// and statements that do not parse.
+    fn spell(&mut self, text: &str) {
+        let chars: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+        let mut i = 0;
+        while i < chars.len() {
+            let rest: String = chars[i..].iter().collect();
+            if rest.starts_with("\\\\") || rest.starts_with("..") {
+                self.syllable(&rest[..2]);
+                i += 2;
+            } else if chars[i] == '\\' {
+                let len = 1 + chars[i + 1..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_alphabetic())
+                    .count();
+                let len = if len == 1 && rest.starts_with("\\?") {
+                    2
+                } else {
+                    len
+                };
+                let gsl: String = chars[i..i + len].iter().collect();
+                match constant_word(self.translator, &gsl) {
+                    Some(word) => self.word(word),
+                    None if COMMANDS.iter().any(|(_, g)| *g == gsl) => self.command(&gsl),
+                    None => self.say(&gsl),
+                }
+                i += len;
+            } else if chars[i].is_ascii_digit() {
+                let len = chars[i..].iter().take_while(|c| c.is_ascii_digit()).count();
+                self.say(&rest[..len]);
+                i += len;
+            } else if chars[i].is_ascii_uppercase() {
+                let len = 1 + chars[i + 1..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_digit() || **c == '\'')
+                    .count();
+                let name: String = chars[i..i + len].iter().collect();
+                self.name(&name);
+                i += len;
+            } else {
+                let gsl = chars[i].to_string();
+                if SYLLABLES.iter().any(|(_, g, _)| *g == gsl) {
+                    self.syllable(&gsl);
+                } else if chars[i].is_ascii_lowercase() {
+                    self.word(VARIABLE);
+                    self.name(&gsl);
+                } else if let Some(word) = constant_word(self.translator, &gsl) {
+                    self.word(word);
+                } else {
+                    self.say(&gsl);
+                }
+                i += 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,146 @@
+//! The shape of a proof. `\p:` opens a goal, the statements after it are its
+//! steps, and `\q` (`□`) closes the innermost goal still open. A proof by
+//! contradiction, opened with `\pC:`, closes with `↯` (`\qC`) instead, since
+//! reaching the contradiction is what finishes it. Proofs nest, so a lemma can
+//! be proved in the middle of a larger argument.
+//!
+//! `∵` and `∴` mark the statement after them as a reason or a conclusion. The
+//! structure is recorded on the steps: each step's depth is how many proofs it
+//! sits inside, and a closing marker knows the step that opened its goal, so
+//! its English can say what was proved.
+
+use crate::ast::*;
+use crate::diagnostic::Diagnostic;
+use crate::translator::Step;
+use crate::Translator;
+
+/// A goal that has not been closed yet.
+struct Goal {
+    /// The index of the step that opened it.
+    step: usize,
+    contradiction: bool,
+}
+
+/// Match each closing marker in `steps` to the goal it closes, set every
+/// step's depth, and report markers that do not balance. Goals left open
+/// are only reported when the problem is `complete`.
+pub(crate) fn structure(
+    translator: &Translator,
+    steps: &mut [Step],
+    complete: bool,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let mut open: Vec<Goal> = Vec::new();
+    for i in 0..steps.len() {
+        steps[i].depth = open.len();
+        let by_contradiction = match &steps[i].statement.kind {
+            StmtKind::Prove(_) | StmtKind::ProveByContradiction(_) => {
+                let contradiction =
+                    matches!(steps[i].statement.kind, StmtKind::ProveByContradiction(_));
+                open.push(Goal {
+                    step: i,
+                    contradiction,
+                });
+                continue;
+            }
+            StmtKind::Marker(ProofMarker::Qed) => false,
+            StmtKind::Marker(ProofMarker::Contradiction) => true,
+            StmtKind::Marker(ProofMarker::Because | ProofMarker::Therefore) => {
+                if !steps.get(i + 1).is_some_and(introduces) {
+                    diagnostics.push(Diagnostic::warning(
+                        format!(
+                            "`{}` is not followed by the statement it introduces",
+                            steps[i].source
+                        ),
+                        steps[i].statement.span,
+                    ));
+                }
+                continue;
+            }
+            _ => continue,
+        };
+
+        let span = steps[i].statement.span;
+        let Some(goal) = open.pop() else {
+            diagnostics.push(
+                Diagnostic::error(
+                    format!("there is no open proof for `{}` to close", steps[i].source),
+                    span,
+                )
+                .with_hint("open one with `\\p:`, or `\\pC:` for a proof by contradiction"),
+            );
+            continue;
+        };
+        let opened = steps[goal.step].statement.span;
+        match (goal.contradiction, by_contradiction) {
+            (true, false) => diagnostics.push(
+                Diagnostic::error("a proof by contradiction must reach `↯` to close", span)
+                    .with_note("the proof opens here", opened)
+                    .with_hint("write `↯` or `\\qC` where the assumption leads to a contradiction"),
+            ),
+            (false, true) => diagnostics.push(
+                Diagnostic::error(
+                    "`↯` closes a proof by contradiction, but this proof is direct",
+                    span,
+                )
+                .with_note("the proof opens here", opened)
+                .with_hint(
+                    "open it with `\\pC:` to argue by contradiction, or close it with `\\q`",
+                ),
+            ),
+            _ => {}
+        }
+        steps[i].depth = open.len();
+        steps[i].closes = Some(goal.step);
+        if let Some(claim) = claim(&steps[goal.step].statement) {
+            steps[i].english = format!(
+                "{}, which proves that {}.",
+                steps[i].english.trim_end_matches('.'),
+                sentence_case(&translator.translate_statement(claim))
+            );
+        }
+    }
+    if !complete {
+        return;
+    }
+    for goal in open {
+        let hint = if goal.contradiction {
+            "close it with `↯` once the assumption leads to a contradiction"
+        } else {
+            "close it with `\\q` once the claim is shown"
+        };
+        diagnostics.push(
+            Diagnostic::error(
+                "this proof is never closed",
+                steps[goal.step].statement.span,
+            )
+            .with_hint(hint),
+        );
+    }
+}
+
+/// The claim a `\p:` or `\pC:` step sets out to prove.
+pub(crate) fn claim(stmt: &Statement) -> Option<&Statement> {
+    match &stmt.kind {
+        StmtKind::Prove(claim) | StmtKind::ProveByContradiction(claim) => Some(claim),
+        _ => None,
+    }
+}
+
+/// Whether a step can be the reason or conclusion a `∵` or `∴` introduces.
+fn introduces(step: &Step) -> bool {
+    !matches!(step.statement.kind, StmtKind::Marker(_))
+}
+
+/// A sentence as the middle of another: without its full stop, and starting
+/// in lower case unless it starts with a name such as `AB`.
+fn sentence_case(sentence: &str) -> String {
+    let sentence = sentence.trim_end_matches('.');
+    let mut chars = sentence.chars();
+    match (chars.next(), chars.next()) {
+        (Some(first), Some(second)) if first.is_uppercase() && second.is_lowercase() => {
+            format!("{}{}", first.to_lowercase(), &sentence[first.len_utf8()..])
+        }
+        _ => sentence.to_string(),
+    }
+}
@@ -0,0 +1,202 @@
+//! `gslc repl`: translate statements one line at a time, keeping the
+//! construction built so far so later lines can refer to earlier points.
+
+use crate::numbered;
+use gslc::diagnostic::SourceFile;
+use gslc::Translator;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+const HELP: &str = "Enter one statement per line, e.g. `P:A,B` then `S:AB`.
+Commands:
+  :list          Show every statement so far
+  :undo          Remove the last statement
+  :save <file>   Write the statements to a .gsl file
+  :pron [-s]     Pronounce the statements (-s numbers them)
+  :clear         Start over
+  :help          Show this message
+  :quit          Leave (Ctrl-D also works)";
+
+pub fn run() {
+    let translator = Translator::new();
+    let mut session = Session {
+        translator: &translator,
+        statements: Vec::new(),
+    };
+    let interactive = io::stdin().is_terminal();
+    let history = history_path();
+    if interactive {
+        println!("gslc repl: one statement per line, :help for commands");
+    }
+
+    let stdin = io::stdin();
+    let mut line = String::new();
+    loop {
+        if interactive {
+            print!("gsl> ");
+            io::stdout().flush().ok();
+        }
+        line.clear();
+        match stdin.lock().read_line(&mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        let entry = line.trim();
+        if entry.is_empty() {
+            continue;
+        }
+        if let Some(path) = &history {
+            remember(path, entry);
+        }
+        if !session.handle(entry) {
+            break;
+        }
+    }
+}
+
+/// Where entered lines are appended: `gslc/history` under the platform's
+/// per-user data directory.
+fn history_path() -> Option<PathBuf> {
+    let var = |name: &str| {
+        env::var_os(name)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+    let data = if let Some(dir) = var("XDG_DATA_HOME") {
+        dir
+    } else if cfg!(windows) {
+        var("APPDATA")?
+    } else if cfg!(target_os = "macos") {
+        var("HOME")?.join("Library").join("Application Support")
+    } else {
+        var("HOME")?.join(".local").join("share")
+    };
+    Some(data.join("gslc").join("history"))
+}
+
+/// History is a convenience, so failing to write it is not an error.
+fn remember(path: &Path, entry: &str) {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).ok();
+    }
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+        writeln!(file, "{}", entry).ok();
+    }
+}
+
+struct Session<'a> {
+    translator: &'a Translator,
+    /// Accepted statements, in the order they were entered.
+    statements: Vec<String>,
+}
+
+impl Session<'_> {
+    /// The statements so far as one GSL problem.
+    fn program(statements: &[String]) -> String {
+        format!("\\\\{}\\\\", statements.join("/"))
+    }
+
+    /// Handle one line; returns false when the session should end.
+    fn handle(&mut self, entry: &str) -> bool {
+        let (command, arg) = match entry.split_once(char::is_whitespace) {
+            Some((command, arg)) => (command, arg.trim()),
+            None => (entry, ""),
+        };
+        match command {
+            ":quit" | ":q" | ":exit" => return false,
+            ":help" => println!("{}", HELP),
+            ":list" => self.list(),
+            ":undo" => match self.statements.pop() {
+                Some(stmt) => println!("Removed `{}`.", stmt),
+                None => println!("Nothing to undo."),
+            },
+            ":clear" => {
+                self.statements.clear();
+                println!("Cleared.");
+            }
+            ":pron" => println!(
+                "{}",
+                self.translator
+                    .pronounce(&Self::program(&self.statements), arg == "-s")
+            ),
+            ":save" if arg.is_empty() => eprintln!("Error: :save needs a file name"),
+            ":save" => {
+                let program = Self::program(&self.statements);
+                match fs::write(arg, format!("{}\n", program)) {
+                    Ok(()) => {
+                        let count = self.statements.len();
+                        let noun = if count == 1 {
+                            "statement"
+                        } else {
+                            "statements"
+                        };
+                        println!("Saved {} {} to {}.", count, noun, arg)
+                    }
+                    Err(e) => eprintln!("Error: could not write {}: {}", arg, e),
+                }
+            }
+            _ if command.starts_with(':') => {
+                eprintln!("Error: unknown command `{}`; try :help", command)
+            }
+            _ => self.add(entry),
+        }
+        true
+    }
+
+    fn list(&self) {
+        if self.statements.is_empty() {
+            println!("No statements yet.");
+            return;
+        }
+        let translation = self
+            .translator
+            .translate_draft(&Self::program(&self.statements));
+        for (i, step) in translation.steps.iter().enumerate() {
+            println!("{}", numbered(i + 1, step));
+        }
+    }
+
+    /// Translate `entry` in the context of the statements before it, and
+    /// keep it unless that produces an error.
+    fn add(&mut self, entry: &str) {
+        let entry = entry
+            .strip_prefix("\\\\")
+            .and_then(|e| e.strip_suffix("\\\\"))
+            .unwrap_or(entry);
+        // A macro's body may hold several statements
+        let definition = entry.starts_with("\\def");
+        if entry.contains('/') && !definition {
+            eprintln!("Error: enter one statement per line, without `/`");
+            return;
+        }
+        let mut statements = self.statements.clone();
+        statements.push(entry.to_string());
+        let program = Self::program(&statements);
+        let start = program.len() - 2 - entry.len();
+        let translation = self.translator.translate_draft(&program);
+
+        let source = SourceFile::new("<repl>", &program);
+        let new: Vec<_> = translation
+            .diagnostics
+            .iter()
+            .filter(|d| d.span.start >= start)
+            .collect();
+        for diagnostic in &new {
+            eprintln!("{}", source.render(diagnostic));
+        }
+        if new.iter().any(|d| d.is_error()) {
+            return;
+        }
+        for (i, step) in translation.steps.iter().enumerate() {
+            if step.statement.span.start >= start {
+                println!("{}", numbered(i + 1, step));
+            }
+        }
+        if definition {
+            println!("Defined.");
+        }
+        self.statements = statements;
+    }
+}
@@ -0,0 +1,553 @@
+//! English back to GSL: reads the sentences [`Translator::translate_statement`]
+//! writes and recovers the shorthand they came from.
+//!
+//! Each statement is printed in its canonical spelling, the one the syntax
+//! tree's `Display` gives. Where the English does not say which spelling was
+//! used, the code form wins: `AB and CD are parallel.` becomes `AB;CD*P`
+//! rather than `AB∥CD`, and `π (pi)` becomes `\P`.
+
+use crate::diagnostic::Diagnostic;
+use crate::lexer::Span;
+use crate::parser;
+use crate::translator::shape_name;
+use crate::translator::Table;
+use crate::Translator;
+
+/// Shorthand recovered from English, plus the sentences that could not be read.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reversal {
+    /// Canonical GSL for each sentence that was understood, in order.
+    pub statements: Vec<String>,
+    /// Spans point into the English, so rendering them gives line numbers.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Reversal {
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(Diagnostic::is_error)
+    }
+
+    /// The statements as one problem, e.g. `\\P:A,B/S:AB\\`.
+    pub fn program(&self) -> String {
+        format!("\\\\{}\\\\", self.statements.join("/"))
+    }
+}
+
+impl Translator {
+    /// Turn English, one sentence per line as `gslc` prints it, back into
+    /// GSL. Step numbers and `→ answer` suffixes are ignored, and a
+    /// `Begin casework` line belongs to the sentence before it.
+    pub fn reverse(&self, english: &str) -> Reversal {
+        let mut reversal = Reversal {
+            statements: Vec::new(),
+            diagnostics: Vec::new(),
+        };
+        for (sentence, span) in sentences(english) {
+            let Some(gsl) = self.reverse_sentence(&sentence) else {
+                reversal.diagnostics.push(
+                    Diagnostic::error("unrecognized sentence", span).with_hint(
+                        "write one sentence per line, worded as gslc translates it, \
+                         e.g. `Construct point A.`",
+                    ),
+                );
+                continue;
+            };
+            match canonical(&gsl) {
+                Ok(stmt) => reversal.statements.push(stmt),
+                Err(message) => reversal.diagnostics.push(Diagnostic::error(
+                    format!(
+                        "sentence reads as `{}`, which is not valid GSL: {}",
+                        gsl, message
+                    ),
+                    span,
+                )),
+            }
+        }
+        reversal
+    }
+
+    /// Shorthand for one sentence, not yet checked by the parser.
+    fn reverse_sentence(&self, s: &str) -> Option<String> {
+        // A theorem cited for a statement ends its sentence
+        if let Some((claim, phrase)) = s.rsplit_once(" by ") {
+            let (phrase, stop) = match phrase.strip_suffix('.') {
+                Some(phrase) => (phrase, "."),
+                None => (phrase, ""),
+            };
+            if let Some(code) = self.cited_theorem(phrase) {
+                let claim = self.reverse_sentence(&format!("{}{}", claim, stop))?;
+                return Some(format!("{}{}", claim, code));
+            }
+        }
+        if let Some(inner) = s.strip_prefix("We will prove by contradiction: ") {
+            return Some(format!("\\pC:{}", self.reverse_sentence(inner)?));
+        }
+        if let Some(inner) = s.strip_prefix("We will prove: ") {
+            return Some(format!("\\p:{}", self.reverse_sentence(inner)?));
+        }
+        if let Some((main, rest)) = s.split_once("\nBegin casework") {
+            let main = match main {
+                "" => String::new(),
+                // A value such as `AB` may stand as the subject of casework
+                main => self
+                    .reverse_sentence(main)
+                    .or_else(|| Some(self.expr(main)).filter(|v| !v.contains(' ')))?,
+            };
+            return match rest {
+                "." => Some(format!("{}<<", main)),
+                _ => Some(format!("{}<<{}>>", main, cases(rest.strip_prefix(": ")?)?)),
+            };
+        }
+        // A closing marker names the claim it proves, which the `\\p:` before
+        // it already gave.
+        let closing = s
+            .split_once(", which proves that ")
+            .map_or(s, |(marker, _)| marker);
+        match closing.trim_end_matches('.') {
+            "And that is what was to be shown" => return Some("\\q".into()),
+            "Achieving a contradiction" => return Some("\\qC".into()),
+            _ => {}
+        }
+        match s {
+            "Because" => return Some("\\bc".into()),
+            "Therefore" => return Some("\\th".into()),
+            "End casework." => return Some(">>".into()),
+            _ => {}
+        }
+        if let Some(body) = s.strip_prefix("For all ") {
+            return Some(format!("|A{}", body));
+        }
+        if let Some(body) = s.strip_prefix("There exists ") {
+            return Some(format!("|E{}", body));
+        }
+        if let Some(question) = s.strip_suffix('?') {
+            return self.question(question);
+        }
+        if let Some(declaration) = s.strip_suffix('.') {
+            return self.declaration(declaration);
+        }
+        self.claim(s)
+    }
+
+    /// Queries and yes/no questions, without the `?`.
+    fn question(&self, s: &str) -> Option<String> {
+        let query = if let Some(arc) = s.strip_prefix("What is the length of arc ") {
+            format!("a{}", arc)
+        } else if let Some(sector) = s.strip_prefix("What is the area of sector ") {
+            format!("q{}", sector)
+        } else if let Some(obj) = s.strip_prefix("What is the area of ") {
+            format!("[{}]", obj)
+        } else if let Some(obj) = s.strip_prefix("What is the perimeter of ") {
+            format!("({})", obj)
+        } else if let Some(arc) = s.strip_prefix("What is the measure of arc ") {
+            format!("<a{}", arc)
+        } else if let Some(obj) = s.strip_prefix("What is the measure of angle ") {
+            format!("<{}", obj)
+        } else if let Some(expr) = s.strip_prefix("What is ") {
+            self.expr(expr)
+        } else if let Some(rest) = s.strip_prefix("Are ") {
+            let (objects, relation) = rest.rsplit_once(' ')?;
+            format!(
+                "{}*{}",
+                objects.replace(" and ", ";"),
+                self.relation_code(relation)
+            )
+        } else if let Some(rest) = s.strip_prefix("Is ") {
+            let (object, property) = rest.rsplit_once(' ')?;
+            format!("{}*{}", object, self.property_code(property))
+        } else {
+            return None;
+        };
+        Some(format!("{}?", query))
+    }
+
+    /// Sentences ending in a period, without it.
+    fn declaration(&self, s: &str) -> Option<String> {
+        if let Some(inner) = s.strip_prefix("Prove that ") {
+            return Some(format!("{}\\?", inner));
+        }
+        if let Some(eq) = s.strip_prefix("Graph the function ") {
+            return Some(format!("G:{{{}}}", eq));
+        }
+        if let Some(rest) = s.strip_prefix("Construct the ") {
+            return Some(self.derived(rest));
+        }
+        if let Some(rest) = s.strip_prefix("Let point ") {
+            let (name, rest) = rest.split_once(" be ")?;
+            if let Some(coords) = rest.strip_prefix("at coordinates ") {
+                return Some(format!("P:{}{{{}}}", name, coords));
+            }
+            let objects = rest.strip_prefix("the intersection of ")?;
+            let (init, last) = objects.rsplit_once(" and ")?;
+            let objects = init
+                .split(", ")
+                .chain([last])
+                .map(described_object)
+                .collect::<Option<Vec<_>>>()?;
+            return Some(format!("P:{}={}", name, objects.join("x")));
+        }
+        if let Some(names) = s.strip_prefix("Construct points ") {
+            return Some(format!("P:{}", names.replace(", ", ",")));
+        }
+        if let Some(rest) = s.strip_prefix("Construct point ") {
+            return self.point_on(rest);
+        }
+        for (prefix, code) in [
+            ("Connect segment ", "S"),
+            ("Connect segments ", "S"),
+            ("Connect line ", "L"),
+            ("Connect lines ", "L"),
+            ("Construct ray ", "W"),
+            ("Construct rays ", "W"),
+        ] {
+            if let Some(objects) = s.strip_prefix(prefix) {
+                return Some(format!("{}:{}", code, objects.replace(", ", ",")));
+            }
+        }
+        if let Some(rest) = s.strip_prefix("Construct a circle ") {
+            return self.circle(rest);
+        }
+        if let Some(name) = s.strip_prefix("Construct circle ") {
+            return Some(format!("C:={}", name));
+        }
+        if let Some(rest) = s.strip_prefix("Construct ") {
+            if rest.contains(" with side ") {
+                return Some(format!("R:{}", regular_polygon(rest)?));
+            }
+            return self.polygon(rest);
+        }
+        if let Some(phrase) = s.strip_prefix("By ") {
+            return match self.cited_theorem(phrase) {
+                Some(code) => Some(code.to_string()),
+                None if phrase.starts_with("the ") => None,
+                None => Some(phrase.to_string()),
+            };
+        }
+        if let Some(rest) = s.strip_prefix("Arc ") {
+            if let Some((arc, rest)) = rest.split_once(" has measure ") {
+                let value = rest.strip_suffix(" degrees")?;
+                return Some(format!("<a{}={}", arc, self.expr(value)));
+            }
+            let (arc, value) = rest.split_once(" has length ")?;
+            return Some(format!("a{}={}", arc, self.expr(value)));
+        }
+        if let Some(rest) = s.strip_prefix("The area of sector ") {
+            let (sector, value) = rest.split_once(" is ")?;
+            return Some(format!("q{}={}", sector, self.expr(value)));
+        }
+        if let Some(rest) = s.strip_prefix("Let the area of ") {
+            let (obj, value) = rest.split_once(" be ")?;
+            return Some(format!("[{}]={}", obj, self.expr(value)));
+        }
+        if let Some(rest) = s.strip_prefix("Let the perimeter of ") {
+            let (obj, value) = rest.split_once(" be ")?;
+            return Some(format!("({})={}", obj, self.expr(value)));
+        }
+        if let Some(rest) = s.strip_prefix("Angle ") {
+            let (obj, rest) = rest.split_once(" measures ")?;
+            let value = rest.strip_suffix(" degrees")?;
+            return Some(format!("<{}={}", obj, self.expr(value)));
+        }
+        self.fact(s)
+    }
+
+    /// Orientation, relationship and property sentences, which are also the
+    /// forms conditions on a point take.
+    fn fact(&self, s: &str) -> Option<String> {
+        if let Some(object) = s.strip_suffix(" goes clockwise") {
+            return Some(format!("{}*+", object));
+        }
+        if let Some(object) = s.strip_suffix(" goes counterclockwise") {
+            return Some(format!("{}*-", object));
+        }
+        if let Some((objects, relation)) = s.split_once(" are ") {
+            return Some(format!(
+                "{}*{}",
+                objects.replace(" and ", ";"),
+                self.relation_code(relation)
+            ));
+        }
+        let (object, property) = s.split_once(" is ")?;
+        if object.contains(' ') || property.contains(' ') {
+            return None;
+        }
+        Some(format!("{}*{}", object, self.property_code(property)))
+    }
+
+    /// Logic and comparisons, which the translator leaves unpunctuated.
+    fn claim(&self, s: &str) -> Option<String> {
+        for (word, op) in [(" implies ", "=>"), (" or ", "||"), (" and ", "&&")] {
+            if let Some((lhs, rhs)) = s.split_once(word) {
+                if !lhs.contains(' ') && !rhs.contains(' ') {
+                    return Some(format!("{}{}{}", lhs, op, rhs));
+                }
+            }
+        }
+        for (phrase, op) in [
+            (" is less than or equal to ", "<="),
+            (" is greater than or equal to ", ">="),
+            (" is less than ", "<"),
+            (" is greater than ", ">"),
+            (" does not equal ", "!="),
+            (" = ", "="),
+        ] {
+            if let Some((lhs, rhs)) = s.split_once(phrase) {
+                return Some(format!("{}{}{}", self.expr(lhs), op, self.expr(rhs)));
+            }
+        }
+        None
+    }
+
+    /// `Construct the ...` without the prefix: a derived construction.
+    fn derived(&self, s: &str) -> String {
+        let (head, args) = s.split_once(" of ").unwrap_or((s, ""));
+        let mut names: Vec<(&str, &str)> = self.derived_constructions.iter().collect();
+        // Longest first, so `perpendicular bisector` is not read as
+        // `perpendicular` with target `bisector`.
+        names.sort_by_key(|(code, name)| (std::cmp::Reverse(name.len()), *code));
+        let (code, target) = names
+            .iter()
+            .find_map(|(code, name)| {
+                let rest = head.strip_prefix(name)?;
+                match rest.strip_prefix(' ') {
+                    Some(target) => Some((*code, Some(target))),
+                    None if rest.is_empty() => Some((*code, None)),
+                    None => None,
+                }
+            })
+            .unwrap_or_else(|| match head.split_once(' ') {
+                Some((code, target)) => (code, Some(target)),
+                None => (head, None),
+            });
+        match target {
+            Some(target) => format!("{}:{}={}", code, args, target),
+            None => format!("{}:{}", code, args),
+        }
+    }
+
+    /// `Construct point ...` without the prefix.
+    fn point_on(&self, s: &str) -> Option<String> {
+        if let Some((name, region)) = s.split_once(" in the region ") {
+            return Some(format!("P:{}..{}", name, region));
+        }
+        let Some((name, rest)) = s.split_once(" on ") else {
+            return Some(format!("P:{}", s));
+        };
+        let Some((object, conditions)) = rest.split_once(" such that ") else {
+            return Some(format!("P:{}.{}", name, rest));
+        };
+        let conditions = self.conditions(conditions)?;
+        Some(format!("P:{}.{}|{}", name, object, conditions.join(",")))
+    }
+
+    fn conditions(&self, s: &str) -> Option<Vec<String>> {
+        // Polygon conditions list their polygons with `, ` too, so pieces
+        // are glued back on until the `is` that ends the list.
+        let mut pieces: Vec<String> = Vec::new();
+        for piece in s.split(", ") {
+            match pieces.last_mut() {
+                Some(last) if last.starts_with("polygon ") && !last.contains(" is ") => {
+                    last.push_str(", ");
+                    last.push_str(piece);
+                }
+                _ => pieces.push(piece.to_string()),
+            }
+        }
+        pieces.iter().map(|c| self.condition(c)).collect()
+    }
+
+    fn condition(&self, s: &str) -> Option<String> {
+        if s.contains(" with side ") {
+            return Some(format!("R:{}", regular_polygon(s)?));
+        }
+        if let Some(rest) = s.strip_prefix("the area of ") {
+            let (obj, value) = rest.split_once(" is ")?;
+            return Some(format!("[{}]={}", obj, self.expr(value)));
+        }
+        if let Some(rest) = s.strip_prefix("polygon ") {
+            let (objects, properties) = rest.split_once(" is ")?;
+            let codes: Vec<String> = properties
+                .split(" and ")
+                .map(|p| format!("*{}", self.property_code(p)))
+                .collect();
+            return Some(format!(
+                "J:{}{}",
+                objects.replace(", ", ","),
+                codes.concat()
+            ));
+        }
+        // Anything else was written as shorthand.
+        Some(self.fact(s).unwrap_or_else(|| s.to_string()))
+    }
+
+    /// `Construct a circle ...` without the prefix.
+    fn circle(&self, s: &str) -> Option<String> {
+        if let Some(points) = s.strip_prefix("through points ") {
+            let points: Vec<&str> = points
+                .split(", ")
+                .map(|p| p.strip_prefix("and ").unwrap_or(p))
+                .collect();
+            return Some(format!("C:{}", points.join(";")));
+        }
+        if let Some(point) = s.strip_prefix("passing through point ") {
+            return Some(format!("C:{}", point));
+        }
+        let rest = s.strip_prefix("with center ")?;
+        if let Some((center, radius)) = rest.split_once(" and radius ") {
+            return Some(format!("C:{};{}", center, self.expr(radius)));
+        }
+        let (center, point) = rest.split_once(" passing through point ")?;
+        Some(format!("C:{};{}", center, point))
+    }
+
+    /// `Construct ... polygon ABC` without `Construct `.
+    fn polygon(&self, s: &str) -> Option<String> {
+        let (words, objects) = s
+            .split_once("polygon ")
+            .or_else(|| s.split_once("polygons "))?;
+        let codes: Vec<String> = words
+            .split_whitespace()
+            .map(|word| format!("*{}", self.property_code(word)))
+            .collect();
+        Some(format!(
+            "J:{}{}",
+            objects.replace(", ", ","),
+            codes.concat()
+        ))
+    }
+
+    /// An expression as `describe_expr` wrote it, with constants spelled out.
+    fn expr(&self, s: &str) -> String {
+        let mut expr = s.replace(" times ", "").replace("angle ", "<");
+        for (code, name) in self.constants.iter() {
+            if code.starts_with('\\') {
+                expr = expr.replace(name, code);
+            }
+        }
+        expr
+    }
+
+    fn property_code<'a>(&'a self, name: &'a str) -> &'a str {
+        code_for(&self.properties, name).unwrap_or(name)
+    }
+
+    fn relation_code<'a>(&'a self, name: &'a str) -> &'a str {
+        code_for(&self.relationships, name)
+            .or_else(|| code_for(&self.properties, name))
+            .unwrap_or(name)
+    }
+
+    /// The code of the theorem a citation names, with or without `the`, as
+    /// in `the Pythagorean Theorem` or `Heron's Formula`.
+    fn cited_theorem(&self, phrase: &str) -> Option<&str> {
+        let name = phrase.strip_prefix("the ").unwrap_or(phrase);
+        code_for(&self.theorems, name)
+    }
+}
+
+/// The code a table gives `name`. When several do, the ASCII one that sorts
+/// first is used, so `perpendicular` is `PD` and not `⊥`.
+fn code_for<'a>(table: &'a Table, name: &str) -> Option<&'a str> {
+    table
+        .iter()
+        .filter(|(_, n)| *n == name)
+        .map(|(code, _)| code)
+        .min_by_key(|code| (!code.is_ascii(), code.len(), *code))
+}
+
+/// `ray AB`, `circle O`, ... as `get_object_description` writes them.
+fn described_object(s: &str) -> Option<String> {
+    let (kind, name) = s.split_once(' ')?;
+    let prefix = match kind {
+        "segment" => "",
+        "ray" => "w",
+        "line" => "l",
+        "circle" => "c",
+        "arc" => "a",
+        "sector" => "q",
+        "triangle" => "△",
+        _ => return None,
+    };
+    Some(format!("{}{}", prefix, name))
+}
+
+/// `square ABCD with side AB` as `3;AB=ABC`-style regular polygon arguments.
+fn regular_polygon(s: &str) -> Option<String> {
+    let (shape, side) = s.rsplit_once(" with side ")?;
+    let (shape, polygon) = shape.rsplit_once(' ')?;
+    let sides = ["3", "4", "5", "6", "8"]
+        .into_iter()
+        .find(|n| shape_name(n) == shape)
+        .map(String::from)
+        .or_else(|| {
+            let n = shape.strip_prefix("regular ")?.strip_suffix("-gon")?;
+            Some(n.to_string())
+        })?;
+    Some(format!("{};{}={}", sides, side, polygon))
+}
+
+/// `Case 1: if c, then r; Case 2: ...` as `(c;r),(...)`.
+fn cases(s: &str) -> Option<String> {
+    let cases = s
+        .split("; Case ")
+        .map(|case| {
+            let (_, body) = case.split_once(": if ")?;
+            let (condition, result) = body.split_once(", then ")?;
+            Some(format!("({};{})", condition, result))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(cases.join(","))
+}
+
+/// Parse one statement and print it back, which settles its spelling.
+fn canonical(gsl: &str) -> Result<String, String> {
+    let mut parsed = parser::parse(&format!("\\\\{}\\\\", gsl));
+    match (parsed.pop(), parsed.is_empty()) {
+        (Some(Ok(stmt)), true) => Ok(stmt.to_string()),
+        (Some(Err(err)), true) => Err(err.message),
+        _ => Err("expected a single statement".to_string()),
+    }
+}
+
+/// The sentences of `text` with where each one is, one per line.
+fn sentences(text: &str) -> Vec<(String, Span)> {
+    let mut sentences: Vec<(String, Span)> = Vec::new();
+    let mut previous_blank = true;
+    let mut start = 0;
+    for line in text.split_inclusive('\n') {
+        let line_start = start;
+        start += line.len();
+        let trimmed = line.trim();
+        let offset = line_start + (line.len() - line.trim_start().len());
+        let span = Span::new(offset, offset + trimmed.len());
+        let sentence = strip_decoration(trimmed);
+
+        if sentence.starts_with("Begin casework") {
+            match sentences.last_mut() {
+                Some((last, last_span)) if !previous_blank => {
+                    last.push('\n');
+                    last.push_str(sentence);
+                    last_span.end = span.end;
+                }
+                _ => sentences.push((format!("\n{}", sentence), span)),
+            }
+        } else if !sentence.is_empty() {
+            sentences.push((sentence.to_string(), span));
+        }
+        previous_blank = sentence.is_empty();
+    }
+    sentences
+}
+
+/// Drop the `3. ` and ` → 6` the text format adds around a sentence.
+fn strip_decoration(line: &str) -> &str {
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let line = match line[digits..].strip_prefix('.') {
+        Some(rest) if digits > 0 && !rest.starts_with(|c: char| !c.is_whitespace()) => {
+            rest.trim_start()
+        }
+        _ => line,
+    };
+    line.split_once(" → ")
+        .map_or(line, |(sentence, _)| sentence)
+}
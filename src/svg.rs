@@ -0,0 +1,397 @@
+//! SVG diagrams of a problem's construction, laid out by the numeric
+//! evaluator and fitted to the figure's bounding box.
+
+use crate::ast::*;
+use crate::eval::{Figure, Scene};
+use crate::geometry::{self, Circle, Line, Point};
+use crate::translator::Translation;
+use std::fmt::Write;
+
+/// The longer side of the drawing, in pixels.
+const SIZE: f64 = 480.0;
+/// Screen radius of angle markers.
+const MARKER: f64 = 18.0;
+
+const STYLE: &str = "  <style>
+    .figure { fill: none; stroke: #222; stroke-width: 1.5; }
+    .derived { fill: none; stroke: #777; stroke-width: 1; stroke-dasharray: 6 4; }
+    .area { fill: #4a90d9; fill-opacity: 0.25; stroke: none; }
+    .sector { fill: #e67e22; fill-opacity: 0.3; stroke: #e67e22; }
+    .arc { fill: none; stroke: #c0392b; stroke-width: 3; }
+    .angle { fill: none; stroke: #c0392b; stroke-width: 1.2; }
+    .point { fill: #000; }
+    .label { font: 14px sans-serif; fill: #000; }
+  </style>
+";
+
+/// A standalone SVG drawing of every object the translation constructs.
+pub fn translation(translation: &Translation) -> String {
+    let statements: Vec<&Statement> = translation.steps.iter().map(|s| &s.statement).collect();
+    let scene = Scene::evaluate(statements.iter().copied());
+    render(&statements, &scene)
+}
+
+/// Draw `scene`, highlighting the areas, angles, arcs and sectors the
+/// statements mention.
+pub fn render(statements: &[&Statement], scene: &Scene) -> String {
+    let view = Viewport::fit(scene);
+    let mut out = String::new();
+    writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w:.0}\" height=\"{h:.0}\" viewBox=\"0 0 {w:.2} {h:.2}\">",
+        w = view.width,
+        h = view.height
+    )
+    .unwrap();
+    out.push_str(STYLE);
+    out.push_str("  <rect width=\"100%\" height=\"100%\" fill=\"#fff\"/>\n");
+
+    let mut marks = Vec::new();
+    for stmt in statements {
+        stmt.walk(&mut |s| collect_marks(s, &mut marks));
+    }
+
+    for mark in &marks {
+        match mark {
+            Mark::Area(obj) => view.area(&mut out, scene, obj),
+            Mark::Sector(obj) => view.sector(&mut out, scene, obj, "sector"),
+            _ => {}
+        }
+    }
+    for figure in scene.figures() {
+        view.figure(&mut out, scene, figure);
+    }
+    for mark in &marks {
+        match mark {
+            Mark::Angle(obj) => view.angle(&mut out, scene, obj),
+            Mark::Arc(obj) => view.arc(&mut out, scene, obj),
+            _ => {}
+        }
+    }
+    view.points(&mut out, scene);
+    out.push_str("</svg>\n");
+    out
+}
+
+/// Something a statement asks to be highlighted.
+pub(crate) enum Mark<'a> {
+    Area(&'a Object),
+    Sector(&'a Object),
+    Angle(&'a Object),
+    Arc(&'a Object),
+}
+
+pub(crate) fn collect_marks<'a>(stmt: &'a Statement, marks: &mut Vec<Mark<'a>>) {
+    match &stmt.kind {
+        StmtKind::Relation(lhs, _, rhs) => {
+            expr_marks(lhs, marks);
+            expr_marks(rhs, marks);
+        }
+        StmtKind::Query(expr) | StmtKind::Value(expr) => expr_marks(expr, marks),
+        _ => {}
+    }
+}
+
+fn expr_marks<'a>(expr: &'a Expr, marks: &mut Vec<Mark<'a>>) {
+    match expr {
+        Expr::Area(obj) if obj.kind == ObjectKind::Sector => marks.push(Mark::Sector(obj)),
+        Expr::Area(obj) => marks.push(Mark::Area(obj)),
+        Expr::Angle(obj) if obj.kind == ObjectKind::Arc => marks.push(Mark::Arc(obj)),
+        Expr::Angle(obj) => marks.push(Mark::Angle(obj)),
+        Expr::Object(obj) if obj.kind == ObjectKind::Arc => marks.push(Mark::Arc(obj)),
+        Expr::Object(obj) if obj.kind == ObjectKind::Sector => marks.push(Mark::Sector(obj)),
+        Expr::Binary(lhs, _, rhs) | Expr::Product(lhs, rhs) => {
+            expr_marks(lhs, marks);
+            expr_marks(rhs, marks);
+        }
+        _ => {}
+    }
+}
+
+/// Maps figure coordinates onto the drawing, with y pointing up.
+struct Viewport {
+    min: Point,
+    max: Point,
+    scale: f64,
+    width: f64,
+    height: f64,
+    center: Point,
+}
+
+impl Viewport {
+    fn fit(scene: &Scene) -> Viewport {
+        let mut min = Point::new(f64::INFINITY, f64::INFINITY);
+        let mut max = Point::new(f64::NEG_INFINITY, f64::NEG_INFINITY);
+        let mut include = |p: Point, r: f64| {
+            min = Point::new(min.x.min(p.x - r), min.y.min(p.y - r));
+            max = Point::new(max.x.max(p.x + r), max.y.max(p.y + r));
+        };
+        let points: Vec<Point> = scene.points().map(|(_, p)| p).collect();
+        for &p in &points {
+            include(p, 0.0);
+        }
+        for figure in scene.figures() {
+            if let Figure::Circle(key) = figure {
+                if let Some(c) = scene.circle_by_key(key) {
+                    include(c.center, c.radius);
+                }
+            }
+        }
+        if min.x > max.x {
+            min = Point::new(-1.0, -1.0);
+            max = Point::new(1.0, 1.0);
+        }
+        let span = (max.x - min.x).max(max.y - min.y).max(1.0);
+        let margin = Point::new(1.0, 1.0) * (span * 0.12);
+        let min = min - margin;
+        let max = max + margin;
+        let scale = SIZE / (max.x - min.x).max(max.y - min.y);
+        let center = if points.is_empty() {
+            min.midpoint(max)
+        } else {
+            geometry::centroid(&points)
+        };
+        Viewport {
+            min,
+            max,
+            scale,
+            width: (max.x - min.x) * scale,
+            height: (max.y - min.y) * scale,
+            center,
+        }
+    }
+
+    fn x(&self, p: Point) -> f64 {
+        (p.x - self.min.x) * self.scale
+    }
+
+    fn y(&self, p: Point) -> f64 {
+        (self.max.y - p.y) * self.scale
+    }
+
+    fn xy(&self, p: Point) -> String {
+        format!("{:.2} {:.2}", self.x(p), self.y(p))
+    }
+
+    /// The part of a line inside the viewport; rays start at `line.a`.
+    fn clip(&self, line: &Line, ray: bool) -> Option<(Point, Point)> {
+        let d = line.direction();
+        let (mut lo, mut hi) = (if ray { 0.0 } else { f64::NEG_INFINITY }, f64::INFINITY);
+        for (delta, start, low, high) in [
+            (d.x, line.a.x, self.min.x, self.max.x),
+            (d.y, line.a.y, self.min.y, self.max.y),
+        ] {
+            if delta.abs() < geometry::EPSILON {
+                if start < low || start > high {
+                    return None;
+                }
+                continue;
+            }
+            let (t0, t1) = ((low - start) / delta, (high - start) / delta);
+            lo = lo.max(t0.min(t1));
+            hi = hi.min(t0.max(t1));
+        }
+        (lo < hi).then(|| (line.a + d * lo, line.a + d * hi))
+    }
+
+    fn segment(&self, out: &mut String, a: Point, b: Point, class: &str) {
+        writeln!(
+            out,
+            "  <line class=\"{}\" x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\"/>",
+            class,
+            self.x(a),
+            self.y(a),
+            self.x(b),
+            self.y(b)
+        )
+        .unwrap();
+    }
+
+    fn polygon(&self, out: &mut String, points: &[Point], class: &str) {
+        let coords: Vec<String> = points
+            .iter()
+            .map(|&p| format!("{:.2},{:.2}", self.x(p), self.y(p)))
+            .collect();
+        writeln!(
+            out,
+            "  <polygon class=\"{}\" points=\"{}\"/>",
+            class,
+            coords.join(" ")
+        )
+        .unwrap();
+    }
+
+    fn circle(&self, out: &mut String, c: &Circle, class: &str) {
+        writeln!(
+            out,
+            "  <circle class=\"{}\" cx=\"{:.2}\" cy=\"{:.2}\" r=\"{:.2}\"/>",
+            class,
+            self.x(c.center),
+            self.y(c.center),
+            c.radius * self.scale
+        )
+        .unwrap();
+    }
+
+    fn figure(&self, out: &mut String, scene: &Scene, figure: &Figure) {
+        let point = |name: &String| scene.point(name);
+        match figure {
+            Figure::Segment(a, b) => {
+                if let (Some(a), Some(b)) = (point(a), point(b)) {
+                    self.segment(out, a, b, "figure");
+                }
+            }
+            Figure::Line(a, b) | Figure::Ray(a, b) => {
+                if let (Some(a), Some(b)) = (point(a), point(b)) {
+                    let ray = matches!(figure, Figure::Ray(..));
+                    if let Some((p, q)) = self.clip(&Line::new(a, b), ray) {
+                        self.segment(out, p, q, "figure");
+                    }
+                }
+            }
+            Figure::Polygon(names) => {
+                if let Some(points) = names.iter().map(point).collect::<Option<Vec<_>>>() {
+                    self.polygon(out, &points, "figure");
+                }
+            }
+            Figure::Circle(key) => {
+                if let Some(c) = scene.circle_by_key(key) {
+                    self.circle(out, &c, "figure");
+                }
+            }
+            Figure::DerivedLine { line, .. } => {
+                if let Some((p, q)) = self.clip(line, false) {
+                    self.segment(out, p, q, "derived");
+                }
+            }
+        }
+    }
+
+    fn area(&self, out: &mut String, scene: &Scene, obj: &Object) {
+        if obj.kind == ObjectKind::Circle {
+            if let Some(c) = scene.circle(obj) {
+                self.circle(out, &c, "area");
+            }
+            return;
+        }
+        let points: Option<Vec<Point>> = obj.points.iter().map(|n| scene.point(n)).collect();
+        if let Some(points) = points.filter(|p| p.len() >= 3) {
+            self.polygon(out, &points, "area");
+        }
+    }
+
+    /// A minor arc from `from` to `to` around `center`, as SVG path commands.
+    fn arc_path(&self, center: Point, from: Point, to: Point, radius: f64) -> String {
+        let sweep = if (from - center).cross(to - center) > 0.0 {
+            0
+        } else {
+            1
+        };
+        format!(
+            "M {} A {:.2} {:.2} 0 0 {} {}",
+            self.xy(from),
+            radius,
+            radius,
+            sweep,
+            self.xy(to)
+        )
+    }
+
+    fn sector(&self, out: &mut String, scene: &Scene, obj: &Object, class: &str) {
+        let points: Option<Vec<Point>> = obj.points.iter().map(|n| scene.point(n)).collect();
+        let Some([o, a, b]) = points.as_deref() else {
+            return;
+        };
+        let r = o.dist(*a);
+        let end = *o + (*b - *o).unit() * r;
+        writeln!(
+            out,
+            "  <path class=\"{}\" d=\"M {} L {} Z\"/>",
+            class,
+            self.xy(*o),
+            self.arc_path(*o, *a, end, r * self.scale)
+                .trim_start_matches("M ")
+        )
+        .unwrap();
+    }
+
+    fn arc(&self, out: &mut String, scene: &Scene, obj: &Object) {
+        let (Some(c), [a, b]) = (scene.circle_through(&obj.points), &obj.points[..]) else {
+            return;
+        };
+        let (Some(a), Some(b)) = (scene.point(a), scene.point(b)) else {
+            return;
+        };
+        writeln!(
+            out,
+            "  <path class=\"arc\" d=\"{}\"/>",
+            self.arc_path(c.center, a, b, c.radius * self.scale)
+        )
+        .unwrap();
+    }
+
+    fn angle(&self, out: &mut String, scene: &Scene, obj: &Object) {
+        let points: Option<Vec<Point>> = obj.points.iter().map(|n| scene.point(n)).collect();
+        let Some([a, b, c]) = points.as_deref() else {
+            return;
+        };
+        if a.dist(*b) < geometry::EPSILON || c.dist(*b) < geometry::EPSILON {
+            return;
+        }
+        let r = MARKER / self.scale;
+        let u = (*a - *b).unit();
+        let v = (*c - *b).unit();
+        if (geometry::angle(*a, *b, *c) - 90.0).abs() < 1e-6 {
+            let s = r * 0.7;
+            let corner = [*b + u * s, *b + u * s + v * s, *b + v * s];
+            let coords: Vec<String> = corner.iter().map(|&p| self.xy(p)).collect();
+            writeln!(
+                out,
+                "  <path class=\"angle\" d=\"M {} L {} L {}\"/>",
+                coords[0], coords[1], coords[2]
+            )
+            .unwrap();
+        } else {
+            writeln!(
+                out,
+                "  <path class=\"angle\" d=\"{}\"/>",
+                self.arc_path(*b, *b + u * r, *b + v * r, MARKER)
+            )
+            .unwrap();
+        }
+    }
+
+    fn points(&self, out: &mut String, scene: &Scene) {
+        for (name, p) in scene.points() {
+            writeln!(
+                out,
+                "  <circle class=\"point\" cx=\"{:.2}\" cy=\"{:.2}\" r=\"3\"/>",
+                self.x(p),
+                self.y(p)
+            )
+            .unwrap();
+            // Put the label on the side away from the middle of the figure.
+            let away = p - self.center;
+            let dir = if away.norm() < geometry::EPSILON {
+                Point::new(1.0, 1.0).unit()
+            } else {
+                away.unit()
+            };
+            let at = Point::new(self.x(p) + dir.x * 12.0, self.y(p) - dir.y * 12.0);
+            writeln!(
+                out,
+                "  <text class=\"label\" x=\"{:.2}\" y=\"{:.2}\" text-anchor=\"middle\" dominant-baseline=\"middle\">{}</text>",
+                at.x,
+                at.y,
+                escape(name)
+            )
+            .unwrap();
+        }
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,285 @@
+//! What the theorems GSL can cite say, as `gslc theorem <code>` prints it.
+//!
+//! A step cites a theorem with a trailing code, as in `AC=5_PY` or
+//! `△ABC≅△DEF_SAC`. Each built-in code has a statement of the theorem and the
+//! hypotheses a step must meet to cite it. Codes added with `\def _CODE{name}`
+//! only have their name.
+
+use crate::diagnostic::Diagnostic;
+use crate::lexer::Span;
+use crate::translator::unknown_code_hint;
+use crate::Translator;
+
+/// Each built-in theorem code, what the theorem says and what it assumes.
+const STATEMENTS: &[(&str, &str, &[&str])] = &[
+    (
+        "_PY",
+        "In a right triangle, the square of the hypotenuse is the sum of the squares of the legs: a² + b² = c².",
+        &["the triangle has a right angle", "c is the side opposite it"],
+    ),
+    (
+        "_TI",
+        "For any points A, B and C, AB + BC ≥ AC, with equality exactly when B lies on segment AC.",
+        &["A, B and C are any three points"],
+    ),
+    (
+        "_ST",
+        "If D on side BC of triangle ABC splits it into BD = m and DC = n, with AD = d and a = m + n, then b²m + c²n = a(d² + mn).",
+        &["D lies on segment BC"],
+    ),
+    (
+        "_AT",
+        "If M is the midpoint of side BC of triangle ABC, then AB² + AC² = 2(AM² + BM²).",
+        &["M is the midpoint of BC"],
+    ),
+    (
+        "_VT",
+        "The distances from a point of an equilateral triangle to its three sides add up to the triangle's height.",
+        &["the triangle is equilateral", "the point lies inside it or on a side"],
+    ),
+    (
+        "_NP",
+        "The centers of the equilateral triangles erected on the sides of any triangle form an equilateral triangle.",
+        &["the equilateral triangles are all erected outward, or all inward"],
+    ),
+    (
+        "_EL",
+        "The orthocenter H, centroid G and circumcenter O of a triangle lie on one line, with HG = 2GO.",
+        &["the triangle is not equilateral, so the three centers are distinct"],
+    ),
+    (
+        "_9C",
+        "The midpoints of a triangle's sides, the feet of its altitudes and the midpoints from its vertices to the orthocenter lie on one circle, whose radius is half the circumradius.",
+        &["the nine points come from the same triangle"],
+    ),
+    (
+        "_SL",
+        "The feet of the perpendiculars from a point P to the side lines of triangle ABC are collinear exactly when P lies on the circumcircle of ABC.",
+        &["P lies on the circumcircle of ABC"],
+    ),
+    (
+        "_CV",
+        "Lines AD, BE and CF through the vertices of triangle ABC are concurrent exactly when (BD/DC)(CE/EA)(AF/FB) = 1.",
+        &[
+            "D, E and F lie on lines BC, CA and AB",
+            "the ratios are signed when a point lies outside its side",
+        ],
+    ),
+    (
+        "_ML",
+        "Points D, E and F on lines BC, CA and AB of triangle ABC are collinear exactly when (BD/DC)(CE/EA)(AF/FB) = −1, with signed lengths.",
+        &[
+            "D, E and F lie on lines BC, CA and AB",
+            "none of them is a vertex",
+        ],
+    ),
+    (
+        "_AB",
+        "If AD bisects angle BAC of triangle ABC with D on BC, then BD/DC = AB/AC.",
+        &["AD bisects angle BAC", "D lies on BC"],
+    ),
+    (
+        "_IE",
+        "If the bisector of angle A meets the circumcircle of triangle ABC again at M, then M is the same distance from B, C, the incenter and the A-excenter.",
+        &["M is the midpoint of arc BC that does not contain A"],
+    ),
+    (
+        "_CT",
+        "The signed distances from the circumcenter of a triangle to its sides add up to R + r, the circumradius plus the inradius.",
+        &["a distance is negative when the segment to its side lies outside the triangle"],
+    ),
+    (
+        "_MQ",
+        "If D, E and F lie on sides BC, CA and AB of triangle ABC, the circles AEF, BFD and CDE pass through a common point.",
+        &["D, E and F lie on lines BC, CA and AB"],
+    ),
+    (
+        "_ET",
+        "The distance d between the circumcenter and incenter of a triangle satisfies d² = R(R − 2r), so R ≥ 2r.",
+        &["R is the circumradius and r the inradius of the same triangle"],
+    ),
+    (
+        "_DT",
+        "Two triangles are in perspective from a point exactly when they are in perspective from a line.",
+        &["the vertices of the triangles correspond in order"],
+    ),
+    (
+        "_HF",
+        "A triangle with sides a, b and c and semiperimeter s = (a + b + c)/2 has area √(s(s − a)(s − b)(s − c)).",
+        &["a, b and c are the three side lengths"],
+    ),
+    (
+        "_QF",
+        "A quadrilateral with sides a, b, c and d, semiperimeter s and opposite angles α and γ has area √((s − a)(s − b)(s − c)(s − d) − abcd·cos²((α + γ)/2)).",
+        &["the quadrilateral is convex"],
+    ),
+    (
+        "_BF",
+        "A cyclic quadrilateral with sides a, b, c and d and semiperimeter s has area √((s − a)(s − b)(s − c)(s − d)).",
+        &["the quadrilateral is cyclic"],
+    ),
+    (
+        "_JT",
+        "However a cyclic polygon is cut into triangles by its diagonals, the inradii of the triangles have the same sum.",
+        &["the polygon is convex and cyclic"],
+    ),
+    (
+        "_NT",
+        "In a quadrilateral with an incircle, the midpoints of the diagonals and the center of the incircle are collinear.",
+        &["the quadrilateral has an incircle"],
+    ),
+    (
+        "_PT",
+        "In a cyclic quadrilateral ABCD, AC·BD = AB·CD + AD·BC.",
+        &["A, B, C and D lie on a circle in that order"],
+    ),
+    (
+        "_PP",
+        "If lines through P meet a circle at A and B and at C and D, then PA·PB = PC·PD, which is PT² for a tangent PT.",
+        &[
+            "A, B, C and D lie on one circle",
+            "P, A and B are collinear, and so are P, C and D",
+        ],
+    ),
+    (
+        "_BT",
+        "If chords AB and CD pass through the midpoint M of chord PQ, and AD and BC meet PQ at X and Y, then M is the midpoint of XY.",
+        &["M is the midpoint of chord PQ", "chords AB and CD pass through M"],
+    ),
+    (
+        "_PC",
+        "The three points where opposite sides of a hexagon inscribed in a conic meet are collinear.",
+        &["the six vertices lie on a circle or another conic"],
+    ),
+    (
+        "_LC",
+        "In triangle ABC, c² = a² + b² − 2ab·cos C.",
+        &["c is the side opposite angle C"],
+    ),
+    (
+        "_LS",
+        "In triangle ABC, a/sin A = b/sin B = c/sin C = 2R, where R is the circumradius.",
+        &["each side is paired with the angle opposite it"],
+    ),
+    (
+        "_LT",
+        "In triangle ABC, (a − b)/(a + b) = tan((A − B)/2)/tan((A + B)/2).",
+        &["a and b are the sides opposite angles A and B"],
+    ),
+    (
+        "_PK",
+        "A polygon whose vertices are lattice points, with i lattice points inside and b on its boundary, has area i + b/2 − 1.",
+        &["every vertex has integer coordinates", "the polygon does not cross itself"],
+    ),
+    (
+        "_SH",
+        "A polygon with vertices (x₁, y₁), …, (xₙ, yₙ) in order has area ½|Σ(xᵢyᵢ₊₁ − xᵢ₊₁yᵢ)|.",
+        &[
+            "the vertices are listed in order around the polygon",
+            "the polygon does not cross itself",
+        ],
+    ),
+    (
+        "_SSC",
+        "Two triangles whose corresponding sides are equal are congruent.",
+        &["AB = DE, BC = EF and CA = FD"],
+    ),
+    (
+        "_SAC",
+        "Two triangles with two pairs of equal sides and equal angles between them are congruent.",
+        &["AB = DE and AC = DF", "∠BAC = ∠EDF"],
+    ),
+    (
+        "_SSA",
+        "Two sides and an angle not between them fix a triangle only when the side opposite the angle is at least as long as the other side.",
+        &[
+            "AB = DE and BC = EF",
+            "∠BCA = ∠EFD",
+            "AB ≥ BC, as when the angle is right or obtuse",
+        ],
+    ),
+    (
+        "_ASA",
+        "Two triangles with two pairs of equal angles and equal sides between them are congruent.",
+        &["∠A = ∠D and ∠B = ∠E", "AB = DE"],
+    ),
+    (
+        "_AAS",
+        "Two triangles with two pairs of equal angles and a pair of equal sides not between them are congruent.",
+        &["∠A = ∠D and ∠B = ∠E", "BC = EF"],
+    ),
+    (
+        "_HL",
+        "Two right triangles with equal hypotenuses and a pair of equal legs are congruent.",
+        &[
+            "both triangles have a right angle",
+            "their hypotenuses are equal",
+            "a leg of one equals a leg of the other",
+        ],
+    ),
+    (
+        "_AA",
+        "Two triangles with two pairs of equal angles are similar.",
+        &["∠A = ∠D and ∠B = ∠E"],
+    ),
+    (
+        "_SAS",
+        "Two triangles with two pairs of proportional sides and equal angles between them are similar.",
+        &["AB/DE = AC/DF", "∠BAC = ∠EDF"],
+    ),
+    (
+        "_SSS",
+        "Two triangles whose corresponding sides are proportional are similar.",
+        &["AB/DE = BC/EF = CA/FD"],
+    ),
+];
+
+/// A theorem a step can cite.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theorem {
+    /// The code that cites it, such as `_PY`.
+    pub code: String,
+    pub name: String,
+    /// What the theorem says, for codes built into GSL.
+    pub statement: Option<&'static str>,
+    /// What must hold for a step to cite it.
+    pub hypotheses: &'static [&'static str],
+}
+
+impl Translator {
+    /// Every theorem the translator can cite, in order of code.
+    pub fn theorems(&self) -> Vec<Theorem> {
+        let mut theorems: Vec<Theorem> = self
+            .theorems
+            .iter()
+            .map(|(code, name)| {
+                let statement = STATEMENTS.iter().find(|(c, ..)| *c == code);
+                Theorem {
+                    code: code.to_string(),
+                    name: name.to_string(),
+                    statement: statement.map(|&(_, statement, _)| statement),
+                    hypotheses: statement.map_or(&[], |&(.., hypotheses)| hypotheses),
+                }
+            })
+            .collect();
+        theorems.sort_by(|a, b| a.code.cmp(&b.code));
+        theorems
+    }
+
+    /// The theorem with `code`, which may leave out the `_` and be in lower
+    /// case. An unknown code is reported with the nearest one, spanning
+    /// `code`.
+    pub fn theorem(&self, code: &str) -> Result<Theorem, Diagnostic> {
+        let full = format!("_{}", code.trim_start_matches('_').to_uppercase());
+        self.theorems()
+            .into_iter()
+            .find(|theorem| theorem.code == full)
+            .ok_or_else(|| {
+                Diagnostic::error(
+                    format!("unknown theorem `{}`", full),
+                    Span::new(0, code.len()),
+                )
+                .with_hint(unknown_code_hint(&full, &self.theorems))
+            })
+    }
+}
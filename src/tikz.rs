@@ -0,0 +1,411 @@
+//! TikZ pictures drawn with tkz-euclide macros. Points and circles built by a
+//! derived construction are defined with the tkz-euclide macro for it
+//! (`\tkzDefTriangleCenter`, `\tkzDefMidPoint`, `\tkzInterLL`, …); every
+//! other point is defined at the coordinates the numeric evaluator solved, so
+//! the picture matches `draw` and the computed answers.
+
+use crate::ast::*;
+use crate::eval::{circle_key, format_number, Figure, Scene};
+use crate::geometry::{self, Circle, Line, Point};
+use crate::latex;
+use crate::svg::{collect_marks, Mark};
+use crate::translator::Translation;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+const PREAMBLE: &str = "\\documentclass[border=5pt]{standalone}
+\\usepackage{tkz-euclide}
+
+\\begin{document}
+";
+
+/// A `tikzpicture` of every object the translation constructs.
+pub fn translation(translation: &Translation) -> String {
+    let statements: Vec<&Statement> = translation.steps.iter().map(|s| &s.statement).collect();
+    let scene = Scene::evaluate(statements.iter().copied());
+    render(&statements, &scene)
+}
+
+/// A complete document around the picture that compiles on its own.
+pub fn document(translation: &Translation) -> String {
+    format!(
+        "{}{}\\end{{document}}\n",
+        PREAMBLE,
+        self::translation(translation)
+    )
+}
+
+/// Draw `scene`, marking the areas, angles, arcs and sectors the statements
+/// mention.
+pub fn render(statements: &[&Statement], scene: &Scene) -> String {
+    let mut constructions = Constructions::default();
+    for stmt in statements {
+        stmt.walk(&mut |s| constructions.statement(s));
+    }
+    let mut picture = Picture {
+        scene,
+        out: String::from("\\begin{tikzpicture}\n"),
+        helpers: Vec::new(),
+        circles: constructions.circles,
+    };
+    let names: Vec<&str> = scene.points().map(|(name, _)| name).collect();
+    let mut defined = HashSet::new();
+    for (name, p) in scene.points() {
+        // A construction can only be replayed once the points it is built
+        // from have been defined.
+        match constructions.points.get(name) {
+            Some((from, command)) if from.iter().all(|f| defined.contains(f.as_str())) => {
+                picture.line(format!("{} \\tkzGetPoint{{{}}}", command, name))
+            }
+            _ => picture.line(format!("\\tkzDefPoint({}){{{}}}", coords(p), name)),
+        }
+        defined.insert(name);
+    }
+
+    let mut marks = Vec::new();
+    for stmt in statements {
+        stmt.walk(&mut |s| collect_marks(s, &mut marks));
+    }
+    for mark in &marks {
+        match mark {
+            Mark::Area(obj) => picture.area(obj),
+            Mark::Sector(obj) => picture.sector(obj),
+            _ => {}
+        }
+    }
+    for figure in scene.figures() {
+        picture.figure(figure);
+    }
+    for mark in &marks {
+        match mark {
+            Mark::Angle(obj) => picture.angle(obj),
+            Mark::Arc(obj) => picture.arc(obj),
+            _ => {}
+        }
+    }
+    if !names.is_empty() {
+        picture.line(format!("\\tkzDrawPoints({})", names.join(",")));
+        picture.labels();
+    }
+    picture.out.push_str("\\end{tikzpicture}\n");
+    picture.out
+}
+
+fn coords(p: Point) -> String {
+    format!("{},{}", format_number(p.x), format_number(p.y))
+}
+
+/// The `\tkzDefTriangleCenter` option for a center code.
+fn center_option(code: &str) -> Option<&'static str> {
+    Some(match code {
+        "CCO" | "CC" => "circum",
+        "ICO" | "IC" => "in",
+        "OC" => "ortho",
+        "CT" => "centroid",
+        "9O" | "9C" => "euler",
+        _ => return None,
+    })
+}
+
+/// The tkz-euclide macros that build what the statements derive: for each
+/// point, the points it is built from and the macro that leaves it to be
+/// picked up by `\tkzGetPoint`; for each circle, the construction and the
+/// triangle it is built on.
+#[derive(Default)]
+struct Constructions {
+    points: HashMap<String, (Vec<String>, String)>,
+    circles: HashMap<String, (String, Vec<String>)>,
+}
+
+impl Constructions {
+    fn statement(&mut self, stmt: &Statement) {
+        match &stmt.kind {
+            StmtKind::Derived { code, args, target } => self.derived(code, args, target.as_deref()),
+            StmtKind::Point(PointDecl::Intersection { name, objects }) => {
+                let straight = |o: &Object| {
+                    o.kind != ObjectKind::Circle && o.kind != ObjectKind::Arc && o.points.len() == 2
+                };
+                if let [l, m] = &objects[..] {
+                    if straight(l) && straight(m) {
+                        let command = format!(
+                            "\\tkzInterLL({},{})({},{})",
+                            l.points[0], l.points[1], m.points[0], m.points[1]
+                        );
+                        let from = [l.points.clone(), m.points.clone()].concat();
+                        self.points.insert(name.clone(), (from, command));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn derived(&mut self, code: &str, args: &[Object], target: Option<&str>) {
+        let Some(first) = args.first() else {
+            return;
+        };
+        let points = &first.points;
+        let command = match (code, &points[..], args.get(1)) {
+            ("CC" | "IC" | "9C", [_, _, _], None) => {
+                let key = target.map_or_else(|| circle_key(points), str::to_string);
+                self.circles.insert(key, (code.to_string(), points.clone()));
+                return;
+            }
+            ("M", [a, b], None) => format!("\\tkzDefMidPoint({},{})", a, b),
+            ("PD", [p, a, b], None) => {
+                format!("\\tkzDefPointBy[projection=onto {}--{}]({})", a, b, p)
+            }
+            ("PD", [p], Some(line)) => match &line.points[..] {
+                [a, b] => format!("\\tkzDefPointBy[projection=onto {}--{}]({})", a, b, p),
+                _ => return,
+            },
+            (_, [a, b, c], None) => match center_option(code) {
+                Some(option) => {
+                    format!("\\tkzDefTriangleCenter[{}]({},{},{})", option, a, b, c)
+                }
+                None => return,
+            },
+            _ => return,
+        };
+        if let Some(target) = target {
+            let from = args.iter().flat_map(|a| a.points.clone()).collect();
+            self.points.insert(target.to_string(), (from, command));
+        }
+    }
+}
+
+struct Picture<'a> {
+    scene: &'a Scene,
+    out: String,
+    /// Helper points defined so far, for constructions that need a point
+    /// the problem does not name.
+    helpers: Vec<(Point, String)>,
+    /// Circles built on a triangle, by key: the construction and the triangle.
+    circles: HashMap<String, (String, Vec<String>)>,
+}
+
+impl Picture<'_> {
+    fn line(&mut self, command: String) {
+        writeln!(self.out, "  {}", command).unwrap();
+    }
+
+    /// The name of a point at `p`: a problem point or helper if one is
+    /// there, otherwise a newly defined helper.
+    fn name_at(&mut self, p: Point) -> String {
+        self.helper(p, |name| {
+            format!("\\tkzDefPoint({}){{{}}}", coords(p), name)
+        })
+    }
+
+    /// Like [`name_at`](Self::name_at), defining a new helper with the
+    /// command `define` gives for its name.
+    fn helper(&mut self, p: Point, define: impl FnOnce(&str) -> String) -> String {
+        let named = self.scene.points().map(|(name, q)| (q, name));
+        let helpers = self.helpers.iter().map(|(q, name)| (*q, name.as_str()));
+        if let Some((_, name)) = named
+            .chain(helpers)
+            .find(|(q, _)| q.dist(p) < geometry::EPSILON)
+        {
+            return name.to_string();
+        }
+        let name = format!("aux{}", self.helpers.len() + 1);
+        let command = define(&name);
+        self.line(command);
+        self.helpers.push((p, name.clone()));
+        name
+    }
+
+    /// Center and a point on a circle built on triangle `abc`, by the
+    /// construction `code` that built it.
+    fn derived_circle_names(&mut self, c: &Circle, code: &str, abc: &[String]) -> (String, String) {
+        let option = center_option(code).unwrap_or("circum");
+        let triangle = abc.join(",");
+        let center = self.helper(c.center, |name| {
+            format!(
+                "\\tkzDefTriangleCenter[{}]({}) \\tkzGetPoint{{{}}}",
+                option, triangle, name
+            )
+        });
+        let on = match (code, abc) {
+            // The incircle touches AB at the foot of the perpendicular from
+            // its center
+            ("IC", [a, b, _]) => {
+                let (pa, pb) = (self.scene.point(a), self.scene.point(b));
+                let foot = match (pa, pb) {
+                    (Some(pa), Some(pb)) => Line::new(pa, pb).foot(c.center),
+                    _ => c.at(0.0),
+                };
+                self.helper(foot, |name| {
+                    format!(
+                        "\\tkzDefPointBy[projection=onto {}--{}]({}) \\tkzGetPoint{{{}}}",
+                        a, b, center, name
+                    )
+                })
+            }
+            // The nine-point circle passes through the midpoint of BC
+            ("9C", [_, b, c_]) => {
+                let mid = match (self.scene.point(b), self.scene.point(c_)) {
+                    (Some(pb), Some(pc)) => pb.midpoint(pc),
+                    _ => c.at(0.0),
+                };
+                self.helper(mid, |name| {
+                    format!("\\tkzDefMidPoint({},{}) \\tkzGetPoint{{{}}}", b, c_, name)
+                })
+            }
+            (_, [a, ..]) => a.clone(),
+            _ => self.name_at(c.at(0.0)),
+        };
+        (center, on)
+    }
+
+    /// Center and a point on the circle, as tkz-euclide circle macros take them.
+    fn circle_names(&mut self, c: &Circle) -> (String, String) {
+        let center = self.name_at(c.center);
+        let on = self
+            .scene
+            .points()
+            .find(|&(_, p)| c.contains(p))
+            .map(|(name, _)| name.to_string());
+        let on = match on {
+            Some(name) => name,
+            None => self.name_at(c.at(0.0)),
+        };
+        (center, on)
+    }
+
+    fn figure(&mut self, figure: &Figure) {
+        match figure {
+            Figure::Segment(a, b) => self.line(format!("\\tkzDrawSegment({},{})", a, b)),
+            Figure::Line(a, b) => self.line(format!("\\tkzDrawLine({},{})", a, b)),
+            Figure::Ray(a, b) => self.line(format!("\\tkzDrawLine[add=0 and 2]({},{})", a, b)),
+            Figure::Polygon(names) => self.line(format!("\\tkzDrawPolygon({})", names.join(","))),
+            Figure::Circle(key) => {
+                if let Some(c) = self.scene.circle_by_key(key) {
+                    let (center, on) = match self.circles.get(key).cloned() {
+                        Some((code, abc)) => self.derived_circle_names(&c, &code, &abc),
+                        None => self.circle_names(&c),
+                    };
+                    self.line(format!("\\tkzDrawCircle({},{})", center, on));
+                }
+            }
+            Figure::DerivedLine { line, .. } => {
+                let (a, b) = (self.name_at(line.a), self.name_at(line.b));
+                self.line(format!("\\tkzDrawLine[dashed,gray]({},{})", a, b));
+            }
+        }
+    }
+
+    /// The named points of `obj`, if they have all been placed.
+    fn placed<'o>(&self, obj: &'o Object) -> Option<Vec<(&'o str, Point)>> {
+        obj.points
+            .iter()
+            .map(|n| self.scene.point(n).map(|p| (n.as_str(), p)))
+            .collect()
+    }
+
+    fn area(&mut self, obj: &Object) {
+        if obj.kind == ObjectKind::Circle {
+            if let Some(c) = self.scene.circle(obj) {
+                let (center, on) = self.circle_names(&c);
+                self.line(format!("\\tkzFillCircle[fill=blue!20]({},{})", center, on));
+            }
+            return;
+        }
+        if let Some(points) = self.placed(obj).filter(|p| p.len() >= 3) {
+            let names: Vec<&str> = points.iter().map(|(n, _)| *n).collect();
+            self.line(format!(
+                "\\tkzFillPolygon[fill=blue!20]({})",
+                names.join(",")
+            ));
+        }
+    }
+
+    /// tkz-euclide sweeps sectors and arcs counterclockwise, so take the
+    /// endpoints in whichever order gives the minor one.
+    fn sector(&mut self, obj: &Object) {
+        let Some(&[(o, po), (a, pa), (b, pb)]) = self.placed(obj).as_deref() else {
+            return;
+        };
+        let (a, b) = ccw(po, (a, pa), (b, pb));
+        self.line(format!(
+            "\\tkzFillSector[fill=orange!30]({},{})({})",
+            o, a, b
+        ));
+    }
+
+    fn arc(&mut self, obj: &Object) {
+        let (Some(c), Some(&[(a, pa), (b, pb)])) = (
+            self.scene.circle_through(&obj.points),
+            self.placed(obj).as_deref(),
+        ) else {
+            return;
+        };
+        let center = self.name_at(c.center);
+        let (a, b) = ccw(c.center, (a, pa), (b, pb));
+        self.line(format!("\\tkzDrawArc[red,thick]({},{})({})", center, a, b));
+    }
+
+    fn angle(&mut self, obj: &Object) {
+        let Some(&[(a, pa), (b, pb), (c, pc)]) = self.placed(obj).as_deref() else {
+            return;
+        };
+        if pa.dist(pb) < geometry::EPSILON || pc.dist(pb) < geometry::EPSILON {
+            return;
+        }
+        if (geometry::angle(pa, pb, pc) - 90.0).abs() < 1e-6 {
+            self.line(format!("\\tkzMarkRightAngle({},{},{})", a, b, c));
+        } else {
+            let (a, c) = ccw(pb, (a, pa), (c, pc));
+            self.line(format!("\\tkzMarkAngle[size=0.5]({},{},{})", a, b, c));
+        }
+    }
+
+    /// Each label goes on the side of its point away from the middle of the
+    /// figure.
+    fn labels(&mut self) {
+        let points: Vec<(String, Point)> = self
+            .scene
+            .points()
+            .map(|(n, p)| (n.to_string(), p))
+            .collect();
+        let all: Vec<Point> = points.iter().map(|&(_, p)| p).collect();
+        let center = geometry::centroid(&all);
+        for (name, p) in points {
+            self.line(format!(
+                "\\tkzLabelPoint[{}]({}){{${}$}}",
+                position(p - center),
+                name,
+                latex::point(&name)
+            ));
+        }
+    }
+}
+
+/// `a` and `b` ordered so that turning from `a` to `b` about `center` is
+/// counterclockwise.
+fn ccw<'o>(center: Point, a: (&'o str, Point), b: (&'o str, Point)) -> (&'o str, &'o str) {
+    if (a.1 - center).cross(b.1 - center) >= 0.0 {
+        (a.0, b.0)
+    } else {
+        (b.0, a.0)
+    }
+}
+
+/// The TikZ anchor word nearest to `dir`.
+fn position(dir: Point) -> &'static str {
+    if dir.norm() < geometry::EPSILON {
+        return "above right";
+    }
+    const WORDS: [&str; 8] = [
+        "right",
+        "above right",
+        "above",
+        "above left",
+        "left",
+        "below left",
+        "below",
+        "below right",
+    ];
+    let octant = (dir.y.atan2(dir.x).to_degrees() / 45.0).round() as i64;
+    WORDS[octant.rem_euclid(8) as usize]
+}
@@ -0,0 +1,1094 @@
+use crate::ast::*;
+use crate::checker;
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::eval::{format_number, Scene};
+use crate::lexer;
+use crate::macros::Scope;
+use crate::parser;
+use crate::proof;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// Translates GSL shorthand into English using the language's code tables.
+#[derive(Clone)]
+pub struct Translator {
+    pub(crate) properties: Table,
+    pub(crate) relationships: Table,
+    pub(crate) theorems: Table,
+    pub(crate) constants: Table,
+    pub(crate) derived_constructions: Table,
+}
+
+/// Codes and what they stand for, such as `_PY` and "the Pythagorean
+/// Theorem". The built-in entries can be added to by a problem's `\def`
+/// lines.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Table(HashMap<Cow<'static, str>, Cow<'static, str>>);
+
+impl Table {
+    pub(crate) fn insert(
+        &mut self,
+        code: impl Into<Cow<'static, str>>,
+        name: impl Into<Cow<'static, str>>,
+    ) {
+        self.0.insert(code.into(), name.into());
+    }
+
+    pub(crate) fn get(&self, code: &str) -> Option<&str> {
+        self.0.get(code).map(|name| name.as_ref())
+    }
+
+    pub(crate) fn contains_key(&self, code: &str) -> bool {
+        self.0.contains_key(code)
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(code, name)| (code.as_ref(), name.as_ref()))
+    }
+}
+
+impl std::ops::Index<&str> for Table {
+    type Output = str;
+
+    fn index(&self, code: &str) -> &str {
+        &self.0[code]
+    }
+}
+
+impl Default for Translator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Translator {
+    pub fn new() -> Self {
+        let mut properties = Table::default();
+        properties.insert("R", "regular");
+        properties.insert("CV", "convex");
+        properties.insert("CC", "concave");
+        properties.insert("RT", "right");
+        properties.insert("OB", "obtuse");
+        properties.insert("AC", "acute");
+        properties.insert("SC", "scalene");
+        properties.insert("IS", "isosceles");
+        properties.insert("TR", "trapezoid");
+        properties.insert("PL", "parallelogram");
+        properties.insert("EQ", "equilateral");
+        properties.insert("EA", "equiangular");
+        properties.insert("C", "cyclic");
+        properties.insert("TP", "tangential");
+
+        let mut relationships = Table::default();
+        relationships.insert("S", "collinear");
+        relationships.insert("P", "parallel");
+        relationships.insert("∥", "parallel");
+        relationships.insert("PR", "perpendicular");
+        relationships.insert("PD", "perpendicular");
+        relationships.insert("⊥", "perpendicular");
+        relationships.insert("TG", "tangent");
+        relationships.insert("CG", "congruent");
+        relationships.insert("≅", "congruent");
+        relationships.insert("SM", "similar");
+        relationships.insert("~", "similar");
+
+        let mut theorems = Table::default();
+        theorems.insert("_PY", "Pythagorean Theorem");
+        theorems.insert("_TI", "Triangle Inequality");
+        theorems.insert("_ST", "Stewart's Theorem");
+        theorems.insert("_AT", "Apollonius Theorem");
+        theorems.insert("_VT", "Viviani's Theorem");
+        theorems.insert("_NP", "Napoleon's Theorem");
+        theorems.insert("_EL", "Euler Line");
+        theorems.insert("_9C", "Nine-Point Circle");
+        theorems.insert("_SL", "Simson Line");
+        theorems.insert("_CV", "Ceva's Theorem");
+        theorems.insert("_ML", "Menelaus' Theorem");
+        theorems.insert("_AB", "Angle Bisector Theorem");
+        theorems.insert("_IE", "Incenter-Excenter Lemma");
+        theorems.insert("_CT", "Carnot's Theorem");
+        theorems.insert("_MQ", "Miquel's Theorem");
+        theorems.insert("_ET", "Euler's Theorem");
+        theorems.insert("_DT", "Desargue's Theorem");
+        theorems.insert("_HF", "Heron's Formula");
+        theorems.insert("_QF", "Bretschinder's Formula");
+        theorems.insert("_BF", "Brahmagupta's Formula");
+        theorems.insert("_JT", "Japanese Theorem");
+        theorems.insert("_NT", "Newton's Theorem");
+        theorems.insert("_PT", "Ptolemy's Theorem");
+        theorems.insert("_PP", "Power of a Point Theorem");
+        theorems.insert("_BT", "Butterfly Theorem");
+        theorems.insert("_PC", "Pascal's Theorem");
+        theorems.insert("_LC", "Law of Cosines");
+        theorems.insert("_LS", "Law of Sines");
+        theorems.insert("_LT", "Law of Tangents");
+        theorems.insert("_PK", "Pick's Theorem");
+        theorems.insert("_SH", "Shoelace Theorem");
+        theorems.insert("_SSC", "SSS Congruence");
+        theorems.insert("_SAC", "SAS Congruence");
+        theorems.insert("_SSA", "SSA Congruence");
+        theorems.insert("_ASA", "ASA Congruence");
+        theorems.insert("_AAS", "AAS Congruence");
+        theorems.insert("_HL", "HL Congruence");
+        theorems.insert("_AA", "AA Similarity");
+        theorems.insert("_SAS", "SAS Similarity");
+        theorems.insert("_SSS", "SSS Similarity");
+
+        let mut constants = Table::default();
+        constants.insert("\\T", "τ (tau)");
+        constants.insert("τ", "τ (tau)");
+        constants.insert("\\P", "π (pi)");
+        constants.insert("π", "π (pi)");
+        constants.insert("\\G", "φ (phi)");
+        constants.insert("φ", "φ (phi)");
+
+        let mut derived_constructions = Table::default();
+        derived_constructions.insert("PB", "perpendicular bisector");
+        derived_constructions.insert("CCO", "circumcenter");
+        derived_constructions.insert("CC", "circumcircle");
+        derived_constructions.insert("AB", "angle bisector");
+        derived_constructions.insert("ICO", "incenter");
+        derived_constructions.insert("IC", "incircle");
+        derived_constructions.insert("EAB", "exterior angle bisector");
+        derived_constructions.insert("ECO", "excenter");
+        derived_constructions.insert("EC", "excircle");
+        derived_constructions.insert("M", "midpoint");
+        derived_constructions.insert("MD", "median");
+        derived_constructions.insert("CT", "centroid");
+        derived_constructions.insert("PD", "perpendicular");
+        derived_constructions.insert("OC", "orthocenter");
+        derived_constructions.insert("9O", "nine-point center");
+        derived_constructions.insert("9C", "nine-point circle");
+        derived_constructions.insert("PL", "parallel line");
+        derived_constructions.insert("TG", "tangent line");
+
+        Translator {
+            properties,
+            relationships,
+            theorems,
+            constants,
+            derived_constructions,
+        }
+    }
+
+    /// English for a single parsed statement.
+    pub fn translate_statement(&self, stmt: &Statement) -> String {
+        let english = self.sentence(stmt);
+        match &stmt.theorem {
+            Some(code) => self.cite(&english, code, str::to_string),
+            None => english,
+        }
+    }
+
+    /// How a step cites the theorem with `code`: `the Pythagorean Theorem`,
+    /// but `Heron's Formula` without `the` when the name is possessive, and
+    /// the code itself when the theorem is unknown. `escape` prepares the
+    /// name for the output format.
+    pub(crate) fn theorem_phrase(&self, code: &str, escape: impl Fn(&str) -> String) -> String {
+        let Some(name) = self.theorems.get(code) else {
+            return escape(code);
+        };
+        let first = name.split_whitespace().next().unwrap_or_default();
+        if first.ends_with("'s") || first.ends_with("s'") {
+            escape(name)
+        } else {
+            format!("the {}", escape(name))
+        }
+    }
+
+    /// `sentence` justified by the theorem with `code`, as in `AC = 5 by the
+    /// Pythagorean Theorem`, keeping its full stop at the end.
+    pub(crate) fn cite(
+        &self,
+        sentence: &str,
+        code: &str,
+        escape: impl Fn(&str) -> String,
+    ) -> String {
+        let (body, stop) = match sentence.strip_suffix('.') {
+            Some(body) => (body, "."),
+            None => (sentence, ""),
+        };
+        format!("{} by {}{}", body, self.theorem_phrase(code, escape), stop)
+    }
+
+    fn sentence(&self, stmt: &Statement) -> String {
+        match &stmt.kind {
+            // Proof markers
+            StmtKind::Prove(inner) => {
+                format!("We will prove: {}", self.translate_statement(inner))
+            }
+            StmtKind::ProveByContradiction(inner) => format!(
+                "We will prove by contradiction: {}",
+                self.translate_statement(inner)
+            ),
+            StmtKind::Marker(ProofMarker::Qed) => "And that is what was to be shown.".to_string(),
+            StmtKind::Marker(ProofMarker::Contradiction) => {
+                "Achieving a contradiction.".to_string()
+            }
+            StmtKind::Marker(ProofMarker::Because) => "Because".to_string(),
+            StmtKind::Marker(ProofMarker::Therefore) => "Therefore".to_string(),
+
+            // Logic operators
+            StmtKind::Logic(op, lhs, rhs) => {
+                let word = match op {
+                    LogicOp::Or => "or",
+                    LogicOp::And => "and",
+                    LogicOp::Implies => "implies",
+                };
+                format!("{} {} {}", lhs, word, rhs)
+            }
+            StmtKind::Quantified(Quantifier::ForAll, body) => format!("For all {}", body),
+            StmtKind::Quantified(Quantifier::Exists, body) => format!("There exists {}", body),
+
+            // Casework
+            StmtKind::Casework { main, cases } => {
+                let main = main
+                    .as_ref()
+                    .map(|m| self.translate_statement(m))
+                    .unwrap_or_default();
+                match cases {
+                    Some(cases) => format!(
+                        "{}\nBegin casework: {}",
+                        main,
+                        self.translate_casework(cases)
+                    ),
+                    None => format!("{}\nBegin casework.", main),
+                }
+            }
+            StmtKind::EndCasework => "End casework.".to_string(),
+            StmtKind::Define(definition) => self.translate_definition(definition),
+            StmtKind::Include(path) => format!("Include {}.", path),
+
+            StmtKind::Derived { code, args, target } => {
+                self.handle_derived_construction(code, args, target.as_deref())
+            }
+            StmtKind::Graph(eq) => format!("Graph the function {}.", eq),
+            StmtKind::Point(decl) => self.handle_point_construction(decl),
+            StmtKind::Linear(kind, objects) => {
+                let (singular, plural, verb) = match kind {
+                    LinearKind::Segment => ("segment", "segments", "Connect"),
+                    LinearKind::Line => ("line", "lines", "Connect"),
+                    LinearKind::Ray => ("ray", "rays", "Construct"),
+                };
+                let noun = if objects.len() == 1 { singular } else { plural };
+                format!("{} {} {}.", verb, noun, join_display(objects, ", "))
+            }
+            StmtKind::Polygon {
+                objects,
+                properties,
+            } => {
+                let noun = if objects.len() == 1 {
+                    "polygon"
+                } else {
+                    "polygons"
+                };
+                let mut words: Vec<&str> =
+                    properties.iter().map(|p| self.property_name(p)).collect();
+                words.push(noun);
+                format!(
+                    "Construct {} {}.",
+                    words.join(" "),
+                    join_display(objects, ", ")
+                )
+            }
+            StmtKind::Circle(decl) => self.handle_circle_construction(decl),
+            StmtKind::RegularPolygon(poly) => {
+                format!("Construct {}.", self.translate_regular_polygon(poly))
+            }
+            StmtKind::Citation(code) => {
+                format!("By {}.", self.theorem_phrase(code, str::to_string))
+            }
+            StmtKind::Relation(lhs, op, rhs) => self.handle_relation(lhs, *op, rhs),
+            StmtKind::Query(expr) => self.handle_query(expr),
+            StmtKind::Value(expr) => self.describe_expr(expr),
+            StmtKind::ProveQuery(inner) => format!("Prove that {}.", inner),
+            StmtKind::Property {
+                object,
+                property,
+                question,
+            } => self.handle_property(object, property, *question),
+            StmtKind::Relationship {
+                objects,
+                relation,
+                question,
+                ..
+            } => {
+                let objs = join_display(objects, " and ");
+                let rel = self.relationship_name(relation);
+                if *question {
+                    format!("Are {} {}?", objs, rel)
+                } else {
+                    format!("{} are {}.", objs, rel)
+                }
+            }
+            StmtKind::Orientation { object, clockwise } => {
+                format!("{} goes {}.", object, orientation_word(*clockwise))
+            }
+        }
+    }
+
+    fn translate_definition(&self, definition: &Definition) -> String {
+        match definition {
+            Definition::Macro { name, params, body } => {
+                let body: Vec<String> = body.iter().map(|s| self.translate_statement(s)).collect();
+                format!(
+                    "Let {}:{} stand for: {}",
+                    name,
+                    params.concat(),
+                    body.join(" ")
+                )
+            }
+            Definition::Construction { code, name } => {
+                format!("Let the construction {} be the {}.", code, name)
+            }
+            Definition::Property { code, name } => {
+                format!("Let the property {} mean {}.", code, name)
+            }
+            Definition::Theorem { code, name } => format!("Let {} cite {}.", code, name),
+        }
+    }
+
+    pub(crate) fn property_name<'a>(&'a self, code: &'a str) -> &'a str {
+        self.properties.get(code).unwrap_or(code)
+    }
+
+    pub(crate) fn relationship_name<'a>(&'a self, code: &'a str) -> &'a str {
+        self.relationships
+            .get(code)
+            .or_else(|| self.properties.get(code))
+            .unwrap_or(code)
+    }
+
+    pub(crate) fn derived_name<'a>(&'a self, code: &'a str) -> &'a str {
+        self.derived_constructions.get(code).unwrap_or(code)
+    }
+
+    fn handle_derived_construction(
+        &self,
+        code: &str,
+        args: &[Object],
+        target: Option<&str>,
+    ) -> String {
+        let name = self.derived_name(code);
+        match target {
+            Some(target) => format!(
+                "Construct the {} {} of {}.",
+                name,
+                target,
+                join_display(args, ";")
+            ),
+            None => format!("Construct the {} of {}.", name, join_display(args, ";")),
+        }
+    }
+
+    fn handle_point_construction(&self, decl: &PointDecl) -> String {
+        match decl {
+            PointDecl::Free(names) if names.len() == 1 => format!("Construct point {}.", names[0]),
+            PointDecl::Free(names) => format!("Construct points {}.", names.join(", ")),
+            PointDecl::Intersection { name, objects } => {
+                let descs: Vec<String> = objects
+                    .iter()
+                    .map(|o| self.get_object_description(o))
+                    .collect();
+                let (last, init) = descs.split_last().expect("intersection of two objects");
+                format!(
+                    "Let point {} be the intersection of {} and {}.",
+                    name,
+                    init.join(", "),
+                    last
+                )
+            }
+            PointDecl::Coordinates { name, coords } => {
+                format!("Let point {} be at coordinates {}.", name, coords)
+            }
+            PointDecl::InRegion { name, region } => {
+                format!("Construct point {} in the region {}.", name, region)
+            }
+            PointDecl::OnObject {
+                name,
+                object,
+                conditions,
+            } => {
+                if conditions.is_empty() {
+                    return format!("Construct point {} on {}.", name, object);
+                }
+                let cond_strs = self.parse_conditions(conditions);
+                format!(
+                    "Construct point {} on {} such that {}.",
+                    name,
+                    object,
+                    cond_strs.join(", ")
+                )
+            }
+        }
+    }
+
+    fn get_object_description(&self, obj: &Object) -> String {
+        match obj.kind {
+            ObjectKind::Ray => format!("ray {}", obj.name()),
+            ObjectKind::Line => format!("line {}", obj.name()),
+            ObjectKind::Circle => format!("circle {}", obj.name()),
+            ObjectKind::Arc => format!("arc {}", obj.name()),
+            ObjectKind::Sector => format!("sector {}", obj.name()),
+            ObjectKind::Triangle => format!("triangle {}", obj.name()),
+            ObjectKind::Figure => format!("segment {}", obj.name()),
+        }
+    }
+
+    fn parse_conditions(&self, conditions: &[Statement]) -> Vec<String> {
+        conditions
+            .iter()
+            .map(|c| match &c.kind {
+                StmtKind::RegularPolygon(poly) => self.translate_regular_polygon(poly),
+                StmtKind::Relation(Expr::Area(obj), CmpOp::Eq, val) => {
+                    format!("the area of {} is {}", obj, self.describe_expr(val))
+                }
+                StmtKind::Orientation { object, clockwise } => {
+                    format!("{} goes {}", object, orientation_word(*clockwise))
+                }
+                StmtKind::Property {
+                    object, property, ..
+                } => format!("{} is {}", object, self.property_name(property)),
+                StmtKind::Polygon {
+                    objects,
+                    properties,
+                } if !properties.is_empty() => {
+                    let names: Vec<&str> =
+                        properties.iter().map(|p| self.property_name(p)).collect();
+                    format!(
+                        "polygon {} is {}",
+                        join_display(objects, ", "),
+                        names.join(" and ")
+                    )
+                }
+                StmtKind::Relationship {
+                    objects, relation, ..
+                } => format!(
+                    "{} are {}",
+                    join_display(objects, " and "),
+                    self.relationship_name(relation)
+                ),
+                _ => c.to_string(),
+            })
+            .collect()
+    }
+
+    fn handle_circle_construction(&self, decl: &CircleDecl) -> String {
+        match decl {
+            CircleDecl::ThroughPoints(points) => format!(
+                "Construct a circle through points {}, {}, and {}.",
+                points[0], points[1], points[2]
+            ),
+            CircleDecl::CenterRadius { center, radius } => format!(
+                "Construct a circle with center {} and radius {}.",
+                center,
+                self.describe_expr(radius)
+            ),
+            CircleDecl::CenterThrough { center, point } => format!(
+                "Construct a circle with center {} passing through point {}.",
+                center, point
+            ),
+            CircleDecl::Named(name) => format!("Construct circle {}.", name),
+            CircleDecl::Through(point) => {
+                format!("Construct a circle passing through point {}.", point)
+            }
+        }
+    }
+
+    fn describe_expr(&self, expr: &Expr) -> String {
+        match expr {
+            Expr::Constant(c) => self.constants.get(c.as_str()).unwrap_or(c).to_string(),
+            Expr::Binary(l, op, r) => {
+                format!("{}{}{}", self.describe_expr(l), op, self.describe_expr(r))
+            }
+            Expr::Product(l, r) => {
+                format!("{} times {}", self.describe_expr(l), self.describe_expr(r))
+            }
+            Expr::Angle(obj) if obj.kind != ObjectKind::Arc => format!("angle {}", obj),
+            other => other.to_string(),
+        }
+    }
+
+    fn handle_relation(&self, lhs: &Expr, op: CmpOp, rhs: &Expr) -> String {
+        let val = self.describe_expr(rhs);
+        // `<ABC=60` gives a measure; `<ABC=<DEF` compares two angles
+        if op == CmpOp::Eq && rhs.measure() == "value" {
+            match lhs {
+                Expr::Object(obj) if obj.kind == ObjectKind::Arc => {
+                    return self.handle_arc(obj, &val)
+                }
+                Expr::Object(obj) if obj.kind == ObjectKind::Sector => {
+                    return self.handle_sector(obj, Some(&val))
+                }
+                Expr::Area(obj) => return self.handle_area(obj, Some(&val)),
+                Expr::Perimeter(obj) => return self.handle_perimeter(obj, Some(&val)),
+                Expr::Angle(obj) => return self.handle_angle(obj, Some(&val)),
+                _ => {}
+            }
+        }
+        let phrase = match op {
+            CmpOp::Eq => "=",
+            CmpOp::NotEq => "does not equal",
+            CmpOp::Lt => "is less than",
+            CmpOp::Le => "is less than or equal to",
+            CmpOp::Gt => "is greater than",
+            CmpOp::Ge => "is greater than or equal to",
+        };
+        format!("{} {} {}", self.describe_expr(lhs), phrase, val)
+    }
+
+    fn handle_query(&self, expr: &Expr) -> String {
+        match expr {
+            Expr::Object(obj) if obj.kind == ObjectKind::Arc => {
+                format!("What is the length of arc {}?", obj.name())
+            }
+            Expr::Object(obj) if obj.kind == ObjectKind::Sector => self.handle_sector(obj, None),
+            Expr::Area(obj) => self.handle_area(obj, None),
+            Expr::Perimeter(obj) => self.handle_perimeter(obj, None),
+            Expr::Angle(obj) => self.handle_angle(obj, None),
+            other => format!("What is {}?", self.describe_expr(other)),
+        }
+    }
+
+    fn handle_arc(&self, arc: &Object, val: &str) -> String {
+        format!("Arc {} has length {}.", arc.name(), val)
+    }
+
+    fn handle_sector(&self, sector: &Object, val: Option<&str>) -> String {
+        match val {
+            Some(val) => format!("The area of sector {} is {}.", sector.name(), val),
+            None => format!("What is the area of sector {}?", sector.name()),
+        }
+    }
+
+    fn handle_area(&self, obj: &Object, val: Option<&str>) -> String {
+        match val {
+            Some(val) => format!("Let the area of {} be {}.", obj, val),
+            None => format!("What is the area of {}?", obj),
+        }
+    }
+
+    fn handle_perimeter(&self, obj: &Object, val: Option<&str>) -> String {
+        match val {
+            Some(val) => format!("Let the perimeter of {} be {}.", obj, val),
+            None => format!("What is the perimeter of {}?", obj),
+        }
+    }
+
+    fn handle_angle(&self, obj: &Object, val: Option<&str>) -> String {
+        match (obj.kind, val) {
+            (ObjectKind::Arc, Some(val)) => {
+                format!("Arc {} has measure {} degrees.", obj.name(), val)
+            }
+            (ObjectKind::Arc, None) => format!("What is the measure of arc {}?", obj.name()),
+            (_, Some(val)) => format!("Angle {} measures {} degrees.", obj, val),
+            (_, None) => format!("What is the measure of angle {}?", obj),
+        }
+    }
+
+    fn handle_property(&self, object: &Object, property: &str, question: bool) -> String {
+        let prop_name = self.property_name(property);
+        if question {
+            format!("Is {} {}?", object, prop_name)
+        } else {
+            format!("{} is {}.", object, prop_name)
+        }
+    }
+
+    fn translate_casework(&self, cases: &[Case]) -> String {
+        cases
+            .iter()
+            .enumerate()
+            .map(|(i, case)| {
+                format!(
+                    "Case {}: if {}, then {}",
+                    i + 1,
+                    case.condition,
+                    case.result
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    fn translate_regular_polygon(&self, poly: &RegularPolygon) -> String {
+        format!(
+            "{} {} with side {}",
+            shape_name(&poly.sides),
+            poly.polygon,
+            poly.side
+        )
+    }
+
+    /// Report codes that are not in the translator's tables.
+    pub fn check_codes(&self, stmt: &Statement, diagnostics: &mut Vec<Diagnostic>) {
+        if let Some(code) = &stmt.theorem {
+            if !self.theorems.contains_key(code.as_str()) {
+                diagnostics.push(
+                    Diagnostic::error(format!("unknown theorem `{}`", code), stmt.theorem_span)
+                        .with_hint(unknown_code_hint(code, &self.theorems)),
+                );
+            }
+        }
+        stmt.walk(&mut |s| {
+            let (code, hint) = match &s.kind {
+                StmtKind::Derived { code, .. }
+                    if !self.derived_constructions.contains_key(code.as_str()) =>
+                {
+                    (
+                        format!("unknown derived construction `{}:`", code),
+                        unknown_code_hint(code, &self.derived_constructions),
+                    )
+                }
+                StmtKind::Citation(code) if !self.theorems.contains_key(code.as_str()) => (
+                    format!("unknown theorem `{}`", code),
+                    unknown_code_hint(code, &self.theorems),
+                ),
+                StmtKind::Property { property, .. } if !self.is_known_property(property) => (
+                    format!("unknown property `{}`", property),
+                    unknown_code_hint(property, &self.properties),
+                ),
+                StmtKind::Polygon { properties, .. } => {
+                    match properties.iter().find(|p| !self.is_known_property(p)) {
+                        Some(property) => (
+                            format!("unknown property `{}`", property),
+                            unknown_code_hint(property, &self.properties),
+                        ),
+                        None => return,
+                    }
+                }
+                StmtKind::Relationship { relation, .. } if !self.is_known_property(relation) => (
+                    format!("unknown relationship `{}`", relation),
+                    unknown_code_hint(relation, &self.relationships),
+                ),
+                _ => return,
+            };
+            diagnostics.push(Diagnostic::error(code, s.span).with_hint(hint));
+        });
+    }
+
+    fn is_known_property(&self, code: &str) -> bool {
+        self.properties.contains_key(code) || self.relationships.contains_key(code)
+    }
+
+    /// Parse, check and translate a problem. Statements that fail to parse
+    /// are left out of the steps and reported in the diagnostics instead.
+    pub fn translate(&self, input: &str) -> Translation {
+        self.translate_parsed(input, parser::parse(input), &mut Scope::default(), true)
+    }
+
+    /// Like [`translate`](Self::translate), for a problem that is still
+    /// being written, as in the REPL: proofs it leaves open are not errors
+    /// yet, since their `\q` may be on its way.
+    pub fn translate_draft(&self, input: &str) -> Translation {
+        self.translate_parsed(input, parser::parse(input), &mut Scope::default(), false)
+    }
+
+    /// Translate each `\\...\\` problem in a file on its own, so points
+    /// and answers in one do not carry over to the next. Spans in the
+    /// diagnostics are relative to the whole file.
+    pub fn translate_problems(&self, input: &str) -> Vec<Problem> {
+        let mut problems = Vec::new();
+        // Definitions carry over from one problem to the next.
+        let mut scope = Scope::default();
+        // Where each id was given, to report duplicates.
+        let mut ids: Vec<(String, lexer::Span)> = Vec::new();
+        for (i, parsed) in parser::parse_problems(input).into_iter().enumerate() {
+            let mut translation = self.translate_parsed(input, parsed.statements, &mut scope, true);
+            let metadata = Metadata::from_fields(&parsed.fields, &mut translation.diagnostics);
+            let heading = parsed.fields.iter().find(|f| f.key == "problem");
+            if let (Some(id), Some(field)) = (&metadata.id, heading) {
+                match ids.iter().find(|(seen, _)| seen == id) {
+                    Some((_, earlier)) => translation.diagnostics.push(
+                        Diagnostic::error(format!("problem id `{}` is used twice", id), field.span)
+                            .with_note("first used here", *earlier),
+                    ),
+                    None => ids.push((id.clone(), field.span)),
+                }
+            }
+            if let Some(field) = parsed
+                .fields
+                .first()
+                .filter(|_| translation.steps.is_empty())
+            {
+                translation.diagnostics.push(Diagnostic::warning(
+                    "this problem has no statements",
+                    field.span,
+                ));
+            }
+            if let Some(expected) = &metadata.answer {
+                check_answer(expected, &parsed.fields, &mut translation);
+            }
+            translation.diagnostics.sort_by_key(|d| d.span.start);
+            problems.push(Problem {
+                number: i + 1,
+                metadata,
+                translation,
+            });
+        }
+        problems
+    }
+
+    fn translate_parsed(
+        &self,
+        input: &str,
+        parsed: Vec<Result<Statement, parser::ParseError>>,
+        scope: &mut Scope,
+        complete: bool,
+    ) -> Translation {
+        let mut translation = Translation {
+            steps: Vec::new(),
+            diagnostics: Vec::new(),
+            definitions: Vec::new(),
+        };
+        let mut statements = Vec::new();
+        for parsed in parsed {
+            match parsed {
+                Ok(stmt) => statements.push(stmt),
+                Err(err) => translation.diagnostics.push(Diagnostic::from(&err)),
+            }
+        }
+        let statements = scope.expand(self, statements, &mut translation.diagnostics);
+        translation.definitions = scope.definitions();
+        let translator = self.with_definitions(&translation.definitions);
+        for stmt in statements {
+            translator.check_codes(&stmt, &mut translation.diagnostics);
+            scope.check_order(&stmt, &mut translation.diagnostics);
+            translation.steps.push(Step {
+                source: input[stmt.span.start..stmt.span.end].to_string(),
+                english: translator.translate_statement(&stmt),
+                answer: None,
+                depth: 0,
+                closes: None,
+                statement: stmt,
+            });
+        }
+        proof::structure(
+            &translator,
+            &mut translation.steps,
+            complete,
+            &mut translation.diagnostics,
+        );
+        let statements: Vec<Statement> = translation
+            .steps
+            .iter()
+            .map(|step| step.statement.clone())
+            .collect();
+        translation.diagnostics.extend(checker::check(&statements));
+
+        let scene = Scene::evaluate(&statements);
+        for step in &mut translation.steps {
+            if let StmtKind::Query(expr) = &step.statement.kind {
+                // Coordinates too large to measure overflow to infinity
+                step.answer = scene.answer(expr).filter(|v| v.is_finite());
+            }
+        }
+        translation
+            .diagnostics
+            .extend(scene.diagnostics().iter().cloned());
+        scope.annotate(&mut translation.diagnostics);
+        translation.diagnostics.sort_by_key(|d| d.span.start);
+        translation
+    }
+}
+
+/// One translated statement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    /// The shorthand exactly as written.
+    pub source: String,
+    pub statement: Statement,
+    pub english: String,
+    /// The numeric answer to a query, when the problem's coordinates determine it.
+    pub answer: Option<f64>,
+    /// How many proofs the step is inside. `\p:` and the `\q` that closes
+    /// it are at the level around the proof, and its steps one deeper.
+    pub depth: usize,
+    /// For `\q` or `↯`, the index of the step whose goal it closes.
+    pub closes: Option<usize>,
+}
+
+impl Step {
+    /// The answer with its unit, e.g. `6` or `90°`.
+    pub fn answer_text(&self) -> Option<String> {
+        let StmtKind::Query(expr) = &self.statement.kind else {
+            return None;
+        };
+        let value = format_number(self.answer?);
+        Some(match expr.measure() {
+            "angle" => format!("{}°", value),
+            _ => value,
+        })
+    }
+}
+
+/// Warn when a problem's `@answer` is a number that its last answered query
+/// contradicts.
+fn check_answer(expected: &str, fields: &[parser::Field], translation: &mut Translation) {
+    let Ok(value) = expected.trim_end_matches('°').trim().parse::<f64>() else {
+        return;
+    };
+    let Some(step) = translation.steps.iter().rev().find(|s| s.answer.is_some()) else {
+        return;
+    };
+    let actual = step.answer.unwrap_or_default();
+    if (actual - value).abs() <= 1e-6 * value.abs().max(1.0) {
+        return;
+    }
+    let field = fields.iter().find(|f| f.key == "answer").unwrap();
+    translation.diagnostics.push(
+        Diagnostic::warning(
+            format!(
+                "the stated answer is {}, but the figure gives {}",
+                expected,
+                step.answer_text().unwrap_or_default()
+            ),
+            field.span,
+        )
+        .with_note(
+            "the figure's answer comes from this query",
+            step.statement.span,
+        ),
+    );
+}
+
+/// The keys a problem's metadata lines may use.
+pub const METADATA_KEYS: &[&str] = &["problem", "source", "difficulty", "tags", "answer"];
+
+/// What a file says about one of its problems in `@key: value` lines.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Metadata {
+    /// From `@problem id: title`.
+    pub id: Option<String>,
+    pub title: Option<String>,
+    pub source: Option<String>,
+    pub difficulty: Option<String>,
+    /// From `@tags: a, b`.
+    pub tags: Vec<String>,
+    /// The expected answer, as written.
+    pub answer: Option<String>,
+}
+
+impl Metadata {
+    fn from_fields(fields: &[parser::Field], diagnostics: &mut Vec<Diagnostic>) -> Metadata {
+        let mut metadata = Metadata::default();
+        let text = |value: &str| Some(value.to_string()).filter(|v| !v.is_empty());
+        for field in fields {
+            let value = field.value.as_str();
+            match field.key.as_str() {
+                "problem" => {
+                    let (id, title) = value.split_once(':').unwrap_or((value, ""));
+                    metadata.id = text(id.trim());
+                    metadata.title = text(title.trim());
+                }
+                "source" => metadata.source = text(value),
+                "difficulty" => metadata.difficulty = text(value),
+                "tags" => {
+                    metadata.tags = value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|t| !t.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                }
+                "answer" => metadata.answer = text(value),
+                key => diagnostics.push(
+                    Diagnostic::warning(format!("unknown metadata key `@{}`", key), field.span)
+                        .with_hint(format!(
+                            "expected one of {}",
+                            METADATA_KEYS
+                                .iter()
+                                .map(|k| format!("`@{}`", k))
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )),
+                ),
+            }
+        }
+        metadata
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Metadata::default()
+    }
+
+    /// Labelled details for a problem's header, such as `("Source", "AMC 8")`.
+    /// The id and title are left to [`Problem::heading`].
+    pub fn details(&self) -> Vec<(&'static str, String)> {
+        let mut details = Vec::new();
+        if let Some(source) = &self.source {
+            details.push(("Source", source.clone()));
+        }
+        if let Some(difficulty) = &self.difficulty {
+            details.push(("Difficulty", difficulty.clone()));
+        }
+        if !self.tags.is_empty() {
+            details.push(("Tags", self.tags.join(", ")));
+        }
+        if let Some(answer) = &self.answer {
+            details.push(("Answer", answer.clone()));
+        }
+        details
+    }
+}
+
+/// One problem of a file, translated on its own.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    /// Position in the file, from 1.
+    pub number: usize,
+    pub metadata: Metadata,
+    pub translation: Translation,
+}
+
+impl Problem {
+    /// The id from `@problem`, or else the problem's number.
+    pub fn id(&self) -> String {
+        self.metadata
+            .id
+            .clone()
+            .unwrap_or_else(|| self.number.to_string())
+    }
+
+    /// `Problem 2` or `Problem 2: Title`.
+    pub fn heading(&self) -> String {
+        match &self.metadata.title {
+            Some(title) => format!("Problem {}: {}", self.number, title),
+            None => format!("Problem {}", self.number),
+        }
+    }
+}
+
+/// English for each statement, plus any problems found along the way.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Translation {
+    pub steps: Vec<Step>,
+    pub diagnostics: Vec<Diagnostic>,
+    /// Codes defined with `\def` for the translator's tables, which the
+    /// other renderers need to word the steps.
+    pub definitions: Vec<Definition>,
+}
+
+impl Translation {
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(Diagnostic::is_error)
+    }
+
+    /// The English sentences in order.
+    pub fn english(&self) -> Vec<&str> {
+        self.steps
+            .iter()
+            .map(|step| step.english.as_str())
+            .collect()
+    }
+
+    /// Treat every warning as an error, as `--strict` does.
+    pub fn deny_warnings(&mut self) {
+        for diagnostic in &mut self.diagnostics {
+            diagnostic.severity = Severity::Error;
+        }
+    }
+}
+
+/// The hint for a code missing from `table`: the nearest code in it, when
+/// one is close enough to be a typo, or else every code it has.
+pub(crate) fn unknown_code_hint(code: &str, table: &Table) -> String {
+    match nearest_code(code, table) {
+        Some(nearest) => format!("did you mean `{}`?", nearest),
+        None => format!("expected one of {}", known_codes(table)),
+    }
+}
+
+/// The code in `table` fewest edits away from `code`, if that is at most two
+/// edits and fewer than the characters of `code` after its `_`. Ties go to
+/// the code with the most characters in common, so `_YP` suggests `_PY`,
+/// and then to the first alphabetically.
+pub(crate) fn nearest_code<'a>(code: &str, table: &'a Table) -> Option<&'a str> {
+    let mut codes: Vec<&str> = table.iter().map(|(code, _)| code).collect();
+    codes.sort_unstable();
+    let length = code.trim_start_matches('_').chars().count();
+    let common = |known: &str| {
+        let mut rest: Vec<char> = known.chars().collect();
+        code.chars()
+            .filter(|c| match rest.iter().position(|k| k == c) {
+                Some(i) => {
+                    rest.remove(i);
+                    true
+                }
+                None => false,
+            })
+            .count()
+    };
+    codes
+        .into_iter()
+        .map(|known| (edit_distance(code, known), known))
+        .filter(|&(distance, _)| distance <= 2 && distance < length)
+        .min_by_key(|&(distance, known)| (distance, std::cmp::Reverse(common(known))))
+        .map(|(_, known)| known)
+}
+
+/// The number of insertions, deletions, substitutions and swaps of adjacent
+/// characters that turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // distances[i][j] is the distance between a[..i] and b[..j]
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    distances[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = best;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+fn known_codes(table: &Table) -> String {
+    let mut codes: Vec<&str> = table.iter().map(|(code, _)| code).collect();
+    codes.sort_unstable();
+    codes
+        .iter()
+        .map(|c| format!("`{}`", c))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The usual name of a regular polygon with `sides` sides.
+pub(crate) fn shape_name(sides: &str) -> String {
+    match sides {
+        "3" => "equilateral triangle".to_string(),
+        "4" => "square".to_string(),
+        "5" => "regular pentagon".to_string(),
+        "6" => "regular hexagon".to_string(),
+        "8" => "regular octagon".to_string(),
+        n => format!("regular {}-gon", n),
+    }
+}
+
+pub(crate) fn orientation_word(clockwise: bool) -> &'static str {
+    if clockwise {
+        "clockwise"
+    } else {
+        "counterclockwise"
+    }
+}
+
+fn join_display<T: std::fmt::Display>(items: &[T], sep: &str) -> String {
+    items
+        .iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(sep)
+}
@@ -0,0 +1,274 @@
+//! Spoken GSL back to shorthand: reads the words [`Translator::pronounce`]
+//! writes, as typed or dictated, and rebuilds the problem they spell.
+//!
+//! Every word stands for one piece of shorthand. Syllables, commands and
+//! constants are looked up; a letter, or letters and digits joined with `-`,
+//! is a point or a code and comes back in capitals, so `a` is point A while
+//! `arc` is the prefix `a`, and `c-c-o` is `CCO`. `var r` is the variable
+//! `r`, and digits and symbols without a syllable are kept as they are.
+//!
+//! Pronunciation leaves out the closing `]` and `)`, so they are put back:
+//! `]` after the shape `area` names, and `)` after the shape `pairim` names
+//! or, for a case in `<<...>>`, before the `,` or `>>` that ends it.
+//!
+//! Step-numbered text from `--pron -s` is read too, one statement per line.
+//! It has no `uh`, so the statements are wrapped in one problem.
+
+use crate::diagnostic::Diagnostic;
+use crate::lexer::Span;
+use crate::pronounce::{constant_word, COMMANDS, SYLLABLES, VARIABLE};
+use crate::Translator;
+
+/// Characters that always have a syllable, so a word holding one was not
+/// written by `pronounce`.
+const SPOKEN: &str = "\\/:;,.?=|*_!△∥⊥∠~≅[]()";
+
+/// Shorthand recovered from a pronunciation, plus the words that could not
+/// be read.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dictation {
+    /// The shorthand, with every statement inside a `\\...\\` problem.
+    pub gsl: String,
+    /// Spans point into the spoken text, so rendering them gives line numbers.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Dictation {
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(Diagnostic::is_error)
+    }
+}
+
+impl Translator {
+    /// Turn a pronunciation, on one line or numbered by statement as
+    /// `gslc --pron` prints it, back into GSL.
+    pub fn unpronounce(&self, spoken: &str) -> Dictation {
+        let mut diagnostics = Vec::new();
+        let mut writer = Writer::default();
+        let mut offset = 0;
+        let mut first = true;
+        for line in spoken.split_inclusive('\n') {
+            let body = step_body(line);
+            let start = offset + line.len() - body.len();
+            offset += line.len();
+            let mut words = words(body).into_iter();
+            let mut next = words.next();
+            if next.is_some() && !std::mem::take(&mut first) {
+                writer.push_str("/");
+            }
+            while let Some((at, word)) = next {
+                next = words.next();
+                let span = |end: usize| Span::new(start + at, start + end);
+                let lower = word.to_lowercase();
+                if lower == "uh" {
+                    writer.wrap();
+                } else if let Some(gsl) = self.unpronounce_word(&lower) {
+                    writer.push_str(&gsl);
+                } else if lower == VARIABLE {
+                    match next {
+                        Some((_, letter)) if is_letter(letter) => {
+                            writer.push_str(&letter.to_lowercase());
+                            next = words.next();
+                        }
+                        _ => diagnostics.push(Diagnostic::error(
+                            format!("`{}` must be followed by a letter", word),
+                            span(at + word.len()),
+                        )),
+                    }
+                } else if let Some(close) = verbatim_close(word) {
+                    // Read as written up to the closing delimiter
+                    let mut text = word.to_string();
+                    let mut end = at + word.len();
+                    while !(text.len() > 1 && text.ends_with(close)) {
+                        let Some((at, word)) = next else { break };
+                        text.push(' ');
+                        text.push_str(word);
+                        end = at + word.len();
+                        next = words.next();
+                    }
+                    if text.len() > 1 && text.ends_with(close) {
+                        writer.push_str(&text);
+                    } else {
+                        diagnostics.push(Diagnostic::error(
+                            format!("unclosed `{}`", &text[..1]),
+                            span(end),
+                        ));
+                    }
+                } else {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            format!("`{}` is not a spoken GSL word", word),
+                            span(at + word.len()),
+                        )
+                        .with_hint(
+                            "words are syllables such as `kuh` for `:` and `mn` for `/`, \
+                             letters naming points, or codes spelled like `c-c-o`",
+                        ),
+                    );
+                }
+            }
+        }
+        writer.finish();
+        let gsl = if writer.wrapped {
+            writer.out
+        } else {
+            format!("\\\\{}\\\\", writer.out)
+        };
+        Dictation { gsl, diagnostics }
+    }
+
+    /// The shorthand for a word that stands alone: a syllable, command,
+    /// constant, name or something said as written.
+    fn unpronounce_word(&self, word: &str) -> Option<String> {
+        let mut table = SYLLABLES
+            .iter()
+            .map(|&(spoken, gsl, _)| (spoken, gsl))
+            .chain(COMMANDS.iter().copied());
+        if let Some((_, gsl)) = table.find(|(spoken, _)| *spoken == word) {
+            return Some(gsl.to_string());
+        }
+        if let Some((code, _)) = self
+            .constants
+            .iter()
+            .find(|(code, _)| code.starts_with('\\') && constant_word(self, code) == Some(word))
+        {
+            return Some(code.to_string());
+        }
+        let parts: Vec<&str> = word.split('-').collect();
+        let is_name = word.contains(|c: char| c.is_ascii_lowercase())
+            && parts.iter().all(|part| {
+                part.chars().count() == 1
+                    && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '\'')
+            });
+        if is_name {
+            return Some(parts.concat().to_uppercase());
+        }
+        let is_raw = !word.contains(|c: char| c.is_ascii_alphabetic() || SPOKEN.contains(c))
+            && verbatim_close(word).is_none();
+        is_raw.then(|| word.to_string())
+    }
+}
+
+fn is_letter(word: &str) -> bool {
+    word.chars().count() == 1 && word.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+/// The delimiter ending text read as written, for a word opening a file
+/// name (`"setup.gsl"`) or the name of a defined code (`{Nagel point}`).
+/// A `{` on its own opens coordinates or a macro body instead.
+fn verbatim_close(word: &str) -> Option<char> {
+    match word.chars().next()? {
+        '"' => Some('"'),
+        '{' if word.len() > 1 => Some('}'),
+        _ => None,
+    }
+}
+
+/// `line` without the `3. ` that `--pron -s` puts before a step.
+fn step_body(line: &str) -> &str {
+    let trimmed = line.trim_start();
+    let digits = trimmed.len()
+        - trimmed
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .len();
+    match trimmed[digits..].strip_prefix(". ") {
+        Some(rest) if digits > 0 => rest,
+        _ => line,
+    }
+}
+
+/// Each whitespace-separated word of `text` with its byte offset.
+fn words(text: &str) -> Vec<(usize, &str)> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => {
+                words.push((s, &text[s..i]));
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        words.push((s, &text[s..]));
+    }
+    words
+}
+
+/// Builds the shorthand, putting back the closing brackets that are not
+/// pronounced.
+#[derive(Default)]
+struct Writer {
+    out: String,
+    /// Whether a `\\` is open.
+    inside: bool,
+    /// Whether any `\\` was written.
+    wrapped: bool,
+    /// The `]` or `)` to write once the shape after `[` or `(` ends.
+    closer: Option<char>,
+    braces: usize,
+    /// The `{}` depth at each open case in `<<...>>`.
+    cases: Vec<usize>,
+    casework: usize,
+}
+
+impl Writer {
+    fn push(&mut self, c: char) {
+        if let Some(closer) = self.closer {
+            if !(c.is_ascii_uppercase() || c.is_ascii_digit() || c == '△' || c == '\'') {
+                self.out.push(closer);
+                self.closer = None;
+            }
+        }
+        if matches!(c, ',' | '>' | '/') && self.cases.last() == Some(&self.braces) {
+            self.out.push(')');
+            self.cases.pop();
+        }
+        match c {
+            '{' => self.braces += 1,
+            '}' => self.braces = self.braces.saturating_sub(1),
+            _ => {}
+        }
+        // A definition's name is not part of the command, as in `\def RT:ABC`
+        if c.is_ascii_uppercase() && self.out.ends_with("\\def") {
+            self.out.push(' ');
+        }
+        let case_opens = c == '('
+            && (self.out.ends_with("<<") || (self.casework > 0 && self.out.ends_with(',')));
+        self.out.push(c);
+        match c {
+            '[' => self.closer = Some(']'),
+            '(' if case_opens => self.cases.push(self.braces),
+            '(' => self.closer = Some(')'),
+            '<' if self.out.ends_with("<<") => self.casework += 1,
+            '>' if self.out.ends_with(">>") => self.casework = self.casework.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    fn push_str(&mut self, text: &str) {
+        text.chars().for_each(|c| self.push(c));
+    }
+
+    /// Write a `\\`, opening or closing a problem.
+    fn wrap(&mut self) {
+        if self.inside {
+            self.finish();
+        }
+        self.out.push_str("\\\\");
+        self.inside = !self.inside;
+        self.wrapped = true;
+    }
+
+    /// Close whatever brackets are still open.
+    fn finish(&mut self) {
+        if let Some(closer) = self.closer.take() {
+            self.out.push(closer);
+        }
+        for _ in self.cases.drain(..) {
+            self.out.push(')');
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use gslc::ast::{CmpOp, Expr, Object, ObjectKind, StmtKind};
+use gslc::diagnostic::{Severity, SourceFile};
+use gslc::{check, parse, Translator};
+
+#[test]
+fn translates_each_statement() {
+    let translation = Translator::new().translate("\\\\P:A,B,C/J:ABC/AB=BC/[ABC]?\\\\");
+    assert!(translation.diagnostics.is_empty());
+    assert_eq!(
+        translation.english(),
+        [
+            "Construct points A, B, C.",
+            "Construct polygon ABC.",
+            "AB = BC",
+            "What is the area of ABC?",
+        ]
+    );
+    let sources: Vec<&str> = translation
+        .steps
+        .iter()
+        .map(|s| s.source.as_str())
+        .collect();
+    assert_eq!(sources, ["P:A,B,C", "J:ABC", "AB=BC", "[ABC]?"]);
+}
+
+#[test]
+fn steps_carry_the_parsed_statement() {
+    let translation = Translator::new().translate("\\\\P:A,B/S:AB/AB=5\\\\");
+    let StmtKind::Relation(lhs, op, rhs) = &translation.steps[2].statement.kind else {
+        panic!("expected a relation");
+    };
+    assert_eq!(
+        *lhs,
+        Expr::Object(Object {
+            kind: ObjectKind::Figure,
+            points: vec!["A".into(), "B".into()],
+        })
+    );
+    assert_eq!(*op, CmpOp::Eq);
+    assert_eq!(*rhs, Expr::Number("5".into()));
+}
+
+#[test]
+fn parse_errors_become_diagnostics() {
+    let input = "\\\\P:A,B/S:AB/AB=\\\\";
+    let translation = Translator::new().translate(input);
+    assert!(translation.has_errors());
+    assert_eq!(translation.steps.len(), 2);
+
+    let rendered = SourceFile::new("test.gsl", input).render(&translation.diagnostics[0]);
+    assert!(rendered.starts_with("error: "));
+    assert!(rendered.contains("--> test.gsl:1:"));
+    assert!(rendered.contains('^'));
+}
+
+#[test]
+fn parse_reports_each_statement() {
+    let results = parse("\\\\P:A/S:/T:ABC\\\\");
+    assert_eq!(results.len(), 3);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+    assert!(results[2].is_ok());
+}
+
+#[test]
+fn checker_warns_about_unconstructed_points() {
+    let statements: Vec<_> = parse("\\\\P:A,B/S:AC\\\\")
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let diagnostics = check(&statements);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+    assert!(diagnostics[0].message.contains("`C`"));
+}
+
+#[test]
+fn deny_warnings_promotes_checker_findings() {
+    let mut translation = Translator::new().translate("\\\\P:A,B/S:AC\\\\");
+    assert!(!translation.has_errors());
+    assert_eq!(translation.steps.len(), 2);
+    translation.deny_warnings();
+    assert!(translation.has_errors());
+}
+
+#[test]
+fn unknown_codes_are_errors() {
+    let translation = Translator::new().translate("\\\\P:A,B,C/XYZ:ABC\\\\");
+    assert!(translation.has_errors());
+    assert!(translation.diagnostics[0].hint.is_some());
+}
+
+#[test]
+fn pronounces_shorthand() {
+    let translator = Translator::new();
+    let spoken = translator.pronounce("P:A,B", false);
+    assert_eq!(spoken, "p kuh a muh b");
+    assert_ne!(spoken, translator.pronounce("P:A,B", true));
+}
+
+#[test]
+fn json_describes_each_step() {
+    let translation = Translator::new().translate("\\\\P:A,B,C/<ABC=90/[ABC]?\\\\");
+    let out = gslc::json::translation(&translation).to_string();
+    assert!(out.starts_with('['));
+    assert!(out.contains("\"kind\": \"point_construction\""));
+    assert!(out.contains("\"kind\": \"angle_assertion\""));
+    assert!(out.contains("\"kind\": \"area_query\""));
+    assert!(out.contains("\"value\": 90"));
+    assert!(out.contains("\"english\": \"What is the area of ABC?\""));
+}
+
+#[test]
+fn json_escapes_strings() {
+    let value = gslc::json::Json::from("\\p:\"x\"\n");
+    assert_eq!(value.to_string(), r#""\\p:\"x\"\n""#);
+}
+
+#[test]
+fn json_parses_what_it_prints() {
+    use gslc::json::Json;
+    let translation = Translator::new().translate("\\\\P:A{0,0}/P:B{3,4}/AB?\\\\");
+    let printed = gslc::json::translation(&translation);
+    assert_eq!(Json::parse(&printed.to_string()), Ok(printed));
+
+    let parsed =
+        Json::parse(r#"{"a": [1, -2.5e3, true, null], "b": "\u00e9\ud83d\ude00"}"#).unwrap();
+    assert_eq!(parsed.get("b").and_then(Json::as_str), Some("é😀"));
+    assert!(Json::parse("[1, 2").is_err());
+    assert!(Json::parse("{} x").is_err());
+}
+
+#[test]
+fn json_numbers_are_valid_json() {
+    use gslc::json::Json;
+    let translator = Translator::new();
+    for (input, number) in [
+        ("\\\\P:A{.5,5.}\\\\", "0.5"),
+        ("\\\\P:A,B/AB=007\\\\", "7"),
+        ("\\\\P:A{-0.25,0}\\\\", "-0.25"),
+    ] {
+        let printed = gslc::json::translation(&translator.translate(input)).to_string();
+        assert!(Json::parse(&printed).is_ok(), "{}", printed);
+        assert!(printed.contains(number), "{}", printed);
+    }
+}
+
+#[test]
+fn answers_that_overflow_are_left_out() {
+    use gslc::json::Json;
+    let translator = Translator::new();
+    let translation = translator.translate("\\\\P:A{1e308,0}/P:B{-1e308,0}/AB?\\\\");
+    assert_eq!(translation.steps[2].answer, None);
+    let output = gslc::json::translation(&translation).to_string();
+    let Json::Array(steps) = Json::parse(&output).unwrap() else {
+        panic!("expected steps in {}", output);
+    };
+    assert_eq!(steps[2].get("answer"), Some(&Json::Null));
+}
+
+#[test]
+fn latex_uses_math_mode() {
+    let translator = Translator::new();
+    let translation = translator.translate("\\\\P:A,B,C/S:AB/<ABC=90/[ABC]=\\P/AB∥BC/_PY\\\\");
+    let latex = gslc::latex::Latex::new(&translator);
+    let fragment = latex.fragment(&translation);
+    assert!(fragment.starts_with("\\begin{enumerate}\n"));
+    assert!(fragment.contains("\\item Connect segment $\\overline{AB}$."));
+    assert!(fragment.contains("$\\angle ABC = 90^\\circ$"));
+    assert!(fragment.contains("$[ABC] = \\pi$"));
+    assert!(fragment.contains("$\\overline{AB} \\parallel \\overline{BC}$"));
+    assert!(fragment.contains("By the Pythagorean Theorem."));
+    assert!(!fragment.contains("\\documentclass"));
+
+    let document = latex.document(&translation);
+    assert!(document.starts_with("\\documentclass{article}"));
+    assert!(document.contains(&fragment));
+    assert!(document.trim_end().ends_with("\\end{document}"));
+}
@@ -0,0 +1,48 @@
+use gslc::Translator;
+
+fn figure(input: &str) -> String {
+    gslc::asy::translation(&Translator::new().translate(input))
+}
+
+#[test]
+fn declares_pairs_and_uses_olympiad_constructions() {
+    let asy = figure("\\\\P:A{0,0}/P:B{6,0}/P:C{1,4}/J:ABC/CCO:ABC=O/IC:ABC/PD:C;AB=H\\\\");
+    assert!(asy.contains("pair A = (0, 0);"));
+    assert!(asy.contains("draw(A--B--C--cycle);"));
+    assert!(asy.contains("pair O = circumcenter(A, B, C);"));
+    assert!(asy.contains("draw(incircle(A, B, C));"));
+    assert!(asy.contains("pair H = foot(C, A, B);"));
+    assert!(asy.contains("dot(H);"));
+    assert!(asy.contains("label(\"$H$\", H, dir("));
+}
+
+#[test]
+fn intersects_lines_with_extension() {
+    let asy = figure("\\\\P:A,B,C,D/S:AB/S:CD/P:X=ABxCD\\\\");
+    assert!(asy.contains("pair X = extension(A, B, C, D);"));
+}
+
+#[test]
+fn marks_right_angles() {
+    let asy = figure("\\\\P:A{4,0}/P:B{0,0}/P:C{0,3}/<ABC=90/<BAC?\\\\");
+    assert!(asy.contains("markscalefactor = "));
+    assert!(asy.contains("draw(rightanglemark(A, B, C));"));
+    assert!(asy.contains("draw(anglemark(B, A, C));"));
+}
+
+#[test]
+fn renames_primed_points() {
+    let asy = figure("\\\\P:A'{1,1}\\\\");
+    assert!(asy.contains("pair Ap = (1, 1);"));
+    assert!(asy.contains("label(\"$A'$\", Ap, dir(45));"));
+}
+
+/// The whole file for a figure that uses each kind of construction, checked
+/// against `tests/golden/triangle.asy`. After an intended change, regenerate
+/// it with `gslc --format asy -f tests/golden/triangle.gsl`.
+#[test]
+fn matches_the_golden_file() {
+    let input = include_str!("golden/triangle.gsl");
+    let expected = include_str!("golden/triangle.asy");
+    assert_eq!(figure(input.trim_end()).trim_end(), expected.trim_end());
+}
@@ -0,0 +1,115 @@
+use gslc::{check, parse};
+
+fn warnings(input: &str) -> Vec<String> {
+    let statements: Vec<_> = parse(input).into_iter().collect::<Result<_, _>>().unwrap();
+    check(&statements).into_iter().map(|d| d.message).collect()
+}
+
+#[test]
+fn derived_constructions_introduce_their_targets() {
+    for input in [
+        "\\\\P:A,B,C/PD:C;AB=H/<AHC=90\\\\",
+        "\\\\P:A,B,C/AB:ABC=D/BD?\\\\",
+        "\\\\P:A,B,C/EAB:ABC=D/BD?\\\\",
+        "\\\\P:A,B,C/MD:ABC=D/AD?\\\\",
+        "\\\\P:A,B,C/CC:ABC=W/cW?\\\\",
+    ] {
+        assert_eq!(warnings(input), Vec::<String>::new(), "{}", input);
+    }
+}
+
+#[test]
+fn a_polygon_constructs_its_vertices() {
+    assert_eq!(warnings("\\\\J:ABC/AB?/<ABC?\\\\"), Vec::<String>::new());
+}
+
+#[test]
+fn statements_that_refine_an_object_do_not_construct_it_again() {
+    for input in [
+        "\\\\J:ABC/R:3;AB=ABC\\\\",
+        "\\\\P:O/C:O;5/C:O;r\\\\",
+        "\\\\P:O,A/C:O;5/C:O;A\\\\",
+    ] {
+        assert_eq!(warnings(input), Vec::<String>::new(), "{}", input);
+    }
+    assert_eq!(
+        warnings("\\\\J:ABC/J:ABC\\\\"),
+        ["polygon `ABC` is already constructed"]
+    );
+}
+
+#[test]
+fn each_finding_has_its_own_warning() {
+    for (input, expected) in [
+        (
+            "\\\\S:AB\\\\",
+            "points `A`, `B` are used before they are constructed",
+        ),
+        (
+            "\\\\P:A/S:AB\\\\",
+            "point `B` is used before it is constructed",
+        ),
+        ("\\\\P:A/P:A\\\\", "point `A` is already constructed"),
+        (
+            "\\\\P:A,B/S:AB/S:AB\\\\",
+            "segment `AB` is already constructed",
+        ),
+        (
+            "\\\\P:A,B,C/R:4;AB=ABC\\\\",
+            "`R:4` describes a 4-sided polygon, but `ABC` has 3 vertices",
+        ),
+        (
+            "\\\\P:A,B/J:AB\\\\",
+            "polygon `AB` needs at least 3 vertices",
+        ),
+        (
+            "\\\\P:A,B/CCO:AB=O\\\\",
+            "`CCO:` takes 3 points, but `AB` names 2",
+        ),
+        (
+            "\\\\P:A,B/<AB?\\\\",
+            "an angle takes 3 points, but `AB` names 2",
+        ),
+        (
+            "\\\\P:A,B,C/S:ABC\\\\",
+            "segment takes 2 points, but `ABC` names 3",
+        ),
+        (
+            "\\\\P:A,B,C/[ABC]?\\\\",
+            "polygon `ABC` has not been constructed",
+        ),
+        ("\\\\P:A/cA?\\\\", "circle `A` has not been constructed"),
+    ] {
+        assert_eq!(warnings(input), [expected], "{}", input);
+    }
+}
+
+#[test]
+fn objects_can_be_read_off_others() {
+    for input in [
+        // The sides of a polygon that were drawn make it
+        "\\\\P:A,B,C/S:AB/S:BC/S:CA/[ABC]?\\\\",
+        // A segment lies on a line or ray through its ends
+        "\\\\P:A,B/L:AB/P:X.AB/AX?\\\\",
+        "\\\\P:A,B/W:AB/P:X.AB/AX?\\\\",
+        // An intersection or a point on an object is constructed by it
+        "\\\\P:A,B,C,D/S:AB/S:CD/P:X=ABxCD/AX?\\\\",
+        "\\\\P:A,B/S:AB/P:X.AB/AX?\\\\",
+    ] {
+        assert_eq!(warnings(input), Vec::<String>::new(), "{}", input);
+    }
+}
+
+#[test]
+fn the_help_and_readme_examples_are_clean() {
+    for input in [
+        "\\\\J:ABC/R:3;AB=ABC\\\\",
+        "\\\\P:A{0,0}/P:B{3,0}/P:C{0,4}/J:ABC/[ABC]?/<BAC?/BC?\\\\",
+        "\\\\P:A,B,C/J:ABC/<ABC=90/[ABC]?\\\\",
+        "\\\\P:A,B,C/J:ABC/[ABC]=20/<ABC=90/CCO:ABC\\\\",
+        "\\\\P:A{0,0}/P:B{6,0}/P:C{1,4}/J:ABC/CCO:ABC=O/PD:C;AB=H/<AHC=90\\\\",
+        "\\\\P:A,B,C/J:ABC/CCO:ABC=O/AB=BC\\\\",
+    ] {
+        assert_eq!(warnings(input), Vec::<String>::new(), "{}", input);
+    }
+}
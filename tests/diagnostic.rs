@@ -0,0 +1,83 @@
+use gslc::diagnostic::{Diagnostic, SourceFile};
+use gslc::lexer::Span;
+use gslc::Translator;
+
+#[test]
+fn the_caret_sits_under_the_span() {
+    let input = "\\\\P:A,B/S:AB\\\\";
+    let diagnostic = Diagnostic::error("bad segment", Span::new(10, 14)).with_hint("try again");
+    assert_eq!(
+        SourceFile::new("test.gsl", input).render(&diagnostic),
+        "error: bad segment
+ --> test.gsl:1:11
+  |
+1 | \\\\P:A,B/S:AB\\\\
+  |           ^^^^
+  = hint: try again
+"
+    );
+}
+
+#[test]
+fn columns_count_characters_and_lines_count_from_one() {
+    let input = "\\\\P:A,B,C\n∠ABC=60\nS:AX\\\\";
+    let source = SourceFile::new("problem.gsl", input);
+    let at = input.find("S:AX").unwrap();
+    assert_eq!(source.line_col(at), (3, 1));
+    assert_eq!(source.line_col(input.find("=60").unwrap()), (2, 5));
+
+    let rendered = source.render(&Diagnostic::warning("x", Span::new(at, at + 4)));
+    assert!(
+        rendered.starts_with("warning: x\n --> problem.gsl:3:1\n"),
+        "{}",
+        rendered
+    );
+    assert!(
+        rendered.contains("3 | S:AX\\\\\n  | ^^^^\n"),
+        "{}",
+        rendered
+    );
+}
+
+#[test]
+fn a_span_over_several_lines_is_underlined_to_the_end_of_its_first() {
+    let input = "\\\\P:A,B,C\r\nJ:ABC\r\nAB?\\\\";
+    let start = input.find("J:").unwrap();
+    let rendered =
+        SourceFile::new("f", input).render(&Diagnostic::error("x", Span::new(start, input.len())));
+    assert!(
+        rendered.contains("2 | J:ABC\n  | ^^^^^\n"),
+        "{:?}",
+        rendered
+    );
+}
+
+#[test]
+fn an_empty_span_still_gets_a_caret() {
+    let input = "\\\\P:A,B/AB=\\\\";
+    let translation = Translator::new().translate(input);
+    let rendered = SourceFile::new("f", input).render(&translation.diagnostics[0]);
+    assert!(rendered.contains("\n  |            ^"), "{}", rendered);
+    assert!(rendered.contains("= hint: "), "{}", rendered);
+}
+
+#[test]
+fn notes_point_at_their_own_line() {
+    let input = "\\\\P:A\nP:A\\\\";
+    let translation = Translator::new().translate(input);
+    let rendered = SourceFile::new("f", input).render(&translation.diagnostics[0]);
+    assert_eq!(
+        rendered,
+        "warning: point `A` is already constructed
+ --> f:2:1
+  |
+2 | P:A\\\\
+  | ^^^
+note: first constructed here
+ --> f:1:3
+  |
+1 | \\\\P:A
+  |   ^^^
+"
+    );
+}
@@ -0,0 +1,151 @@
+use gslc::eval::{format_number, Scene};
+use gslc::geometry::{self, Point};
+use gslc::Translator;
+
+fn statements(input: &str) -> Vec<gslc::ast::Statement> {
+    gslc::parse(input)
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+fn answers(input: &str) -> Vec<Option<String>> {
+    Translator::new()
+        .translate(input)
+        .steps
+        .iter()
+        .map(|step| step.answer_text())
+        .collect()
+}
+
+#[test]
+fn answers_queries_about_fixed_points() {
+    let answers = answers("\\\\P:A{0,0}/P:B{3,0}/P:C{0,4}/[ABC]?/(ABC)?/<BAC?/BC?\\\\");
+    assert_eq!(
+        &answers[3..],
+        [
+            Some("6".to_string()),
+            Some("12".to_string()),
+            Some("90°".to_string()),
+            Some("5".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn leaves_undetermined_queries_unanswered() {
+    let answers = answers("\\\\P:A,B,C/J:ABC/[ABC]?\\\\");
+    assert_eq!(answers[2], None);
+}
+
+#[test]
+fn builds_regular_polygons_from_a_side() {
+    let scene = Scene::evaluate(&statements("\\\\P:A{0,0}/P:B{4,0}/R:4;AB=ABCD\\\\"));
+    let c = scene.point("C").unwrap();
+    let d = scene.point("D").unwrap();
+    assert!(c.dist(Point::new(4.0, 4.0)) < 1e-9);
+    assert!(d.dist(Point::new(0.0, 4.0)) < 1e-9);
+    assert!(scene.is_fixed("D"));
+}
+
+#[test]
+fn derives_triangle_centers() {
+    let scene = Scene::evaluate(&statements(
+        "\\\\P:A{0,0}/P:B{6,0}/P:C{0,8}/CCO:ABC=O/ICO:ABC=I/CT:ABC=G/OC:ABC=H/9O:ABC=N\\\\",
+    ));
+    let at = |name: &str| scene.point(name).unwrap();
+    assert!(at("O").dist(Point::new(3.0, 4.0)) < 1e-9);
+    assert!(at("I").dist(Point::new(2.0, 2.0)) < 1e-9);
+    assert!(at("G").dist(Point::new(2.0, 8.0 / 3.0)) < 1e-9);
+    assert!(at("H").dist(Point::new(0.0, 0.0)) < 1e-9);
+    assert!(at("N").dist(Point::new(1.5, 2.0)) < 1e-9);
+}
+
+#[test]
+fn intersects_lines_and_circles() {
+    let scene = Scene::evaluate(&statements(
+        "\\\\P:O{0,0}/C:O;5/P:A{-5,0}/P:B{1,2}/P:X=lABxcO/P:C{0,-5}/P:D{0,5}/P:Y=lABxlCD\\\\",
+    ));
+    assert!(scene.point("X").unwrap().dist(Point::new(4.0, 3.0)) < 1e-9);
+    assert!(scene.point("Y").unwrap().dist(Point::new(0.0, 5.0 / 3.0)) < 1e-9);
+}
+
+#[test]
+fn places_free_points_deterministically() {
+    let first = Scene::evaluate(&statements("\\\\P:A,B,C/P:D.AB\\\\"));
+    let second = Scene::evaluate(&statements("\\\\P:A,B,C/P:D.AB\\\\"));
+    let first: Vec<_> = first.points().map(|(n, p)| (n.to_string(), p)).collect();
+    let second: Vec<_> = second.points().map(|(n, p)| (n.to_string(), p)).collect();
+    assert_eq!(first, second);
+    assert_eq!(first.len(), 4);
+}
+
+#[test]
+fn free_points_stay_off_placed_points() {
+    let scene = Scene::evaluate(&statements("\\\\P:A{0,0}/P:B{6,0}/P:C\\\\"));
+    let c = scene.point("C").unwrap();
+    for given in ["A", "B"] {
+        assert!(scene.point(given).unwrap().dist(c) > 0.5, "C is at {:?}", c);
+    }
+    // A point that is not finite takes no spot, and does not stop the search
+    let scene = Scene::evaluate(&statements(
+        "\\\\P:A{0,0}/P:B{0,0}/P:C{0,0}/CCO:ABC=O/P:D\\\\",
+    ));
+    assert!(scene.point("D").unwrap().is_finite());
+}
+
+#[test]
+fn warns_about_impossible_constructions() {
+    let translation = Translator::new().translate("\\\\P:A{0,0}/P:B{1,1}/P:C{2,2}/C:A;B;C\\\\");
+    assert!(translation
+        .diagnostics
+        .iter()
+        .any(|d| d.message.contains("collinear")));
+}
+
+#[test]
+fn rejects_coordinates_that_are_not_finite() {
+    for input in ["\\\\P:A{nan,0}/P:D\\\\", "\\\\P:A{0,-inf}\\\\"] {
+        let translation = Translator::new().translate(input);
+        assert!(translation.has_errors(), "{}", input);
+        assert!(translation.diagnostics[0].message.contains("not finite"));
+    }
+}
+
+#[test]
+fn coincident_points_leave_measures_undefined() {
+    let translation =
+        Translator::new().translate("\\\\P:A{0,0}/P:B{0,0}/P:C{0,0}/CCO:ABC=O/OA?\\\\");
+    assert!(translation
+        .diagnostics
+        .iter()
+        .any(|d| d.message.contains("degenerate")));
+    assert_eq!(translation.steps[4].answer, None);
+
+    let answers = answers("\\\\P:A{0,0}/P:B{0,0}/P:C{1,0}/<ABC?/qBAC?\\\\");
+    assert_eq!(answers[3..], [None, None]);
+}
+
+#[test]
+fn arcs_through_a_point_can_be_major() {
+    let answers = answers("\\\\P:O{0,0}/P:A{1,0}/P:X{-1,0}/P:B{0,1}/C:O;A/<aAXB?/<aAB?/<aBXA?\\\\");
+    assert_eq!(
+        answers[5..],
+        [
+            Some("270°".to_string()),
+            Some("90°".to_string()),
+            Some("270°".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn geometry_helpers() {
+    let a = Point::new(0.0, 0.0);
+    let b = Point::new(4.0, 0.0);
+    let c = Point::new(0.0, 3.0);
+    assert!((geometry::polygon_area(&[a, b, c]) - 6.0).abs() < 1e-9);
+    assert!((geometry::angle(b, a, c) - 90.0).abs() < 1e-9);
+    assert_eq!(format_number(2.0), "2");
+    assert_eq!(format_number(1.0 / 3.0), "0.3333");
+}
@@ -0,0 +1,251 @@
+use gslc::formatter::{format, Style};
+use gslc::json::Json;
+use gslc::lexer::{comment_body, comments};
+use gslc::linter::{lint, Config};
+use gslc::{parse, Translator};
+use std::process::Command;
+
+#[test]
+fn line_breaks_separate_statements() {
+    let translator = Translator::new();
+    let lines = translator.translate("\\\\P:A,B,C\nS:AB\n\n<ABC=90\\\\");
+    let slashes = translator.translate("\\\\P:A,B,C/S:AB/<ABC=90\\\\");
+    assert!(!lines.has_errors());
+    assert_eq!(lines.english(), slashes.english());
+
+    // A line that stops partway through a statement carries on
+    for input in [
+        "\\\\P:A,B,C/AB=\n5\\\\",
+        "\\\\P:\nA,B,C\\\\",
+        "\\\\P:A,\nB,C\\\\",
+        "\\\\P:A,B,C/<ABC+\n<BCA=90\\\\",
+        "\\\\P:A,B/S:AB/AB=1<<(x=1;AB=2),\n(x=2;AB=3)>>\\\\",
+        "\\\\P:A,B,C/[ABC\n]=5\\\\",
+        "\\\\P:A,B,C/S:AB/\nS:BC\\\\",
+    ] {
+        assert!(parse(input).iter().all(Result::is_ok), "{:?}", input);
+    }
+}
+
+#[test]
+fn an_unclosed_group_ends_with_its_line() {
+    let input = "\\\\P:A,B,C\n[AB\nS:AB\nS:BC\nJ:ABC*XYZ\\\\";
+    let translation = Translator::new().translate(input);
+    let errors: Vec<(usize, &str)> = translation
+        .diagnostics
+        .iter()
+        .filter(|d| d.is_error())
+        .map(|d| (d.span.start, &input[d.span.start..d.span.end]))
+        .collect();
+    assert_eq!(errors.len(), 2, "{:?}", translation.diagnostics);
+    // The caret is just after `[AB`, not on the next line
+    assert_eq!(errors[0].0, input.find("[AB").unwrap() + 3);
+    assert_eq!(errors[1].1, "J:ABC*XYZ");
+    // Both later statements were still read
+    assert_eq!(translation.steps.len(), 4);
+}
+
+#[test]
+fn comments_are_skipped_with_their_spans() {
+    let input = "# points\n\\\\P:A,B /* between */ \n%% a segment\nS:AB\\\\/* multi\nline */";
+    let spans = comments(input);
+    let texts: Vec<&str> = spans.iter().map(|s| &input[s.start..s.end]).collect();
+    assert_eq!(
+        texts,
+        [
+            "# points",
+            "/* between */",
+            "%% a segment",
+            "/* multi\nline */"
+        ]
+    );
+    let bodies: Vec<&str> = texts.iter().map(|t| comment_body(t)).collect();
+    assert_eq!(bodies, ["points", "between", "a segment", "multi\nline"]);
+
+    let translation = Translator::new().translate(input);
+    assert!(!translation.has_errors());
+    let sources: Vec<&str> = translation
+        .steps
+        .iter()
+        .map(|s| s.source.as_str())
+        .collect();
+    assert_eq!(sources, ["P:A,B", "S:AB"]);
+    let span = translation.steps[1].statement.span;
+    assert_eq!(&input[span.start..span.end], "S:AB");
+}
+
+#[test]
+fn unclosed_block_comment_is_an_error() {
+    let translation = Translator::new().translate("\\\\P:A,B/* oops\nS:AB\\\\");
+    assert!(translation.has_errors());
+    assert!(translation.diagnostics[0]
+        .message
+        .contains("unclosed block comment"));
+}
+
+#[test]
+fn each_problem_is_translated_on_its_own() {
+    let input = "\\\\P:A{0,0}\nP:B{3,4}\nAB?\\\\\n\n# second\n\\\\P:A{0,0}/P:B{1,0}/AB?\\\\";
+    let problems = Translator::new().translate_problems(input);
+    assert_eq!(problems.len(), 2);
+    let (first, second) = (&problems[0].translation, &problems[1].translation);
+    assert_eq!(first.steps.len(), 3);
+    assert_eq!(first.steps[2].answer, Some(5.0));
+    assert_eq!(second.steps[2].answer, Some(1.0));
+    assert_eq!(second.steps[0].source, "P:A{0,0}");
+    assert!(second.steps[0].statement.span.start > input.find("# second").unwrap());
+    assert_eq!(problems[1].id(), "2");
+
+    // Points from the first problem are not constructed in the second
+    let problems = Translator::new().translate_problems("\\\\P:A,B/S:AB\\\\\\\\S:AB\\\\");
+    assert!(problems[0].translation.diagnostics.is_empty());
+    assert!(!problems[1].translation.diagnostics.is_empty());
+}
+
+#[test]
+fn json_numbers_problems() {
+    let problems = Translator::new().translate_problems("\\\\P:A\\\\\n\\\\P:B/P:C\\\\");
+    let Json::Array(items) = gslc::json::problems(&problems) else {
+        panic!("expected an array");
+    };
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[1].get("problem").and_then(Json::as_usize), Some(2));
+    assert_eq!(items[1].get("id").and_then(Json::as_str), Some("2"));
+    let Some(Json::Array(steps)) = items[1].get("steps") else {
+        panic!("expected steps");
+    };
+    assert_eq!(steps.len(), 2);
+}
+
+#[test]
+fn files_always_give_a_problem_array() {
+    let dir = std::env::temp_dir().join(format!("gslc-files-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let gslc = env!("CARGO_BIN_EXE_gslc");
+    let run = |text: &str, args: &[&str]| {
+        let path = dir.join("problem.gsl");
+        std::fs::write(&path, text).unwrap();
+        Command::new(gslc)
+            .arg("-f")
+            .arg(&path)
+            .args(args)
+            .output()
+            .unwrap()
+    };
+
+    for text in [
+        "\\\\P:A,B/AB=5\\\\\n",
+        "@problem pythag\n\\\\P:A,B/AB=5\\\\\n",
+    ] {
+        let output = run(text, &["--format", "json"]);
+        assert!(output.status.success());
+        let json = Json::parse(&String::from_utf8_lossy(&output.stdout)).unwrap();
+        let Json::Array(items) = json else {
+            panic!("expected an array");
+        };
+        assert_eq!(items.len(), 1);
+        assert!(items[0].get("steps").is_some(), "{:?}", items[0]);
+
+        let output = run(text, &["--format", "latex"]);
+        assert!(String::from_utf8_lossy(&output.stdout).contains("\\subsection*"));
+    }
+
+    let output = run("\\\\P:A\\\\\n\\\\P:B\\\\\n", &["--problem", "3"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("it has 1, 2"));
+}
+
+#[test]
+fn formatting_keeps_problems_apart() {
+    let input = "# one\n\\\\P:A,B\nS:AB\\\\\n\\\\P:C   # two\n\\\\";
+    let formatted = format(input, Style::Ascii).unwrap();
+    assert_eq!(
+        formatted,
+        "# one\n\\\\P:A,B/\nS:AB\\\\\n\n\\\\P:C\\\\ # two\n"
+    );
+    assert_eq!(format(&formatted, Style::Ascii).unwrap(), formatted);
+}
+
+#[test]
+fn lint_checks_each_problem() {
+    let input = "\\\\P:A,B/S:AB\\\\\n\\\\P:C,D/S:CD/AB?\\\\";
+    let diagnostics = lint(input, &Config::default());
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0].message.contains("[unconstructed-query]"));
+
+    let allowed = "\\\\P:A,B,C/S:AB /* allow(unused-point) */\\\\";
+    assert!(lint(allowed, &Config::default()).is_empty());
+}
+
+const WORKSHEET: &str = "# Worksheet
+@problem right: A 3-4-5 triangle
+@source: Warm-up
+@difficulty: easy
+@tags: pythagorean, coordinates
+@answer: 5
+\\\\P:A{0,0}/P:B{3,0}/P:C{0,4}/BC?\\\\
+
+@problem unit
+\\\\P:A{0,0}/P:B{1,0}/AB?\\\\
+";
+
+#[test]
+fn problems_carry_their_metadata() {
+    let problems = Translator::new().translate_problems(WORKSHEET);
+    assert_eq!(problems.len(), 2);
+    let metadata = &problems[0].metadata;
+    assert_eq!(problems[0].id(), "right");
+    assert_eq!(problems[0].heading(), "Problem 1: A 3-4-5 triangle");
+    assert_eq!(metadata.source.as_deref(), Some("Warm-up"));
+    assert_eq!(metadata.difficulty.as_deref(), Some("easy"));
+    assert_eq!(metadata.tags, ["pythagorean", "coordinates"]);
+    assert_eq!(metadata.answer.as_deref(), Some("5"));
+    assert!(problems[0].translation.diagnostics.is_empty());
+    assert_eq!(problems[1].id(), "unit");
+    assert_eq!(problems[1].heading(), "Problem 2");
+
+    let json = gslc::json::problem(&problems[0]);
+    let metadata = json.get("metadata").unwrap();
+    assert_eq!(
+        metadata.get("source").and_then(Json::as_str),
+        Some("Warm-up")
+    );
+    assert_eq!(metadata.get("answer").and_then(Json::as_str), Some("5"));
+    assert_eq!(
+        metadata.get("tags"),
+        Some(&Json::array(["pythagorean", "coordinates"], Json::from))
+    );
+}
+
+#[test]
+fn problem_lines_start_problems_without_wrappers() {
+    let problems = Translator::new().translate_problems("@problem a\nP:A\n@problem b\nP:B\n");
+    let ids: Vec<String> = problems.iter().map(|p| p.id()).collect();
+    assert_eq!(ids, ["a", "b"]);
+    assert_eq!(problems[1].translation.steps[0].source, "P:B");
+}
+
+#[test]
+fn metadata_mistakes_are_reported() {
+    let translator = Translator::new();
+    let messages = |input: &str| -> Vec<String> {
+        translator
+            .translate_problems(input)
+            .iter()
+            .flat_map(|p| &p.translation.diagnostics)
+            .map(|d| d.message.clone())
+            .collect()
+    };
+    assert_eq!(
+        messages("@problem a\n\\\\P:A\\\\\n@problem a\n\\\\P:B\\\\"),
+        ["problem id `a` is used twice"]
+    );
+    assert_eq!(
+        messages("@colour: red\n\\\\P:A\\\\"),
+        ["unknown metadata key `@colour`"]
+    );
+    assert_eq!(
+        messages("@answer: 2\n\\\\P:A{0,0}/P:B{1,0}/AB?\\\\"),
+        ["the stated answer is 2, but the figure gives 1"]
+    );
+}
@@ -0,0 +1,116 @@
+use gslc::formatter::{format, Style};
+use gslc::Translator;
+use std::process::Command;
+
+const PROBLEMS: &[&str] = &[
+    "\\\\P:A,B,C/J:ABC/[ABC]=20/<ABC=90/CCO:ABC=O\\\\",
+    "\\\\P:D.AC|R:3;AD=ADE,[ADE]=20,AB∥CD/C:O;\\P/aAB?/<aAB=60\\\\",
+    "\\\\AB∥CD/AB⊥CD?/AB;CD*PR/ABC≅DEF/ABC~DEF/AB;CD*CG/A;B;C*S\\\\",
+    "\\\\\\p:∠ABC=<DEF/□/\\qC/∵/∴/\\th\\\\",
+    "\\\\AB=BC&&CD=DE/∀AB=BC/|EAB=BC/AB=BC\\?\\\\",
+    "\\\\AB=BC<<(x=1;∠ABC=90),(x=2;AB⊥CD)>>/AB=BC<</>>\\\\",
+    "\\\\[ABC]=τ/2/C:O;r/G:{y = x^2}/_PY/ABC*EQ?/ABC*+\\\\",
+];
+
+fn english(input: &str) -> Vec<String> {
+    let translation = Translator::new().translate(input);
+    translation
+        .english()
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+#[test]
+fn formatting_keeps_the_meaning() {
+    for problem in PROBLEMS {
+        let ascii = format(problem, Style::Ascii).unwrap();
+        for style in [Style::Ascii, Style::Unicode] {
+            let formatted = format(problem, style).unwrap();
+            assert_eq!(format(&formatted, style).unwrap(), formatted);
+            let back = format(&formatted, Style::Ascii).unwrap();
+            assert_eq!(english(&ascii), english(&back), "{}", formatted);
+        }
+    }
+}
+
+#[test]
+fn formatting_keeps_the_translation() {
+    let input = "\\\\P:A{0,0}/P:B{3,4}/ S:AB /AB∥CD/∠ABC=90/AB?\\\\";
+    for style in [Style::Ascii, Style::Unicode] {
+        let formatted = format(input, style).unwrap();
+        let before = Translator::new().translate(input);
+        let after = Translator::new().translate(&formatted);
+        assert_eq!(before.english(), after.english());
+        assert_eq!(before.steps[5].answer, after.steps[5].answer);
+    }
+}
+
+#[test]
+fn ascii_and_unicode_spellings() {
+    let input = "\\\\ ∠ABC = 90 / AB∥CD / AB;CD*PR / ∴ / [ABC]=π \\\\";
+    assert_eq!(
+        format(input, Style::Ascii).unwrap(),
+        "\\\\<ABC=90/AB;CD*P/AB;CD*PD/\\th/[ABC]=\\P\\\\\n"
+    );
+    assert_eq!(
+        format(input, Style::Unicode).unwrap(),
+        "\\\\∠ABC=90/AB∥CD/AB⊥CD/∴/[ABC]=π\\\\\n"
+    );
+}
+
+#[test]
+fn multi_line_problems_get_one_statement_per_line() {
+    let input = "\\\\P:A,B /S:AB\n/AB?\\\\\n";
+    assert_eq!(
+        format(input, Style::Ascii).unwrap(),
+        "\\\\P:A,B/\nS:AB/\nAB?\\\\\n"
+    );
+}
+
+#[test]
+fn parse_errors_are_reported_not_formatted() {
+    let diagnostics = format("\\\\P:A/S:\\\\", Style::Ascii).unwrap_err();
+    assert_eq!(diagnostics.len(), 1);
+}
+
+#[test]
+fn check_fails_on_unformatted_files() {
+    let dir = std::env::temp_dir().join(format!("gslc-fmt-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("problem.gsl");
+    std::fs::write(&path, "\\\\P:A, B / S:AB\\\\\n").unwrap();
+    let gslc = env!("CARGO_BIN_EXE_gslc");
+
+    let check = Command::new(gslc)
+        .args(["fmt", "--check"])
+        .arg(&path)
+        .output()
+        .unwrap();
+    assert!(!check.status.success());
+    assert!(String::from_utf8_lossy(&check.stdout).contains("is not formatted"));
+
+    let fix = Command::new(gslc).arg("fmt").arg(&path).status().unwrap();
+    assert!(fix.success());
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "\\\\P:A,B/S:AB\\\\\n"
+    );
+
+    let check = Command::new(gslc)
+        .args(["fmt", "--check"])
+        .arg(&path)
+        .status()
+        .unwrap();
+    assert!(check.success());
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn comments_are_kept() {
+    let input = "%% triangle\n\\\\P:A, B,C/ %% vertices\n%% then the sides\nS:AB\\\\\n";
+    assert_eq!(
+        format(input, Style::Ascii).unwrap(),
+        "%% triangle\n\\\\P:A,B,C/ %% vertices\n%% then the sides\nS:AB\\\\\n"
+    );
+}
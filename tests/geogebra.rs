@@ -0,0 +1,59 @@
+use gslc::Translator;
+
+fn export(input: &str) -> Vec<String> {
+    gslc::geogebra::translation(&Translator::new().translate(input))
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn exports_points_and_figures() {
+    let lines = export("\\\\P:A{0,0}/P:B{4,0}/P:C/J:ABC/S:AB/C:A;B;C/M:AB=M\\\\");
+    assert_eq!(lines[0], "A=(0, 0)");
+    assert_eq!(lines[1], "B=(4, 0)");
+    assert!(lines[2].starts_with("C=("));
+    assert_eq!(
+        &lines[3..],
+        [
+            "poly_{ABC}=Polygon(A, B, C)",
+            "s_{AB}=Segment(A, B)",
+            "c_{ABC}=Circle(A, B, C)",
+            "M=Midpoint(A, B)",
+        ]
+    );
+}
+
+#[test]
+fn names_regular_polygon_vertices() {
+    let lines = export("\\\\P:A,B/R:4;AB=ABCD\\\\");
+    assert_eq!(
+        &lines[2..],
+        [
+            "poly_{ABCD}=Polygon(A, B, 4)",
+            "C=Vertex(poly_{ABCD}, 3)",
+            "D=Vertex(poly_{ABCD}, 4)",
+        ]
+    );
+}
+
+#[test]
+fn covers_every_derived_construction() {
+    for code in [
+        "AB", "EAB", "CCO", "ICO", "ECO", "CC", "IC", "EC", "9C", "9O", "CT", "OC", "MD",
+    ] {
+        let lines = export(&format!("\\\\P:A,B,C/{}:ABC\\\\", code));
+        assert!(!lines[3].starts_with('#'), "{} was not exported", code);
+    }
+    for input in ["M:AB", "PB:AB", "PD:D;AB", "PL:D;AB", "TG:D;cO"] {
+        let lines = export(&format!("\\\\P:A,B,D/C:O;5/{}\\\\", input));
+        assert!(!lines[4].starts_with('#'), "{} was not exported", input);
+    }
+}
+
+#[test]
+fn keeps_unsupported_statements_as_comments() {
+    let lines = export("\\\\P:A,B/AB=5/AB?\\\\");
+    assert_eq!(lines[2], "# AB=5: AB = 5");
+    assert_eq!(lines[3], "# AB?: What is AB?");
+}
@@ -0,0 +1,201 @@
+use gslc::formatter::{format, Style};
+use gslc::include::Source;
+use gslc::Translator;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A fresh directory holding `files`, each given as (path, text).
+fn scratch(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("gslc-include-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    for (path, text) in files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, text).unwrap();
+    }
+    dir
+}
+
+fn load(path: &Path, search_path: &[PathBuf]) -> Source {
+    let text = fs::read_to_string(path).unwrap();
+    Source::new(&path.display().to_string(), &text, Some(path), search_path)
+}
+
+const TRIANGLE: &str = "# a 3-4-5 right triangle
+\\def RIGHT:ABC{P:A{0,0}/P:B{4,0}/P:C{0,3}}
+";
+
+#[test]
+fn included_definitions_apply_to_the_including_file() {
+    let dir = scratch(
+        "relative",
+        &[
+            ("lib/triangle.gsl", TRIANGLE),
+            ("lib/setup.gsl", "\\include \"triangle.gsl\"\nP:O\n"),
+            (
+                "problem.gsl",
+                "\\include \"lib/setup.gsl\"\n\\\\RIGHT:ABC/BC?\\\\\n",
+            ),
+        ],
+    );
+    let source = load(&dir.join("problem.gsl"), &[]);
+    assert!(source.diagnostics.is_empty());
+    let translation = Translator::new().translate(&source.text);
+    assert!(translation.diagnostics.is_empty());
+    assert_eq!(translation.english()[0], "Construct point O.");
+    assert_eq!(translation.steps.last().unwrap().answer, Some(5.0));
+
+    // The step from the included file is placed on the `\include` line
+    let span = source.root_span(translation.steps[0].statement.span);
+    assert_eq!(span.start, 0);
+    assert!(!source.is_root(translation.steps[0].statement.span));
+    assert!(source.is_root(translation.steps[1].statement.span));
+}
+
+#[test]
+fn files_are_found_along_the_search_path() {
+    let dir = scratch(
+        "search",
+        &[
+            ("shared/triangle.gsl", TRIANGLE),
+            (
+                "problem.gsl",
+                "\\include \"triangle.gsl\"\n\\\\RIGHT:ABC\\\\\n",
+            ),
+        ],
+    );
+    let problem = dir.join("problem.gsl");
+    let missing = load(&problem, &[]);
+    assert_eq!(missing.diagnostics.len(), 1);
+    assert_eq!(missing.diagnostics[0].message, "cannot find `triangle.gsl`");
+    assert!(missing.diagnostics[0]
+        .hint
+        .as_deref()
+        .unwrap()
+        .contains("GSL_PATH"));
+
+    let found = load(&problem, &[dir.join("shared")]);
+    assert!(found.diagnostics.is_empty());
+    assert!(found.text.contains("RIGHT:ABC{"));
+}
+
+#[test]
+fn each_file_is_read_once() {
+    let dir = scratch(
+        "once",
+        &[
+            ("triangle.gsl", TRIANGLE),
+            ("a.gsl", "\\include \"triangle.gsl\"\n"),
+            (
+                "problem.gsl",
+                "\\include \"a.gsl\"\n\\include \"triangle.gsl\"\n\\\\RIGHT:ABC\\\\\n",
+            ),
+        ],
+    );
+    let source = load(&dir.join("problem.gsl"), &[]);
+    assert_eq!(source.text.matches("\\def RIGHT").count(), 1);
+    assert!(Translator::new()
+        .translate(&source.text)
+        .diagnostics
+        .is_empty());
+}
+
+#[test]
+fn cycles_are_reported_with_the_include_chain() {
+    let dir = scratch(
+        "cycle",
+        &[
+            ("a.gsl", "\\include \"b.gsl\"\n"),
+            ("b.gsl", "\\include \"a.gsl\"\n"),
+            ("problem.gsl", "\\include \"a.gsl\"\n\\\\P:A\\\\\n"),
+        ],
+    );
+    let source = load(&dir.join("problem.gsl"), &[]);
+    assert_eq!(source.diagnostics.len(), 1);
+    let message = &source.diagnostics[0].message;
+    assert!(message.starts_with("include cycle: "), "{}", message);
+    assert!(message.ends_with("a.gsl"), "{}", message);
+
+    let rendered = source.render(&source.diagnostics[0]);
+    assert!(rendered.contains("b.gsl:1:1"), "{}", rendered);
+    assert!(rendered.contains("is included here"), "{}", rendered);
+    assert!(rendered.contains("problem.gsl:1:1"), "{}", rendered);
+}
+
+#[test]
+fn mistakes_in_included_files_are_shown_there() {
+    let dir = scratch(
+        "mistake",
+        &[
+            ("setup.gsl", "P:A\nS:AB\n"),
+            (
+                "problem.gsl",
+                "# uses the setup\n\\include \"setup.gsl\"\n\\\\P:C\\\\\n",
+            ),
+        ],
+    );
+    let source = load(&dir.join("problem.gsl"), &[]);
+    let translation = Translator::new().translate(&source.text);
+    let warning = &translation.diagnostics[0];
+    let rendered = source.render(warning);
+    assert!(rendered.contains("setup.gsl:2:1"), "{}", rendered);
+    assert!(rendered.contains("2 | S:AB"), "{}", rendered);
+    assert!(
+        rendered.contains("note: `") && rendered.contains("problem.gsl:2:1"),
+        "{}",
+        rendered
+    );
+
+    let moved = source.root_diagnostic(warning);
+    assert!(moved.message.ends_with("setup.gsl`)"), "{}", moved.message);
+    assert_eq!(moved.span.start, "# uses the setup\n".len());
+}
+
+#[test]
+fn the_cli_searches_dash_i_and_gsl_path() {
+    let dir = scratch(
+        "cli",
+        &[
+            ("shared/triangle.gsl", TRIANGLE),
+            (
+                "problem.gsl",
+                "\\include \"triangle.gsl\"\n\\\\RIGHT:ABC/BC?\\\\\n",
+            ),
+        ],
+    );
+    let gslc = env!("CARGO_BIN_EXE_gslc");
+    let problem = dir.join("problem.gsl");
+    let run = |args: &[&str], path: Option<&Path>| {
+        let mut command = Command::new(gslc);
+        command.arg("-f").arg(&problem).args(args);
+        match path {
+            Some(path) => command.env("GSL_PATH", path),
+            None => command.env_remove("GSL_PATH"),
+        };
+        command.output().unwrap()
+    };
+
+    let missing = run(&[], None);
+    assert!(!missing.status.success());
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("cannot find `triangle.gsl`"));
+
+    let shared = dir.join("shared");
+    for output in [
+        run(&["-I", shared.to_str().unwrap()], None),
+        run(&[], Some(&shared)),
+    ] {
+        assert!(output.status.success());
+        assert!(String::from_utf8_lossy(&output.stdout).contains("What is BC? → 5"));
+    }
+}
+
+#[test]
+fn formatting_keeps_includes_above_the_problem() {
+    let input = "\\include \"triangle.gsl\"\n\\\\RIGHT:ABC/\nBC?\\\\\n";
+    assert_eq!(format(input, Style::Ascii).unwrap(), input);
+    assert_eq!(
+        gslc::parse("\\include").remove(0).unwrap_err().message,
+        "expected a file name in quotes after `\\include`, found end of statement"
+    );
+}
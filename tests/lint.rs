@@ -0,0 +1,118 @@
+use gslc::diagnostic::Severity;
+use gslc::lexer::comments;
+use gslc::linter::{lint, Config};
+
+fn rules(input: &str, config: &Config) -> Vec<String> {
+    lint(input, config)
+        .iter()
+        .filter_map(|d| {
+            let (_, rule) = d.message.rsplit_once(" [")?;
+            Some(rule.trim_end_matches(']').to_string())
+        })
+        .collect()
+}
+
+#[test]
+fn each_rule_fires() {
+    let config = Config::default();
+    let cases = [
+        ("\\\\P:A,B,C/J:ABC/_SSA\\\\", "ssa-congruence"),
+        (
+            "\\\\P:A,B,C,D,E,F/ABC;DEF*CG_SSA/S:AB/S:DE\\\\",
+            "ssa-congruence",
+        ),
+        ("\\\\P:A,B/R:4;AC=ABCD\\\\", "regular-polygon-side"),
+        ("\\\\P:A,B,C/J:ABCA\\\\", "repeated-vertex"),
+        ("\\\\P:A,B,C/S:AB\\\\", "unused-point"),
+        ("\\\\P:A,B/AB?\\\\", "unconstructed-query"),
+        ("\\\\P:A,B,C/S:AB/<ABC?\\\\", "unconstructed-query"),
+        ("\\\\P:A,B,C/[ABC]?\\\\", "unconstructed-query"),
+        ("\\\\P:A,B/S:AB/AB=1<<(x=1;AB=2)>>\\\\", "single-case"),
+    ];
+    for (input, rule) in cases {
+        assert_eq!(rules(input, &config), [rule], "{}", input);
+    }
+}
+
+#[test]
+fn clean_problems_have_no_findings() {
+    let input =
+        "\\\\P:A,B,C/J:ABC/R:3;AB=ABD/<ABC?/AB?/[ABC]?/_SAC/AB=1<<(x=1;AB=2),(x=2;AB=3)>>\\\\";
+    assert!(lint(input, &Config::default()).is_empty());
+}
+
+#[test]
+fn levels_come_from_the_config() {
+    let input = "\\\\P:A,B,C/S:AB/_SSA\\\\";
+    let config =
+        Config::parse("# stricter than usual\nssa-congruence = deny\nunused-point = allow\n")
+            .unwrap();
+    let diagnostics = lint(input, &config);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+
+    assert!(Config::parse("no-such-rule = warn").is_err());
+    assert!(Config::parse("unused-point = loud")
+        .unwrap_err()
+        .contains("line 1"));
+}
+
+#[test]
+fn comments_suppress_rules() {
+    let input = "\\\\P:A,B,C/ %% allow(unused-point)\nS:AB/\n%% allow(ssa-congruence)\n_SSA\\\\";
+    assert!(rules(input, &Config::default()).is_empty());
+
+    let input = "\\\\P:A/ %% allow(no-such-rule)\nS:AA\\\\";
+    let diagnostics = lint(input, &Config::default());
+    assert!(diagnostics
+        .iter()
+        .any(|d| d.message == "unknown lint rule `no-such-rule`"));
+
+    // A comment at the end of a one-line problem ends before its `\\`
+    for input in [
+        "\\\\P:A,B,C/S:AB # allow(unused-point)\\\\",
+        "\\\\P:A,B,C/S:AB %% allow(unused-point) \\\\\n",
+    ] {
+        assert!(rules(input, &Config::default()).is_empty(), "{}", input);
+        let comment = comments(input)[0];
+        assert!(input[comment.start..comment.end].ends_with("allow(unused-point)"));
+    }
+}
+
+#[test]
+fn cli_exits_nonzero_on_denied_rules() {
+    let dir = std::env::temp_dir().join(format!("gslc-lint-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let problem = dir.join("problem.gsl");
+    std::fs::write(&problem, "\\\\P:A,B,C/S:AB\\\\\n").unwrap();
+    let config = dir.join("lint.conf");
+    std::fs::write(&config, "unused-point = deny\n").unwrap();
+    let gslc = env!("CARGO_BIN_EXE_gslc");
+
+    let warned = std::process::Command::new(gslc)
+        .arg("lint")
+        .arg(&problem)
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert!(warned.status.success());
+    assert!(String::from_utf8_lossy(&warned.stderr).contains("[unused-point]"));
+
+    let denied = std::process::Command::new(gslc)
+        .arg("lint")
+        .arg("--config")
+        .arg(&config)
+        .arg(&problem)
+        .status()
+        .unwrap();
+    assert!(!denied.success());
+
+    let allowed = std::process::Command::new(gslc)
+        .args(["lint", "-A", "unused-point"])
+        .arg(&problem)
+        .output()
+        .unwrap();
+    assert!(allowed.status.success());
+    assert!(allowed.stderr.is_empty());
+    std::fs::remove_dir_all(&dir).ok();
+}
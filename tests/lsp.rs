@@ -0,0 +1,232 @@
+//! Drives `gslc lsp` the way an editor would: framed JSON-RPC over stdio.
+
+use gslc::json::Json;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+fn frame(body: &str) -> String {
+    format!("Content-Length: {}\r\n\r\n{}", body.len(), body)
+}
+
+/// Send each message in order, then return everything the server sent back.
+fn session(messages: &[String]) -> Vec<Json> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_gslc"))
+        .arg("lsp")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    for message in messages {
+        stdin.write_all(frame(message).as_bytes()).unwrap();
+    }
+    drop(stdin);
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let mut rest = String::from_utf8(output.stdout).unwrap();
+    let mut replies = Vec::new();
+    while let Some((header, body)) = rest.split_once("\r\n\r\n") {
+        let length: usize = header
+            .trim_start_matches("Content-Length: ")
+            .parse()
+            .unwrap();
+        replies.push(Json::parse(&body[..length]).unwrap());
+        rest = body[length..].to_string();
+    }
+    replies
+}
+
+fn request(id: usize, method: &str, params: &str) -> String {
+    format!(
+        r#"{{"jsonrpc":"2.0","id":{},"method":"{}","params":{}}}"#,
+        id, method, params
+    )
+}
+
+fn notify(method: &str, params: &str) -> String {
+    format!(
+        r#"{{"jsonrpc":"2.0","method":"{}","params":{}}}"#,
+        method, params
+    )
+}
+
+fn at(uri: &str, line: usize, character: usize) -> String {
+    format!(
+        r#"{{"textDocument":{{"uri":"{}"}},"position":{{"line":{},"character":{}}}}}"#,
+        uri, line, character
+    )
+}
+
+fn reply(replies: &[Json], id: usize) -> &Json {
+    replies
+        .iter()
+        .find(|r| r.get("id").and_then(Json::as_usize) == Some(id))
+        .unwrap()
+}
+
+fn field<'a>(json: &'a Json, path: &[&str]) -> &'a Json {
+    path.iter().fold(json, |j, key| j.get(key).unwrap())
+}
+
+const DOC: &str = "file:///problem.gsl";
+
+fn open(uri: &str, text: &str) -> String {
+    notify(
+        "textDocument/didOpen",
+        &format!(
+            r#"{{"textDocument":{{"uri":"{}","languageId":"gsl","version":1,"text":{}}}}}"#,
+            uri,
+            Json::from(text)
+        ),
+    )
+}
+
+const TEXT: &str = "\\\\P:A,B\n/S:AB/S:AC\\\\";
+
+/// Open `text` as `DOC`, send `requests`, and shut down, as an editor does
+/// around everything it asks.
+fn with_document(text: &str, requests: &[String]) -> Vec<Json> {
+    let mut messages = vec![
+        request(1, "initialize", r#"{"capabilities":{}}"#),
+        notify("initialized", "{}"),
+        open(DOC, text),
+    ];
+    messages.extend_from_slice(requests);
+    messages.push(request(99, "shutdown", "null"));
+    messages.push(notify("exit", "null"));
+    session(&messages)
+}
+
+fn hover_text(replies: &[Json], id: usize) -> &str {
+    field(reply(replies, id), &["result", "contents", "value"])
+        .as_str()
+        .unwrap()
+}
+
+#[test]
+fn initialize_advertises_capabilities() {
+    let replies = with_document(TEXT, &[]);
+    let capabilities = field(reply(&replies, 1), &["result", "capabilities"]);
+    assert_eq!(capabilities.get("hoverProvider"), Some(&Json::Bool(true)));
+    assert_eq!(
+        capabilities.get("definitionProvider"),
+        Some(&Json::Bool(true))
+    );
+}
+
+#[test]
+fn opening_a_document_publishes_diagnostics() {
+    let replies = with_document(TEXT, &[]);
+    // The checker warns that C was never constructed.
+    let published = replies
+        .iter()
+        .find(|r| r.get("method").and_then(Json::as_str) == Some("textDocument/publishDiagnostics"))
+        .unwrap();
+    let diagnostics = field(published, &["params", "diagnostics"]);
+    let Json::Array(diagnostics) = diagnostics else {
+        panic!("diagnostics should be an array");
+    };
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(field(&diagnostics[0], &["severity"]).as_usize(), Some(2));
+    assert_eq!(
+        field(&diagnostics[0], &["range", "start", "line"]).as_usize(),
+        Some(1)
+    );
+}
+
+#[test]
+fn hover_translates_the_statement_under_the_cursor() {
+    let replies = with_document(
+        TEXT,
+        &[
+            request(2, "textDocument/hover", &at(DOC, 1, 2)),
+            // On the `\\` that opens the problem
+            request(3, "textDocument/hover", &at(DOC, 0, 0)),
+        ],
+    );
+    assert!(hover_text(&replies, 2).ends_with("Connect segment AB."));
+    assert_eq!(field(reply(&replies, 3), &["result"]), &Json::Null);
+}
+
+#[test]
+fn positions_count_utf16_units() {
+    // `∠` is three bytes but one UTF-16 unit, so `S` is at character 8.
+    let replies = with_document(
+        "\\\\P:A,B,C\n∠ABC=90/S:AB\\\\",
+        &[request(2, "textDocument/hover", &at(DOC, 1, 8))],
+    );
+    assert!(hover_text(&replies, 2).ends_with("Connect segment AB."));
+    let start = field(reply(&replies, 2), &["result", "range", "start"]);
+    assert_eq!(field(start, &["character"]).as_usize(), Some(8));
+}
+
+#[test]
+fn definition_finds_where_a_point_is_constructed() {
+    let replies = with_document(
+        TEXT,
+        &[request(2, "textDocument/definition", &at(DOC, 1, 3))],
+    );
+    // B is constructed by `P:A,B` on the first line.
+    let definition = field(reply(&replies, 2), &["result"]);
+    assert_eq!(definition.get("uri").and_then(Json::as_str), Some(DOC));
+    assert_eq!(
+        field(definition, &["range", "start", "character"]).as_usize(),
+        Some(2)
+    );
+}
+
+#[test]
+fn document_symbols_list_points_and_objects() {
+    let replies = with_document(
+        TEXT,
+        &[request(
+            2,
+            "textDocument/documentSymbol",
+            &format!(r#"{{"textDocument":{{"uri":"{}"}}}}"#, DOC),
+        )],
+    );
+    let Json::Array(symbols) = field(reply(&replies, 2), &["result"]) else {
+        panic!("symbols should be an array");
+    };
+    let names: Vec<&str> = symbols
+        .iter()
+        .map(|s| s.get("name").and_then(Json::as_str).unwrap())
+        .collect();
+    assert_eq!(names, ["A", "B", "AB", "AC"]);
+}
+
+#[test]
+fn completion_offers_codes_that_extend_the_word() {
+    let replies = with_document(
+        "\\\\P:A/_P",
+        &[request(2, "textDocument/completion", &at(DOC, 0, 8))],
+    );
+    let Json::Array(items) = field(reply(&replies, 2), &["result"]) else {
+        panic!("completions should be an array");
+    };
+    let labels: Vec<&str> = items
+        .iter()
+        .map(|s| s.get("label").and_then(Json::as_str).unwrap())
+        .collect();
+    assert!(labels.contains(&"_PY"));
+    assert!(labels.iter().all(|l| l.starts_with("_P")));
+}
+
+#[test]
+fn unknown_methods_are_errors() {
+    let replies = with_document(
+        TEXT,
+        &[request(2, "textDocument/formatting", &at(DOC, 0, 0))],
+    );
+    assert_eq!(
+        field(reply(&replies, 2), &["error", "code"]),
+        &Json::Number("-32601".to_string())
+    );
+}
+
+#[test]
+fn shutdown_replies_null() {
+    let replies = with_document(TEXT, &[]);
+    assert_eq!(field(reply(&replies, 99), &["result"]), &Json::Null);
+}
@@ -0,0 +1,164 @@
+use gslc::formatter::{format, Style};
+use gslc::latex::Latex;
+use gslc::linter::{lint, Config};
+use gslc::Translator;
+
+const FOOT: &str = "\\def FOOT:ABCX{P:X.BC|AX;BC*PD
+S:AX}
+\\\\P:A,B,C/S:AB
+FOOT:CABD
+CD?\\\\";
+
+#[test]
+fn macros_expand_with_their_points_renamed() {
+    let translation = Translator::new().translate(FOOT);
+    assert!(translation.diagnostics.is_empty());
+    assert_eq!(
+        translation.english()[2..],
+        [
+            "Construct point D on AB such that CD and AB are perpendicular.",
+            "Connect segment CD.",
+            "What is CD?"
+        ]
+    );
+    // Expanded steps point back at the call
+    assert_eq!(translation.steps[2].source, "FOOT:CABD");
+    assert_eq!(translation.steps[3].source, "FOOT:CABD");
+
+    let right = "\\def RT:ABC{P:A{0,0}/P:B{3,0}/P:C{0,4}}\n\\\\RT:XYZ/YZ?\\\\";
+    let translation = Translator::new().translate(right);
+    assert_eq!(
+        translation.english()[0],
+        "Let point X be at coordinates 0,0."
+    );
+    assert_eq!(translation.steps[3].answer, Some(5.0));
+}
+
+#[test]
+fn definitions_carry_over_to_later_problems() {
+    let input =
+        "\\def MID:ABM{P:M.AB|AM=MB}\n\\\\P:A,B/S:AB/MID:ABM\\\\\n\\\\P:C,D/S:CD/MID:CDN\\\\";
+    let problems = Translator::new().translate_problems(input);
+    assert_eq!(problems.len(), 2);
+    assert!(problems[1].translation.diagnostics.is_empty());
+    assert_eq!(
+        problems[1].translation.english()[2],
+        "Construct point N on CD such that CN=ND."
+    );
+}
+
+#[test]
+fn defined_codes_are_worded_like_built_in_ones() {
+    let input = "\\def NAG:{Nagel point}
+\\def *EQL{equilateral}
+\\def _MEN{Menelaus Theorem}
+\\\\P:A,B,C/NAG:ABC=N/ABC*EQL/_MEN\\\\";
+    let translator = Translator::new();
+    let translation = translator.translate(input);
+    assert!(translation.diagnostics.is_empty());
+    assert_eq!(
+        translation.english()[1..],
+        [
+            "Construct the Nagel point N of ABC.",
+            "ABC is equilateral.",
+            "By the Menelaus Theorem."
+        ]
+    );
+
+    let latex = Latex::new(&translator).fragment(&translation);
+    assert!(latex.contains("Construct the Nagel point $N$ of $ABC$."));
+    assert!(latex.contains("$ABC$ is equilateral."));
+
+    // A definition only applies to what follows it
+    let early = translator.translate("\\\\P:A,B,C/ABC*EQL\\\\\n\\def *EQL{equilateral}");
+    assert_eq!(
+        early.diagnostics[0].message,
+        "`EQL` is used before it is defined"
+    );
+}
+
+#[test]
+fn expansion_errors_point_at_the_definition() {
+    let translator = Translator::new();
+    let report = |input: &str| {
+        let translation = translator.translate(input);
+        let diagnostic = translation.diagnostics[0].clone();
+        let note = diagnostic
+            .notes
+            .first()
+            .map(|n| &input[n.span.start..n.span.end]);
+        (diagnostic.message.clone(), note.map(String::from))
+    };
+
+    let (message, note) = report("\\def SEG:AB{S:AB}\n\\\\P:A,B,C/SEG:ABC\\\\");
+    assert_eq!(message, "`SEG` takes 2 points (AB), but 3 were given");
+    assert_eq!(note.as_deref(), Some("\\def SEG:AB{S:AB}"));
+
+    let (message, _) = report("\\def SEG:AB{S:AB}\n\\\\P:A,B/SEG:AB=C\\\\");
+    assert_eq!(message, "macro `SEG` does not name a result");
+
+    let (message, _) = report("\\def LOOP:A{LOOP:A}\n\\\\P:A/LOOP:A\\\\");
+    assert_eq!(message, "`LOOP` expands into itself");
+
+    // What a recursive macro expanded to before giving up is not reported on
+    let translation = translator.translate("\\def F:AB{S:AB/F:AB}\n\\\\P:X,Y/F:XY\\\\");
+    assert_eq!(translation.diagnostics.len(), 1);
+    assert_eq!(
+        translation.diagnostics[0].message,
+        "`F` expands into itself"
+    );
+    assert_eq!(translation.steps.len(), 1);
+
+    // Mistakes in the body are reported at the call, with a note on the
+    // definition
+    let (message, note) = report("\\def DRAW:AB{S:AX}\n\\\\P:A,B/DRAW:AB\\\\");
+    assert!(message.contains("`X`"), "{}", message);
+    assert_eq!(note.as_deref(), Some("\\def DRAW:AB{S:AX}"));
+
+    let input = "\\def T:ABC{AB=5_YP}\n\\\\P:A,B,C/T:ABC/AC=4_YP\\\\";
+    let translation = translator.translate(input);
+    let spans: Vec<&str> = translation
+        .diagnostics
+        .iter()
+        .map(|d| &input[d.span.start..d.span.end])
+        .collect();
+    assert_eq!(spans, ["T:ABC", "_YP"]);
+    assert_eq!(translation.diagnostics[0].notes.len(), 1);
+    assert!(translation.diagnostics[1].notes.is_empty());
+}
+
+#[test]
+fn definitions_cannot_shadow() {
+    let translator = Translator::new();
+    let messages = |input: &str| -> Vec<String> {
+        translator
+            .translate(input)
+            .diagnostics
+            .iter()
+            .map(|d| d.message.clone())
+            .collect()
+    };
+    assert_eq!(messages("\\def S:AB{S:AB}"), ["`S:` is built into GSL"]);
+    assert_eq!(
+        messages("\\def M:{new midpoint}"),
+        ["`M` already means midpoint"]
+    );
+    assert_eq!(
+        messages("\\def _X{A}\n\\def _X{B}"),
+        ["`_X` is defined twice"]
+    );
+    assert_eq!(
+        messages("\\def F:AA{S:AA}"),
+        ["`F` names the parameter `A` twice"]
+    );
+}
+
+#[test]
+fn tools_see_through_macros() {
+    // The segment CD is drawn by the macro
+    assert!(lint(FOOT, &Config::default()).is_empty());
+
+    let formatted = format(FOOT, Style::Ascii).unwrap();
+    assert!(formatted.starts_with("\\def FOOT:ABCX{P:X.BC|AX;BC*PD/S:AX}\n\\\\P:A,B,C/\n"));
+    assert_eq!(format(&formatted, Style::Ascii).unwrap(), formatted);
+}
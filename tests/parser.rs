@@ -0,0 +1,145 @@
+use gslc::ast::{Expr, StmtKind};
+use gslc::lexer::{comments, tokenize, TokenKind};
+use gslc::{parse, Translator};
+
+fn statement(input: &str) -> StmtKind {
+    let mut results = parse(input);
+    assert_eq!(results.len(), 1, "{}", input);
+    results.remove(0).expect(input).kind
+}
+
+fn number(n: &str) -> Box<Expr> {
+    Box::new(Expr::Number(n.into()))
+}
+
+#[test]
+fn a_coefficient_multiplies_what_follows_it() {
+    let StmtKind::Relation(_, _, rhs) = statement("AB=2x") else {
+        panic!("expected a relation");
+    };
+    assert_eq!(
+        rhs,
+        Expr::Product(number("2"), Box::new(Expr::Variable("x".into())))
+    );
+
+    let StmtKind::Relation(_, _, rhs) = statement("AB=3\\P") else {
+        panic!("expected a relation");
+    };
+    assert_eq!(
+        rhs,
+        Expr::Product(number("3"), Box::new(Expr::Constant("\\P".into())))
+    );
+
+    let StmtKind::Relation(_, _, rhs) = statement("AB=2CD") else {
+        panic!("expected a relation");
+    };
+    assert!(matches!(&rhs, Expr::Product(l, r) if **l == *number("2") && r.to_string() == "CD"));
+    assert_eq!(rhs.measure(), "length");
+}
+
+#[test]
+fn a_radius_can_be_a_product() {
+    let translation = Translator::new().translate("\\\\P:O/C:O;2\\P\\\\");
+    assert!(!translation.has_errors(), "{:?}", translation.diagnostics);
+    assert_eq!(
+        translation.english()[1],
+        "Construct a circle with center O and radius 2 times π (pi)."
+    );
+}
+
+#[test]
+fn products_are_measured() {
+    let translation = Translator::new().translate("\\\\P:A{0,0}/P:B{3,0}/2AB?\\\\");
+    assert_eq!(translation.steps[2].answer, Some(6.0));
+}
+
+#[test]
+fn a_value_can_be_the_subject_of_casework() {
+    let StmtKind::Casework { main, cases } = statement("AB<<(AB=1;CD=2),(AB=2;CD=3)>>") else {
+        panic!("expected casework");
+    };
+    assert_eq!(main.unwrap().kind.to_string(), "AB");
+    assert_eq!(cases.unwrap().len(), 2);
+
+    let translation =
+        Translator::new().translate("\\\\P:A,B,C,D/AB<<(AB=1;CD=2),(AB=2;CD=3)>>\\\\");
+    assert!(
+        translation.diagnostics.is_empty(),
+        "{:?}",
+        translation.diagnostics
+    );
+    assert_eq!(
+        translation.english()[1],
+        "AB\nBegin casework: Case 1: if AB=1, then CD=2; Case 2: if AB=2, then CD=3"
+    );
+
+    let ssml = Translator::new().pronounce_ssml("\\\\AB<<(AB=1;CD=2)>>\\\\");
+    assert!(ssml.contains("&lt;&lt;"), "{}", ssml);
+    assert!(!ssml.contains("&lt; &lt;"), "{}", ssml);
+}
+
+#[test]
+fn an_angle_equal_to_an_angle_is_not_a_measure() {
+    let translation = Translator::new().translate("\\\\P:A,B,C,D,E,F/<ABC=<DEF/<ABC=60\\\\");
+    assert_eq!(
+        translation.english()[1..],
+        ["angle ABC = angle DEF", "Angle ABC measures 60 degrees."]
+    );
+}
+
+#[test]
+fn tokens_cover_the_text_they_were_read_from() {
+    let input = "\\\\P:A1B,C/CCO:ABC=O/∠ABC=60\\\\";
+    let tokens = tokenize(input);
+    let texts: Vec<&str> = tokens
+        .iter()
+        .map(|t| &input[t.span.start..t.span.end])
+        .collect();
+    assert_eq!(
+        texts,
+        [
+            "\\\\", "P:", "A1B", ",", "C", "/", "CCO:", "ABC", "=", "O", "/", "∠", "ABC", "=",
+            "60", "\\\\"
+        ]
+    );
+    assert_eq!(tokens[1].kind, TokenKind::Prefix("P".into()));
+    assert_eq!(tokens[2].kind, TokenKind::Name("A1B".into()));
+    assert_eq!(tokens[11].kind, TokenKind::Symbol('∠'));
+    assert_eq!(tokens[14].kind, TokenKind::Number("60".into()));
+}
+
+#[test]
+fn comments_and_line_breaks_are_spanned() {
+    let input = "\\\\P:A,B %% two points\nS:AB\\\\";
+    let comments = comments(input);
+    assert_eq!(comments.len(), 1);
+    assert_eq!(&input[comments[0].start..comments[0].end], "%% two points");
+    let newline = tokenize(input)
+        .into_iter()
+        .find(|t| t.kind == TokenKind::Newline)
+        .unwrap();
+    assert_eq!(newline.span.start, input.find('\n').unwrap());
+}
+
+#[test]
+fn parsing_resumes_after_a_bad_statement() {
+    let input = "\\\\P:A,B/S:/AB=/J:ABC/T:\\\\";
+    let results = parse(input);
+    assert_eq!(results.len(), 5);
+    let ok: Vec<bool> = results.iter().map(Result::is_ok).collect();
+    assert_eq!(ok, [true, false, false, true, false]);
+    // Each error covers its own statement
+    let statements: Vec<&str> = results
+        .iter()
+        .filter_map(|r| r.as_ref().err())
+        .map(|e| &input[e.statement.start..e.statement.end])
+        .collect();
+    assert_eq!(statements, ["S:", "AB=", "T:"]);
+}
+
+#[test]
+fn an_unexpected_token_is_pointed_at() {
+    let input = "\\\\P:A,B/AB=5)\\\\";
+    let error = parse(input).remove(1).unwrap_err();
+    assert_eq!(&input[error.span.start..error.span.end], ")");
+}
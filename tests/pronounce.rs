@@ -0,0 +1,80 @@
+use gslc::Translator;
+
+fn pron(input: &str) -> String {
+    Translator::new().pronounce(input, false)
+}
+
+#[test]
+fn codes_and_names_are_said_as_units() {
+    assert_eq!(pron("CCO:ABC=O"), "c-c-o kuh a b c eh o");
+    assert_eq!(pron("9C:ABC"), "9-c kuh a b c");
+    assert_eq!(pron("AB;CD*PD"), "a b suh c d xing p-d");
+    assert_eq!(pron("AB;CD*P"), "a b suh c d xing p");
+    assert_eq!(pron("J:ABC*RT"), "j kuh a b c xing r-t");
+    assert_eq!(pron("_PY"), "by p-y");
+    assert_eq!(pron("P:A1,B"), "p kuh a-1 muh b");
+}
+
+#[test]
+fn prefixes_constants_and_commands_have_their_own_words() {
+    assert_eq!(pron("aAB=5"), "arc a b eh 5");
+    assert_eq!(pron("P:X=lABxwCD"), "p kuh x eh line a b ix ray c d");
+    assert_eq!(pron("C:O;r"), "c kuh o suh var r");
+    assert_eq!(pron("AB=\\P/2"), "a b eh pi mn 2");
+    assert_eq!(pron("[ABC]=π"), "area a b c eh pi");
+    assert_eq!(pron("\\p:AB=BC"), "uh-p kuh a b eh b c");
+    assert_eq!(pron("AB∥CD"), "a b pall c d");
+    assert_eq!(pron("∠ABC=90"), pron("<ABC=90"));
+}
+
+#[test]
+fn steps_are_numbered_by_statement() {
+    let translator = Translator::new();
+    assert_eq!(
+        translator.pronounce("\\\\P:A,B\nS:AB/AB=\\T/2\\\\", true),
+        "1. p kuh a muh b\n2. s kuh a b\n3. a b eh tau mn 2"
+    );
+    assert_eq!(translator.pronounce("P:A", true), "1. p kuh a");
+}
+
+#[test]
+fn problems_keep_their_wrappers() {
+    assert_eq!(
+        pron("\\\\P:A/S:AB\\\\\n%% a comment\n\\\\P:C\\\\"),
+        "uh p kuh a mn s kuh a b uh uh p kuh c uh"
+    );
+}
+
+#[test]
+fn statements_that_do_not_parse_are_spelled_out() {
+    assert_eq!(pron("\\\\P:/S:AB\\\\"), "uh p kuh mn s kuh a b uh");
+}
+
+#[test]
+fn ssml_marks_up_syllables_names_and_statements() {
+    let ssml = Translator::new().pronounce_ssml("\\\\P:A/CCO:ABC/AB<BC\\\\");
+    assert!(ssml.starts_with("<speak "), "{}", ssml);
+    assert!(ssml.ends_with("</speak>"), "{}", ssml);
+    assert!(
+        ssml.contains("<phoneme alphabet=\"ipa\" ph=\"kʌ\">kuh</phoneme>"),
+        "{}",
+        ssml
+    );
+    assert!(ssml.contains("<say-as interpret-as=\"characters\">CCO</say-as>"));
+    assert_eq!(ssml.matches("<break strength=\"medium\"/>").count(), 2);
+    // Symbols said as written are escaped
+    assert!(ssml.contains("</say-as> &lt; <say-as"), "{}", ssml);
+}
+
+#[test]
+fn ipa_follows_the_same_layout() {
+    let translator = Translator::new();
+    assert_eq!(
+        translator.pronounce_ipa("\\\\P:A/S:AB\\\\", false),
+        "ʌ piː kʌ eɪ mən ɛs kʌ eɪ biː ʌ"
+    );
+    assert_eq!(
+        translator.pronounce_ipa("\\\\CCO:ABC/\\th/[ABC]=\\P\\\\", true),
+        "1. siː siː oʊ kʌ eɪ biː siː\n2. ʌ tiː eɪtʃ\n3. ˈɛɹiə eɪ biː siː ɛ paɪ"
+    );
+}
@@ -0,0 +1,106 @@
+use gslc::{Translation, Translator};
+
+fn translate(input: &str) -> Translation {
+    Translator::new().translate(input)
+}
+
+fn errors(translation: &Translation) -> Vec<&str> {
+    translation
+        .diagnostics
+        .iter()
+        .filter(|d| d.is_error())
+        .map(|d| d.message.as_str())
+        .collect()
+}
+
+#[test]
+fn nested_proofs_are_one_level_deeper() {
+    let translation =
+        translate("\\\\P:A,B,C/\\p:AB=BC/∵/AB=5/\\pC:AB=4/AB=5/↯/∴/AB!=4/\\q/S:AB\\\\");
+    assert!(errors(&translation).is_empty(), "{:?}", translation);
+    let depths: Vec<usize> = translation.steps.iter().map(|s| s.depth).collect();
+    assert_eq!(depths, [0, 0, 1, 1, 1, 2, 1, 1, 1, 0, 0]);
+    let closes: Vec<Option<usize>> = translation.steps.iter().map(|s| s.closes).collect();
+    assert_eq!(closes[6], Some(4));
+    assert_eq!(closes[9], Some(1));
+}
+
+#[test]
+fn closing_markers_say_what_they_prove() {
+    let translation = translate("\\\\P:A,B,C/\\p:AB=BC/\\pC:AB;CD*P/↯/□\\\\");
+    assert_eq!(
+        translation.english()[3..],
+        [
+            "Achieving a contradiction, which proves that AB and CD are parallel.",
+            "And that is what was to be shown, which proves that AB = BC.",
+        ]
+    );
+}
+
+#[test]
+fn unbalanced_markers_are_errors() {
+    assert_eq!(
+        errors(&translate("\\\\P:A,B/\\q\\\\")),
+        ["there is no open proof for `\\q` to close"]
+    );
+    assert_eq!(
+        errors(&translate("\\\\P:A,B/\\p:AB=5/\\p:AB=5/\\q\\\\")),
+        ["this proof is never closed"]
+    );
+    let translation = translate("\\\\P:A,B/\\pC:AB=5/AB=4/\\q\\\\");
+    assert_eq!(
+        errors(&translation),
+        ["a proof by contradiction must reach `↯` to close"]
+    );
+    assert_eq!(translation.diagnostics[0].notes.len(), 1);
+    assert_eq!(
+        errors(&translate("\\\\P:A,B/\\p:AB=5/↯\\\\")),
+        ["`↯` closes a proof by contradiction, but this proof is direct"]
+    );
+}
+
+#[test]
+fn reasons_need_a_statement_after_them() {
+    let translation = translate("\\\\P:A,B/\\p:AB=5/∴/\\q\\\\");
+    assert!(!translation.has_errors());
+    assert_eq!(
+        translation.diagnostics[0].message,
+        "`∴` is not followed by the statement it introduces"
+    );
+}
+
+#[test]
+fn text_output_indents_proofs() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_gslc"))
+        .arg("\\\\P:A,B/\\p:AB=5/∵/AB=5/\\q\\\\")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim_end(),
+        "1. Construct points A, B.
+2. We will prove: AB = 5
+   3. Because
+   4. AB = 5
+5. And that is what was to be shown, which proves that AB = 5."
+    );
+}
+
+#[test]
+fn reverse_reads_indented_proofs_back() {
+    let translator = Translator::new();
+    let problem = "\\\\P:A,B/\\p:AB=5/\\pC:AB=4/↯/\\th/AB=5/\\q\\\\";
+    let english: Vec<String> = translator
+        .translate(problem)
+        .steps
+        .iter()
+        .enumerate()
+        .map(|(i, step)| format!("{}{}. {}", "   ".repeat(step.depth), i + 1, step.english))
+        .collect();
+    let reversal = translator.reverse(&english.join("\n"));
+    assert!(reversal.diagnostics.is_empty(), "{:?}", reversal);
+    assert_eq!(
+        reversal.program(),
+        "\\\\P:A,B/\\p:AB=5/\\pC:AB=4/\\qC/\\th/AB=5/\\q\\\\"
+    );
+}
@@ -0,0 +1,118 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Feed `input` to `gslc repl`, keeping its history under `data`.
+fn repl(input: &str, data: &std::path::Path) -> (String, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_gslc"))
+        .arg("repl")
+        .env("XDG_DATA_HOME", data)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    (
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+fn scratch(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("gslc-repl-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn keeps_construction_state_between_lines() {
+    let dir = scratch("state");
+    let (out, _) = repl("P:A{0,0}\nP:B{3,4}\nS:AB\nAB?\n", &dir);
+    assert_eq!(
+        out,
+        "1. Let point A be at coordinates 0,0.\n\
+         2. Let point B be at coordinates 3,4.\n\
+         3. Connect segment AB.\n\
+         4. What is AB? → 5\n"
+    );
+    let history = std::fs::read_to_string(dir.join("gslc").join("history")).unwrap();
+    assert_eq!(history, "P:A{0,0}\nP:B{3,4}\nS:AB\nAB?\n");
+}
+
+#[test]
+fn undo_list_save_and_clear() {
+    let dir = scratch("commands");
+    let file = dir.join("saved.gsl");
+    let one = dir.join("one.gsl");
+    let input = format!(
+        "P:A,B\nS:AB\nL:AB\n:undo\n:list\n:save {}\n:pron\n:clear\n:list\nP:C\n:save {}\n",
+        file.display(),
+        one.display()
+    );
+    let (out, _) = repl(&input, &dir);
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines[3], "Removed `L:AB`.");
+    assert_eq!(
+        lines[4..6],
+        ["1. Construct points A, B.", "2. Connect segment AB."]
+    );
+    assert!(
+        lines[6].starts_with("Saved 2 statements to "),
+        "{}",
+        lines[6]
+    );
+    assert_eq!(lines[7], "uh p kuh a muh b mn s kuh a b uh");
+    assert_eq!(lines[8..10], ["Cleared.", "No statements yet."]);
+    assert!(
+        lines[11].starts_with("Saved 1 statement to "),
+        "{}",
+        lines[11]
+    );
+    assert_eq!(
+        std::fs::read_to_string(&file).unwrap(),
+        "\\\\P:A,B/S:AB\\\\\n"
+    );
+}
+
+#[test]
+fn rejects_lines_with_errors() {
+    let dir = scratch("errors");
+    let (out, err) = repl("P:A\nS:A B C\n/\n:list\n", &dir);
+    assert!(err.contains("error"));
+    assert!(err.contains("Error: enter one statement per line"));
+    assert_eq!(out, "1. Construct point A.\n1. Construct point A.\n");
+}
+
+#[test]
+fn definitions_apply_to_later_lines() {
+    let dir = scratch("definitions");
+    let (out, err) = repl("\\def SEGS:ABC{S:AB/S:BC}\nP:A,B,C\nSEGS:ABC\n", &dir);
+    assert_eq!(err, "");
+    assert_eq!(
+        out,
+        "Defined.\n\
+         1. Construct points A, B, C.\n\
+         2. Connect segment AB.\n\
+         3. Connect segment BC.\n"
+    );
+}
+
+#[test]
+fn proofs_can_be_entered_a_line_at_a_time() {
+    let dir = scratch("proof");
+    let (out, err) = repl("P:A,B\n\\p:AB=5\nAB=5\n\\q\n", &dir);
+    assert_eq!(err, "");
+    assert_eq!(
+        out,
+        "1. Construct points A, B.\n\
+         2. We will prove: AB = 5\n   \
+         3. AB = 5\n\
+         4. And that is what was to be shown, which proves that AB = 5.\n"
+    );
+}
@@ -0,0 +1,158 @@
+use gslc::{parse, Translator};
+
+/// One statement of every form the translator words differently.
+const GRAMMAR: &[&str] = &[
+    "P:A",
+    "P:A,B,C",
+    "P:A{1,2}",
+    "P:C.AB",
+    "P:C.cO",
+    "P:D.AC|R:3;AD=ADE,[ADE]=20",
+    "P:D.AB|ABC*+,ABC*-",
+    "P:D.AB|ABC*EQ,AB;CD*P",
+    "P:D.AB|J:ABC,DEF*R*CV",
+    "P:D.AB|AB=5",
+    "P:C..ABC",
+    "P:X=ABxCD",
+    "P:X=lABxwCDxcO",
+    "P:X=aABxqOCDx△ABC",
+    "S:AB",
+    "S:AB,CD",
+    "L:AB",
+    "L:AB,CD",
+    "W:AB",
+    "W:AB,CD",
+    "J:ABC",
+    "J:ABC,DEF",
+    "J:ABCD*R",
+    "J:ABC*RT*IS",
+    "C:A;B;C",
+    "C:O;5",
+    "C:O;r",
+    "C:O;\\P",
+    "C:O;A",
+    "C:A",
+    "C:=ω",
+    "R:3;AB=ABC",
+    "R:4;AB=ABCD",
+    "R:5;AB=ABCDE",
+    "R:6;AB=ABCDEF",
+    "R:7;AB=ABCDEFG",
+    "R:8;AB=ABCDEFGH",
+    "G:{y=x^2}",
+    "CCO:ABC",
+    "CCO:ABC=O",
+    "PB:AB",
+    "AB:ABC",
+    "EAB:ABC",
+    "9C:ABC",
+    "PD:P;AB",
+    "PL:P;AB",
+    "_PY",
+    "_SAS",
+    "_CV",
+    "AB=BC",
+    "AB!=5",
+    "AB<5",
+    "AB<=5",
+    "AB>5",
+    "AB>=5",
+    "x=5",
+    "AB=BC+CD",
+    "AB=\\T/2",
+    "[ABC]=20",
+    "[△ABC]=\\G",
+    "(ABC)=12",
+    "<ABC=90",
+    "<aAB=60",
+    "aAB=5",
+    "qOAB=10",
+    "AB?",
+    "x?",
+    "[ABC]?",
+    "(ABC)?",
+    "<ABC?",
+    "<aAB?",
+    "aAB?",
+    "qOAB?",
+    "AB=BC\\?",
+    "ABC*EQ",
+    "ABC*EQ?",
+    "AB;CD*P",
+    "AB;CD*PD?",
+    "ABC;DEF*CG",
+    "A;B;C*S",
+    "ABC*+",
+    "ABC*-",
+    "\\p:AB=BC",
+    "\\p:[ABC]=20",
+    "\\pC:AB=BC",
+    "\\q",
+    "\\qC",
+    "\\bc",
+    "\\th",
+    "AC=5_PY",
+    "P:A,B_AA",
+    "ABC;DEF*CG_SAC",
+    "[ABC]=6_HF",
+    "AB=BC&&CD=DE",
+    "AB=BC||CD=DE",
+    "AB=BC=>CD=DE",
+    "|AAB=BC",
+    "|EAB=BC",
+    "AB=BC<<(x=1;AB=1),(x=2;AB=2)>>",
+    "AB=BC<<",
+    ">>",
+];
+
+fn canonical(statement: &str) -> String {
+    let mut parsed = parse(&format!("\\\\{}\\\\", statement));
+    assert_eq!(parsed.len(), 1, "{}", statement);
+    parsed.remove(0).unwrap().to_string()
+}
+
+#[test]
+fn reverse_undoes_translate_for_the_whole_grammar() {
+    let translator = Translator::new();
+    let mut failures = Vec::new();
+    for statement in GRAMMAR {
+        let translation = translator.translate(&format!("\\\\{}\\\\", statement));
+        let english = translation.english().join("\n");
+        let reversal = translator.reverse(&english);
+        if !reversal.diagnostics.is_empty() || reversal.statements != [canonical(statement)] {
+            failures.push(format!("{} → {:?} → {:?}", statement, english, reversal));
+        }
+    }
+    assert!(failures.is_empty(), "{:#?}", failures);
+}
+
+#[test]
+fn symbols_come_back_as_codes() {
+    let translator = Translator::new();
+    let translation = translator.translate("\\\\AB∥CD/∠ABC=90/[ABC]=π\\\\");
+    let reversal = translator.reverse(&translation.english().join("\n"));
+    assert_eq!(reversal.statements, ["AB;CD*P", "<ABC=90", "[ABC]=\\P"]);
+}
+
+#[test]
+fn reads_numbered_output_with_answers() {
+    let english = "1. Let point A be at coordinates 0,0.
+2. Let point B be at coordinates 3,4.
+
+3. What is AB? → 5
+";
+    let reversal = Translator::new().reverse(english);
+    assert!(reversal.diagnostics.is_empty());
+    assert_eq!(reversal.program(), "\\\\P:A{0,0}/P:B{3,4}/AB?\\\\");
+}
+
+#[test]
+fn unreadable_sentences_report_their_line() {
+    let english = "Construct point A.\nDraw a nice picture.\nConnect segment AB.";
+    let reversal = Translator::new().reverse(english);
+    assert_eq!(reversal.statements, ["P:A", "S:AB"]);
+    assert_eq!(reversal.diagnostics.len(), 1);
+    let rendered =
+        gslc::diagnostic::SourceFile::new("problem.txt", english).render(&reversal.diagnostics[0]);
+    assert!(rendered.contains("--> problem.txt:2:1"), "{}", rendered);
+}
@@ -0,0 +1,45 @@
+use gslc::Translator;
+
+fn draw(input: &str) -> String {
+    gslc::svg::translation(&Translator::new().translate(input))
+}
+
+#[test]
+fn draws_a_standalone_document() {
+    let svg = draw("\\\\P:A,B,C/J:ABC\\\\");
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    assert_eq!(svg.matches("class=\"point\"").count(), 3);
+    assert_eq!(svg.matches("class=\"label\"").count(), 3);
+    assert!(svg.contains("<polygon class=\"figure\""));
+}
+
+#[test]
+fn highlights_areas_angles_arcs_and_sectors() {
+    let svg = draw("\\\\P:O{0,0}/C:O;5/P:A{5,0}/P:B{0,5}/J:OAB/[OAB]?/<AOB?/<aAB?/[qOAB]?\\\\");
+    assert!(svg.contains("<circle class=\"figure\""));
+    assert!(svg.contains("<polygon class=\"area\""));
+    assert!(svg.contains("<path class=\"sector\""));
+    assert!(svg.contains("<path class=\"arc\""));
+    // A right angle gets a square corner rather than an arc.
+    assert!(svg
+        .lines()
+        .any(|l| l.contains("class=\"angle\"") && l.contains(" L ")));
+}
+
+#[test]
+fn clips_lines_to_the_viewport() {
+    let svg = draw("\\\\P:A{0,0}/P:B{1,0}/L:AB\\\\");
+    let width: f64 = svg
+        .split("width=\"")
+        .nth(1)
+        .and_then(|s| s.split('"').next())
+        .and_then(|s| s.parse().ok())
+        .unwrap();
+    let line = svg
+        .lines()
+        .find(|l| l.contains("<line class=\"figure\""))
+        .unwrap();
+    assert!(line.contains("x1=\"0.00\""));
+    assert!(line.contains(&format!("x2=\"{:.2}\"", width)));
+}
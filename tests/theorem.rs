@@ -0,0 +1,97 @@
+use gslc::latex::Latex;
+use gslc::{json, Translator};
+use std::process::Command;
+
+#[test]
+fn citations_justify_the_step_before_them() {
+    let translator = Translator::new();
+    let translation = translator.translate("\\\\P:A,B,C/AC=5_PY/∴ ABC;DEF*CG _SAC/_PP\\\\");
+    assert!(!translation.has_errors(), "{:?}", translation.diagnostics);
+    assert_eq!(
+        translation.english()[1..],
+        [
+            "AC = 5 by the Pythagorean Theorem",
+            "Therefore",
+            "ABC and DEF are congruent by the SAS Congruence.",
+            "By the Power of a Point Theorem.",
+        ]
+    );
+    assert_eq!(
+        translation.steps[1].statement.theorem.as_deref(),
+        Some("_PY")
+    );
+    assert_eq!(translation.steps[1].source, "AC=5_PY");
+    assert_eq!(translation.steps[1].statement.to_string(), "AC=5_PY");
+
+    let step = json::step(&translation.steps[1]).to_string();
+    assert!(step.contains("\"theorem\": \"_PY\""), "{}", step);
+}
+
+#[test]
+fn possessive_names_are_cited_without_the() {
+    let translator = Translator::new();
+    let translation = translator.translate("\\\\P:A,B,C/[ABC]=6_HF/_ML\\\\");
+    assert_eq!(
+        translation.english()[1..],
+        [
+            "Let the area of ABC be 6 by Heron's Formula.",
+            "By Menelaus' Theorem.",
+        ]
+    );
+    let latex = Latex::new(&translator).document(&translation);
+    assert!(latex.contains("by Heron's Formula."), "{}", latex);
+    assert!(latex.contains("By Menelaus' Theorem."), "{}", latex);
+}
+
+#[test]
+fn unknown_codes_suggest_the_nearest() {
+    let translator = Translator::new();
+    let input = "\\\\P:A,B,C/AC=5_YP/_SSAS/J:ABC*RTT/_Q\\\\";
+    let translation = translator.translate(input);
+    let hints: Vec<(&str, Option<&str>)> = translation
+        .diagnostics
+        .iter()
+        .filter(|d| d.is_error())
+        .map(|d| (&input[d.span.start..d.span.end], d.hint.as_deref()))
+        .collect();
+    assert_eq!(hints[0], ("_YP", Some("did you mean `_PY`?")));
+    assert_eq!(hints[1], ("_SSAS", Some("did you mean `_SAS`?")));
+    assert_eq!(hints[2].1, Some("did you mean `RT`?"));
+    assert!(hints[3].1.unwrap().starts_with("expected one of `_9C`"));
+}
+
+#[test]
+fn theorems_have_statements_and_hypotheses() {
+    let translator = Translator::new();
+    let theorems = translator.theorems();
+    assert!(theorems
+        .iter()
+        .all(|t| t.statement.is_some() && !t.hypotheses.is_empty()));
+    let theorem = translator.theorem("py").unwrap();
+    assert_eq!(theorem.code, "_PY");
+    assert_eq!(theorem.name, "Pythagorean Theorem");
+    assert_eq!(
+        translator.theorem("_PX").unwrap_err().hint.as_deref(),
+        Some("did you mean `_PC`?")
+    );
+}
+
+#[test]
+fn the_cli_states_a_theorem() {
+    let gslc = env!("CARGO_BIN_EXE_gslc");
+    let output = Command::new(gslc)
+        .args(["theorem", "_PY"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let text = String::from_utf8_lossy(&output.stdout);
+    assert!(text.starts_with("_PY: Pythagorean Theorem\n"), "{}", text);
+    assert!(text.contains("Hypotheses:\n  - the triangle has a right angle"));
+
+    let output = Command::new(gslc)
+        .args(["theorem", "_YP"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("did you mean `_PY`?"));
+}
@@ -0,0 +1,67 @@
+use gslc::Translator;
+
+fn picture(input: &str) -> String {
+    gslc::tikz::translation(&Translator::new().translate(input))
+}
+
+#[test]
+fn defines_points_at_solved_coordinates() {
+    let tikz = picture("\\\\P:A{0,0}/P:B{4,0}/P:C{0,3}/J:ABC/M:BC=M\\\\");
+    assert!(tikz.starts_with("\\begin{tikzpicture}\n"));
+    assert!(tikz.trim_end().ends_with("\\end{tikzpicture}"));
+    assert!(tikz.contains("\\tkzDefPoint(0,3){C}"));
+    assert!(tikz.contains("\\tkzDefMidPoint(B,C) \\tkzGetPoint{M}"));
+    assert!(tikz.contains("\\tkzDrawPolygon(A,B,C)"));
+    assert!(tikz.contains("\\tkzDrawPoints(A,B,C,M)"));
+    assert!(tikz.contains("\\tkzLabelPoint[below left](A){$A$}"));
+}
+
+#[test]
+fn draws_circles_through_a_point_on_them() {
+    let tikz = picture("\\\\P:O{0,0}/P:A{3,0}/P:Q{0,1}/C:O;A/C:Q;5\\\\");
+    assert!(tikz.contains("\\tkzDrawCircle(O,A)"));
+    assert!(tikz.contains("\\tkzDefPoint(5,1){aux1}"));
+    assert!(tikz.contains("\\tkzDrawCircle(Q,aux1)"));
+}
+
+#[test]
+fn marks_angles_counterclockwise() {
+    let tikz = picture("\\\\P:A{4,0}/P:B{0,0}/P:C{0,3}/P:D{-1,-1}/<ABC=90/<CBD?\\\\");
+    assert!(tikz.contains("\\tkzMarkRightAngle(A,B,C)"));
+    assert!(tikz.contains("\\tkzMarkAngle[size=0.5](C,B,D)"));
+}
+
+#[test]
+fn derived_points_are_constructed_with_tkz_macros() {
+    let tikz = picture(
+        "\\\\P:A{0,0}/P:B{6,0}/P:C{1,4}/J:ABC/CCO:ABC=O/CC:ABC/M:BC=M/CT:ABC=G/PD:C;AB=H/S:AM/P:X=AMxCH\\\\",
+    );
+    for line in [
+        "\\tkzDefTriangleCenter[circum](A,B,C) \\tkzGetPoint{O}",
+        "\\tkzDefMidPoint(B,C) \\tkzGetPoint{M}",
+        "\\tkzDefTriangleCenter[centroid](A,B,C) \\tkzGetPoint{G}",
+        "\\tkzDefPointBy[projection=onto A--B](C) \\tkzGetPoint{H}",
+        "\\tkzInterLL(A,M)(C,H) \\tkzGetPoint{X}",
+        "\\tkzDrawCircle(O,A)",
+    ] {
+        assert!(tikz.contains(line), "{}\n{}", line, tikz);
+    }
+    assert!(!tikz.contains("){O}"), "{}", tikz);
+}
+
+#[test]
+fn derived_circles_are_constructed_on_their_triangle() {
+    let tikz = picture("\\\\P:A{0,0}/P:B{6,0}/P:C{1,4}/IC:ABC\\\\");
+    assert!(tikz.contains("\\tkzDefTriangleCenter[in](A,B,C) \\tkzGetPoint{aux1}"));
+    assert!(tikz.contains("\\tkzDefPointBy[projection=onto A--B](aux1) \\tkzGetPoint{aux2}"));
+    assert!(tikz.contains("\\tkzDrawCircle(aux1,aux2)"));
+}
+
+#[test]
+fn bare_segments_lines_and_rays_are_drawn() {
+    let tikz = picture("\\\\P:A{0,0}/P:B{4,0}/P:C{0,3}/S:AB/L:BC/W:CA\\\\");
+    assert!(tikz.contains("\\tkzDrawSegment(A,B)"));
+    assert!(tikz.contains("\\tkzDrawLine(B,C)"));
+    assert!(tikz.contains("\\tkzDrawLine[add=0 and 2](C,A)"));
+    assert!(!tikz.contains("\\tkzDrawPolygon"));
+}
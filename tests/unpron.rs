@@ -0,0 +1,123 @@
+use gslc::{parse, Translator};
+use std::process::Command;
+
+/// Problems covering each syllable and every bracket that is not pronounced.
+const PROBLEMS: &[&str] = &[
+    "\\\\P:A/S:AB\\\\",
+    "\\\\P:A,B,C/J:ABC\\\\",
+    "\\\\P:A{1,2}/P:B{-3,4.5}\\\\",
+    "\\\\P:C.AB/P:C.cO\\\\",
+    "\\\\P:C..ABC\\\\",
+    "\\\\P:D.AC|R:3;AD=ADE,[ADE]=20\\\\",
+    "\\\\P:D.AB|ABC*+,ABC*-\\\\",
+    "\\\\P:X=ABxCD/P:X=lABxwCDxcO\\\\",
+    "\\\\P:X=aABxqOCDx△ABC\\\\",
+    "\\\\C:O;r/C:O;AB/C:O;\\P\\\\",
+    "\\\\G:{y=x^2}\\\\",
+    "\\\\AB=BC+CD/AB=\\T/2\\\\",
+    "\\\\[ABC]=20/[△ABC]=\\G/(ABC)=12\\\\",
+    "\\\\<ABC=90/<aAB=60/aAB=5/qOAB=10\\\\",
+    "\\\\AB?/x?/[ABC]?/(ABC)?/<aAB?\\\\",
+    "\\\\AB=BC\\?\\\\",
+    "\\\\AB;CD*P/AB;CD*PD?/ABC;DEF*CG/A;B;C*S\\\\",
+    "\\\\AB∥CD/AB⊥CD/∠ABC=90/ABC~DEF/ABC≅DEF\\\\",
+    "\\\\\\p:AB=BC/\\bc/AB=5/\\th/AB!=4/\\q\\\\",
+    "\\\\\\pC:AB=BC/\\qC\\\\",
+    "\\\\AB=BC&&CD=DE/AB=BC||CD=DE/AB=BC=>CD=DE\\\\",
+    "\\\\|AAB=BC/|EAB=BC/_PY\\\\",
+    "\\\\AC=5_PY/ABC;DEF*CG_SAC/∴/AB=BC_9C\\\\",
+    "\\\\AB=BC<<(x=1;AB=1),(x=2;AB=2)>>\\\\",
+    "\\\\AB=BC<<\\\\\\\\\\>>\\\\",
+    "\\\\P:A\\\\\\\\P:B\\\\",
+    "\\\\\\def RT:ABC{P:A{0,0}/P:B{4,0}/P:C{0,3}}/RT:PQR\\\\",
+];
+
+/// Each statement in its canonical spelling, since pronunciation does not
+/// say which of `<` and `∠` was written.
+fn canonical(gsl: &str) -> Vec<String> {
+    parse(gsl)
+        .into_iter()
+        .map(|stmt| stmt.map_or_else(|e| e.message, |stmt| stmt.to_string()))
+        .collect()
+}
+
+#[test]
+fn unpronounce_undoes_pronounce() {
+    let translator = Translator::new();
+    let mut failures = Vec::new();
+    for problem in PROBLEMS {
+        assert!(parse(problem).iter().all(Result::is_ok), "{}", problem);
+        let spoken = translator.pronounce(problem, false);
+        let dictation = translator.unpronounce(&spoken);
+        if !dictation.diagnostics.is_empty() || canonical(&dictation.gsl) != canonical(problem) {
+            failures.push(format!("{} → {:?} → {:?}", problem, spoken, dictation));
+        }
+    }
+    assert!(failures.is_empty(), "{:#?}", failures);
+}
+
+#[test]
+fn letters_name_points_and_arc_is_the_prefix() {
+    let translator = Translator::new();
+    assert_eq!(
+        translator.unpronounce("uh arc a b eh 5 uh").gsl,
+        "\\\\aAB=5\\\\"
+    );
+    assert_eq!(translator.unpronounce("uh p kuh a uh").gsl, "\\\\P:A\\\\");
+    // Spoken words may be capitalised and spaced however a dictation tool likes
+    assert_eq!(
+        translator.unpronounce("Uh  S kuh\tA B  duh-duh C UH").gsl,
+        "\\\\S:AB..C\\\\"
+    );
+    assert_eq!(
+        translator.unpronounce("uh a b eh duh duh uh").gsl,
+        "\\\\AB=..\\\\"
+    );
+}
+
+#[test]
+fn reads_numbered_steps() {
+    let translator = Translator::new();
+    let problem = "\\\\P:A,B/S:AB/[ABC]?\\\\";
+    let spoken = translator.pronounce(problem, true);
+    assert!(spoken.contains('\n'), "{}", spoken);
+    assert_eq!(translator.unpronounce(&spoken).gsl, problem);
+    assert_eq!(
+        translator.unpronounce("p kuh a mn s kuh a b").gsl,
+        "\\\\P:A/S:AB\\\\"
+    );
+}
+
+#[test]
+fn words_that_are_not_syllables_are_reported() {
+    let spoken = "uh p kuh a\nmn hello uh";
+    let dictation = Translator::new().unpronounce(spoken);
+    assert!(dictation.has_errors());
+    assert_eq!(dictation.diagnostics.len(), 1);
+    let diagnostic = &dictation.diagnostics[0];
+    assert_eq!(diagnostic.message, "`hello` is not a spoken GSL word");
+    assert_eq!(&spoken[diagnostic.span.start..diagnostic.span.end], "hello");
+}
+
+#[test]
+fn the_cli_prints_shorthand_that_translates() {
+    let gslc = env!("CARGO_BIN_EXE_gslc");
+    let output = Command::new(gslc)
+        .args(["--unpron", "uh p kuh a muh b mn s kuh a b uh"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let gsl = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(gsl.trim(), "\\\\P:A,B/S:AB\\\\");
+    assert_eq!(
+        Translator::new().translate(gsl.trim()).english(),
+        ["Construct points A, B.", "Connect segment AB."]
+    );
+
+    let output = Command::new(gslc)
+        .args(["--unpron", "uh p kuh yellow uh"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("<input>:1:10"));
+}